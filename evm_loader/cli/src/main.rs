@@ -9,8 +9,10 @@ mod program_options;
 
 use neon_lib::{
     commands::{
-        cancel_trx, collect_treasury, emulate, get_balance, get_config, get_contract, get_holder,
-        get_neon_elf, get_storage_at, init_environment, trace,
+        account_block_add, account_reclaim, cancel_trx, collect_treasury, emulate, get_balance,
+        get_config, get_contract, get_holder, get_neon_elf, get_storage_at, init_environment,
+        operator_registry::{self, OperatorRegistryAction},
+        trace,
     },
     types::{BalanceAddress, EmulateRequest},
     Config,
@@ -22,7 +24,7 @@ use std::io::Read;
 use ethnum::U256;
 use log::debug;
 use serde_json::json;
-use solana_clap_utils::input_parsers::{pubkey_of, value_of};
+use solana_clap_utils::input_parsers::{pubkey_of, pubkeys_of, value_of};
 use tokio::time::Instant;
 
 use crate::build_info::get_build_info;
@@ -31,6 +33,7 @@ use neon_lib::errors::NeonError;
 use neon_lib::rpc::{CallDbClient, RpcEnum};
 use neon_lib::types::TracerDb;
 use solana_clap_utils::keypair::signer_from_path;
+use solana_sdk::instruction::AccountMeta;
 use solana_sdk::signature::Signer;
 
 type NeonCliResult = Result<serde_json::Value, NeonError>;
@@ -98,6 +101,61 @@ async fn run(options: &ArgMatches<'_>) -> NeonCliResult {
                 .await
                 .map(|result| json!(result))
         }
+        ("account-block-add", Some(params)) => {
+            let rpc_client = config.build_solana_rpc_client();
+            let signer = build_signer(config)?;
+
+            let storage_account =
+                pubkey_of(params, "storage_account").expect("storage_account parse error");
+
+            let writable = pubkeys_of(params, "accounts").unwrap_or_default();
+            let readonly = pubkeys_of(params, "readonly").unwrap_or_default();
+            let accounts: Vec<AccountMeta> = writable
+                .into_iter()
+                .map(|key| AccountMeta::new(key, false))
+                .chain(
+                    readonly
+                        .into_iter()
+                        .map(|key| AccountMeta::new_readonly(key, false)),
+                )
+                .collect();
+
+            account_block_add::execute(
+                &rpc_client,
+                &*signer,
+                config.evm_loader,
+                &storage_account,
+                &accounts,
+            )
+            .await
+            .map(|result| json!(result))
+        }
+        ("account-reclaim", Some(params)) => {
+            let rpc_client = config.build_solana_rpc_client();
+            let signer = build_signer(config)?;
+
+            let recipient = pubkey_of(params, "recipient");
+            let dry_run = params.is_present("dry-run");
+
+            account_reclaim::execute(&rpc_client, &*signer, config.evm_loader, recipient, dry_run)
+                .await
+                .map(|result| json!(result))
+        }
+        (command @ ("operator-add" | "operator-remove" | "operator-suspend"), Some(params)) => {
+            let rpc_client = config.build_solana_rpc_client();
+            let signer = build_signer(config)?;
+
+            let action = match command {
+                "operator-add" => OperatorRegistryAction::Add,
+                "operator-remove" => OperatorRegistryAction::Remove,
+                _ => OperatorRegistryAction::Suspend,
+            };
+            let operator = pubkey_of(params, "operator").expect("operator parse error");
+
+            operator_registry::execute(&rpc_client, &*signer, config.evm_loader, action, operator)
+                .await
+                .map(|result| json!(result))
+        }
         ("neon-elf-params", Some(params)) => {
             let rpc = build_rpc(options, config).await?;
 
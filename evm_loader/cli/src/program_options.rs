@@ -217,6 +217,95 @@ pub fn parse<'a>() -> ArgMatches<'a> {
                         .help("storage account for transaction"),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("account-block-add")
+                .about("Add accounts to NEON transaction in progress")
+                .arg(
+                    Arg::with_name("storage_account")
+                        .index(1)
+                        .value_name("STORAGE_ACCOUNT")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .help("storage account for transaction"),
+                )
+                .arg(
+                    Arg::with_name("accounts")
+                        .index(2)
+                        .value_name("ACCOUNT")
+                        .takes_value(true)
+                        .multiple(true)
+                        .validator(is_valid_pubkey)
+                        .help("writable accounts to add"),
+                )
+                .arg(
+                    Arg::with_name("readonly")
+                        .long("readonly")
+                        .value_name("ACCOUNT")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .validator(is_valid_pubkey)
+                        .help("read-only account to add"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("account-reclaim")
+                .about("Close empty storage cell and contract accounts and reclaim their rent")
+                .arg(
+                    Arg::with_name("recipient")
+                        .long("recipient")
+                        .value_name("ACCOUNT")
+                        .takes_value(true)
+                        .validator(is_valid_pubkey)
+                        .help("operator balance account to receive lamports, the main treasury by default"),
+                )
+                .arg(
+                    Arg::with_name("dry-run")
+                        .long("dry-run")
+                        .takes_value(false)
+                        .help("List empty accounts without closing them"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("operator-add")
+                .about("Add an operator to the operator registry or resume the suspended one")
+                .arg(
+                    Arg::with_name("operator")
+                        .index(1)
+                        .value_name("OPERATOR")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .help("operator key"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("operator-remove")
+                .about("Remove an operator from the operator registry")
+                .arg(
+                    Arg::with_name("operator")
+                        .index(1)
+                        .value_name("OPERATOR")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .help("operator key"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("operator-suspend")
+                .about("Suspend an operator in the operator registry")
+                .arg(
+                    Arg::with_name("operator")
+                        .index(1)
+                        .value_name("OPERATOR")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .help("operator key"),
+                )
+        )
         .subcommand(
             SubCommand::with_name("neon-elf-params")
                 .about("Get NEON values stored in elf")
@@ -15,9 +15,13 @@ use std::{cell::RefCell, collections::HashMap, convert::TryInto, rc::Rc};
 use crate::{rpc::Rpc, NeonError};
 use ethnum::U256;
 use evm_loader::{
-    account::{BalanceAccount, ContractAccount, StorageCell, StorageCellAddress},
+    account::{
+        BalanceAccount, ChainRegistry, ContractAccount, OperatorRegistry, StorageCell,
+        StorageCellAddress,
+    },
     account_storage::AccountStorage,
     config::STORAGE_ENTRIES_IN_CONTRACT_ACCOUNT,
+    evm::Spec,
     executor::{Action, OwnedAccountInfo},
 };
 use log::{debug, info, trace};
@@ -79,7 +83,7 @@ impl<'rpc, T: Rpc + BuildConfigSimulator> EmulatorAccountStorage<'rpc, T> {
             Some(chains) => chains,
         };
 
-        Ok(Self {
+        let storage = Self {
             accounts: RefCell::new(HashMap::new()),
             program_id,
             chains,
@@ -88,7 +92,17 @@ impl<'rpc, T: Rpc + BuildConfigSimulator> EmulatorAccountStorage<'rpc, T> {
             block_number,
             block_timestamp,
             state_overrides,
-        })
+        };
+
+        // Program checks chain ids against the registry, once it is created
+        let (chain_registry, _) = ChainRegistry::address(&program_id);
+        storage.use_account(chain_registry, false).await?;
+
+        // Program requires the registry to authorize the operator
+        let (operator_registry, _) = OperatorRegistry::address(&program_id);
+        storage.use_account(operator_registry, false).await?;
+
+        Ok(storage)
     }
 
     pub async fn with_accounts(
@@ -108,6 +122,13 @@ impl<'rpc, T: Rpc + BuildConfigSimulator> EmulatorAccountStorage<'rpc, T> {
 }
 
 impl<T: Rpc> EmulatorAccountStorage<'_, T> {
+    /// Emulate all chains with the given EVM spec instead of the deployed one
+    pub fn override_spec(&mut self, spec: Spec) {
+        for chain in &mut self.chains {
+            chain.spec = spec;
+        }
+    }
+
     async fn download_accounts(&self, pubkeys: &[Pubkey]) -> Result<(), NeonError> {
         let accounts = self.rpc.get_multiple_accounts(pubkeys).await?;
 
@@ -583,14 +604,24 @@ impl<T: Rpc> AccountStorage for EmulatorAccountStorage<'_, T> {
         false
     }
 
-    fn chain_id_to_token(&self, chain_id: u64) -> Pubkey {
+    fn chain_id_to_token(&self, chain_id: u64) -> evm_loader::error::Result<Pubkey> {
         for chain in &self.chains {
             if chain.id == chain_id {
-                return chain.token;
+                return Ok(chain.token);
             }
         }
 
-        unreachable!();
+        Err(evm_loader::error::Error::InvalidChainId(chain_id))
+    }
+
+    fn chain_spec(&self, chain_id: u64) -> evm_loader::error::Result<Spec> {
+        for chain in &self.chains {
+            if chain.id == chain_id {
+                return Ok(chain.spec);
+            }
+        }
+
+        Err(evm_loader::error::Error::InvalidChainId(chain_id))
     }
 
     fn default_chain_id(&self) -> u64 {
@@ -0,0 +1,62 @@
+use evm_loader::account::{OperatorRegistry, StateAccount};
+use log::info;
+
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::Signer,
+};
+
+use crate::{account_storage::account_info, commands::send_transaction, NeonResult};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountBlockAddReturn {
+    pub transaction: Signature,
+}
+
+/// Adds `accounts` to the NEON transaction in progress in `storage_account`
+pub async fn execute(
+    rpc_client: &RpcClient,
+    signer: &dyn Signer,
+    evm_loader: Pubkey,
+    storage_account: &Pubkey,
+    accounts: &[AccountMeta],
+) -> NeonResult<AccountBlockAddReturn> {
+    let mut acc = rpc_client.get_account(storage_account).await?;
+    let storage_info = account_info(storage_account, &mut acc);
+    let storage = StateAccount::from_account(&evm_loader, storage_info)?;
+
+    let operator = &signer.pubkey();
+
+    let mut accounts_meta: Vec<AccountMeta> = vec![
+        AccountMeta::new(*storage_account, false),  // State account
+        AccountMeta::new_readonly(*operator, true), // Operator
+    ];
+
+    accounts_meta.extend(accounts.iter().map(|meta| AccountMeta {
+        pubkey: meta.pubkey,
+        is_signer: false,
+        is_writable: meta.is_writable,
+    }));
+    accounts_meta.push(AccountMeta::new_readonly(
+        OperatorRegistry::address(&evm_loader).0,
+        false,
+    ));
+    for meta in &accounts_meta {
+        info!("\t{:?}", meta);
+    }
+
+    let block_accounts_instruction =
+        Instruction::new_with_bincode(evm_loader, &(0x2B_u8, storage.trx_hash()), accounts_meta);
+
+    let instructions = vec![block_accounts_instruction];
+
+    let signature = send_transaction(rpc_client, signer, &instructions).await?;
+
+    Ok(AccountBlockAddReturn {
+        transaction: signature,
+    })
+}
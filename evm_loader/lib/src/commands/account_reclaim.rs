@@ -0,0 +1,134 @@
+use evm_loader::account::{
+    ContractAccount, MainTreasury, OperatorRegistry, StorageCell, TAG_ACCOUNT_CONTRACT,
+    TAG_STORAGE_CELL,
+};
+use log::info;
+
+use serde::{Deserialize, Serialize};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::Signer,
+};
+
+use crate::{account_storage::account_info, commands::send_transaction, NeonResult};
+
+/// Accounts closed by a single transaction, limited by the transaction size
+const ACCOUNTS_PER_TRANSACTION: usize = 24;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountReclaimReturn {
+    pub recipient: String,
+    pub accounts: Vec<String>,
+    pub lamports: u64,
+    pub transactions: Vec<Signature>,
+}
+
+/// Finds empty Storage Cell and Contract accounts and closes them,
+/// rent is returned to `recipient` or to the main treasury.
+/// With `dry_run` accounts are only listed.
+pub async fn execute(
+    rpc_client: &RpcClient,
+    signer: &dyn Signer,
+    evm_loader: Pubkey,
+    recipient: Option<Pubkey>,
+    dry_run: bool,
+) -> NeonResult<AccountReclaimReturn> {
+    let recipient = recipient.unwrap_or_else(|| MainTreasury::address(&evm_loader).0);
+
+    let storage_cells = find_accounts(rpc_client, &evm_loader, TAG_STORAGE_CELL, None).await?;
+    // Contracts without code have the minimal size
+    let contract_len = ContractAccount::required_account_size(&[]);
+    let contracts = find_accounts(
+        rpc_client,
+        &evm_loader,
+        TAG_ACCOUNT_CONTRACT,
+        Some(contract_len),
+    )
+    .await?;
+
+    let mut empty_accounts = Vec::new();
+    let mut lamports = 0_u64;
+
+    for (pubkey, mut account) in storage_cells.into_iter().chain(contracts) {
+        let account_lamports = account.lamports;
+        let info = account_info(&pubkey, &mut account);
+
+        if evm_loader::account::is_blocked(&evm_loader, &info)? {
+            continue;
+        }
+
+        let is_empty = if evm_loader::account::tag(&evm_loader, &info)? == TAG_STORAGE_CELL {
+            StorageCell::from_account(&evm_loader, info)?.is_empty()
+        } else {
+            ContractAccount::from_account(&evm_loader, info)?.is_empty()
+        };
+
+        if is_empty {
+            info!("{pubkey}: {account_lamports} lamports");
+            empty_accounts.push(pubkey);
+            lamports += account_lamports;
+        }
+    }
+
+    let (operator_registry, _) = OperatorRegistry::address(&evm_loader);
+
+    let mut transactions = Vec::new();
+    if !dry_run {
+        for chunk in empty_accounts.chunks(ACCOUNTS_PER_TRANSACTION) {
+            let mut accounts_meta: Vec<AccountMeta> = vec![
+                AccountMeta::new_readonly(signer.pubkey(), true), // Operator
+                AccountMeta::new(recipient, false),               // Recipient
+            ];
+            accounts_meta.extend(chunk.iter().map(|key| AccountMeta::new(*key, false)));
+            accounts_meta.push(AccountMeta::new_readonly(operator_registry, false));
+
+            let instruction = Instruction::new_with_bincode(evm_loader, &0x2C_u8, accounts_meta);
+
+            let signature = send_transaction(rpc_client, signer, &[instruction]).await?;
+            transactions.push(signature);
+        }
+    }
+
+    Ok(AccountReclaimReturn {
+        recipient: recipient.to_string(),
+        accounts: empty_accounts.iter().map(ToString::to_string).collect(),
+        lamports,
+        transactions,
+    })
+}
+
+async fn find_accounts(
+    rpc_client: &RpcClient,
+    evm_loader: &Pubkey,
+    tag: u8,
+    data_len: Option<usize>,
+) -> NeonResult<Vec<(Pubkey, Account)>> {
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, vec![tag]))];
+    if let Some(data_len) = data_len {
+        filters.push(RpcFilterType::DataSize(data_len as u64));
+    }
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let accounts = rpc_client
+        .get_program_accounts_with_config(evm_loader, config)
+        .await?;
+
+    Ok(accounts)
+}
@@ -1,4 +1,4 @@
-use evm_loader::account::StateAccount;
+use evm_loader::account::{OperatorRegistry, StateAccount};
 use log::info;
 
 use serde::{Deserialize, Serialize};
@@ -46,6 +46,10 @@ pub async fn execute(
             accounts_meta.push(AccountMeta::new_readonly(blocked_account_meta.key, false));
         }
     }
+    accounts_meta.push(AccountMeta::new_readonly(
+        OperatorRegistry::address(&evm_loader).0,
+        false,
+    ));
     for meta in &accounts_meta {
         info!("\t{:?}", meta);
     }
@@ -1,6 +1,6 @@
 use evm_loader::account::ContractAccount;
 use evm_loader::error::build_revert_message;
-use log::{debug, info};
+use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use solana_sdk::entrypoint::MAX_PERMITTED_DATA_INCREASE;
 use solana_sdk::pubkey::Pubkey;
@@ -21,7 +21,31 @@ use evm_loader::{
     executor::{Action, ExecutorState},
     gasometer::LAMPORTS_PER_SIGNATURE,
 };
-use serde_with::{hex::Hex, serde_as};
+use serde_with::{hex::Hex, serde_as, DisplayFromStr};
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalAccountMeta {
+    #[serde_as(as = "DisplayFromStr")]
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// Solana instruction queued by a precompile, executed by the program after the EVM transaction
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalInstruction {
+    #[serde_as(as = "DisplayFromStr")]
+    pub program_id: Pubkey,
+    pub accounts: Vec<ExternalAccountMeta>,
+    #[serde_as(as = "Hex")]
+    pub data: Vec<u8>,
+    pub fee: u64,
+    /// `false` if the emulator doesn't replay the instruction of this program.
+    /// Precompile reads of the accounts it modifies return the state before the instruction.
+    pub emulated: bool,
+}
 
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,8 +55,11 @@ pub struct EmulateResponse {
     pub result: Vec<u8>,
     pub steps_executed: u64,
     pub used_gas: u64,
+    /// Ethereum-compatible gas, reported for information only
+    pub evm_gas_used: u64,
     pub iterations: u64,
     pub solana_accounts: Vec<SolanaAccount>,
+    pub external_instructions: Vec<ExternalInstruction>,
 }
 
 impl EmulateResponse {
@@ -44,8 +71,10 @@ impl EmulateResponse {
             result: exit_status.into_result().unwrap_or_default(),
             steps_executed: 0,
             used_gas: 0,
+            evm_gas_used: 0,
             iterations: 0,
             solana_accounts: vec![],
+            external_instructions: vec![],
         }
     }
 }
@@ -75,6 +104,10 @@ pub async fn execute(
     )
     .await?;
 
+    if let Some(spec) = emulate_request.spec {
+        storage.override_spec(spec);
+    }
+
     let step_limit = emulate_request.step_limit.unwrap_or(100000);
 
     setup_emulator_syscall_stubs(rpc).await?;
@@ -94,7 +127,7 @@ async fn emulate_trx(
     info!("origin: {:?}", origin);
     info!("tx: {:?}", tx);
 
-    let (exit_status, actions, steps_executed) = {
+    let (exit_status, actions, steps_executed, evm_gas_used) = {
         let mut backend = ExecutorState::new(storage);
         let mut evm = match Machine::new(tx, origin, &mut backend, tracer).await {
             Ok(evm) => evm,
@@ -107,7 +140,7 @@ async fn emulate_trx(
         }
 
         let actions = backend.into_actions();
-        (result, actions, steps_executed)
+        (result, actions, steps_executed, evm.gas_used())
     };
 
     storage.apply_actions(actions.clone()).await?;
@@ -127,17 +160,55 @@ async fn emulate_trx(
     let used_gas = storage.gas + iterations_gas + treasury_gas + cancel_gas;
 
     let solana_accounts = storage.accounts.borrow().values().cloned().collect();
+    let external_instructions = external_instructions(&actions);
+    for instruction in external_instructions.iter().filter(|i| !i.emulated) {
+        warn!(
+            "External instruction of {} is not emulated",
+            instruction.program_id
+        );
+    }
 
     Ok(EmulateResponse {
         exit_status: exit_status.to_string(),
         steps_executed,
         used_gas,
+        evm_gas_used,
         solana_accounts,
+        external_instructions,
         result: exit_status.into_result().unwrap_or_default(),
         iterations,
     })
 }
 
+fn external_instructions(actions: &[Action]) -> Vec<ExternalInstruction> {
+    actions
+        .iter()
+        .filter_map(|action| match action {
+            Action::ExternalInstruction {
+                program_id,
+                accounts,
+                data,
+                fee,
+                ..
+            } => Some(ExternalInstruction {
+                program_id: *program_id,
+                accounts: accounts
+                    .iter()
+                    .map(|meta| ExternalAccountMeta {
+                        pubkey: meta.pubkey,
+                        is_signer: meta.is_signer,
+                        is_writable: meta.is_writable,
+                    })
+                    .collect(),
+                data: data.clone(),
+                fee: *fee,
+                emulated: evm_loader::external_programs::is_emulated(program_id),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
 fn realloc_iterations(actions: &[Action]) -> u64 {
     let mut result = 0;
 
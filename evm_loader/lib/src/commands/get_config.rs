@@ -18,7 +18,8 @@ use solana_sdk::{
     transaction::Transaction,
 };
 
-use crate::{rpc::Rpc, NeonError, NeonResult};
+use crate::{account_storage::account_info, rpc::Rpc, NeonError, NeonResult};
+use evm_loader::{account::ChainRegistry, evm::Spec};
 
 use crate::rpc::{CallDbClient, CloneRpcClient};
 use serde_with::{serde_as, DisplayFromStr};
@@ -40,6 +41,8 @@ pub struct ChainInfo {
     pub name: String,
     #[serde_as(as = "DisplayFromStr")]
     pub token: Pubkey,
+    #[serde(default)]
+    pub spec: Spec,
 }
 
 #[serde_as]
@@ -304,8 +307,7 @@ impl ConfigSimulator<'_> {
             let index = i.to_le_bytes();
             let return_data = self.simulate_evm_instruction(0xA1, &index).await?;
 
-            let (id, name, token) = bincode::deserialize(&return_data)?;
-            result.push(ChainInfo { id, name, token });
+            result.push(decode_chain_info(&return_data)?);
         }
 
         Ok(result)
@@ -348,10 +350,57 @@ pub async fn execute(
     })
 }
 
+/// Programs deployed before the per-chain EVM spec return `(id, name, token)`
+fn decode_chain_info(return_data: &[u8]) -> NeonResult<ChainInfo> {
+    let (id, name, token): (u64, String, Pubkey) = bincode::deserialize(return_data)?;
+
+    // Bincode ignores trailing bytes, the old format is detected by the exact length
+    let legacy_len = bincode::serialized_size(&(id, &name, token))?;
+    let spec = if legacy_len == return_data.len() as u64 {
+        Spec::default()
+    } else {
+        let (_, _, _, spec): (u64, String, Pubkey, Spec) = bincode::deserialize(return_data)?;
+        spec
+    };
+
+    Ok(ChainInfo {
+        id,
+        name,
+        token,
+        spec,
+    })
+}
+
+/// Reads chains from the Chain Registry account,
+/// falls back to the chains compiled into the program if the registry is not created
 pub async fn read_chains(
-    rpc: &impl BuildConfigSimulator,
+    rpc: &(impl BuildConfigSimulator + Rpc),
     program_id: Pubkey,
 ) -> NeonResult<Vec<ChainInfo>> {
+    let (registry_key, _) = ChainRegistry::address(&program_id);
+    if let Some(mut account) = rpc.get_account(&registry_key).await?.value {
+        if account.owner == program_id {
+            let info = account_info(&registry_key, &mut account);
+            let registry = ChainRegistry::from_account(&program_id, info)?;
+
+            let chains = registry
+                .chains()
+                .iter()
+                .filter(|c| c.enabled)
+                .map(|c| {
+                    Ok(ChainInfo {
+                        id: c.id,
+                        name: c.name().to_string(),
+                        token: c.token,
+                        spec: c.spec()?,
+                    })
+                })
+                .collect::<NeonResult<_>>()?;
+
+            return Ok(chains);
+        }
+    }
+
     let mut simulator = rpc.build_config_simulator(program_id).await?;
 
     simulator.get_chains().await
@@ -374,6 +423,25 @@ impl CloneRpcClient {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_decode_chain_info() {
+        let token = Pubkey::new_unique();
+
+        let data = bincode::serialize(&(111_u64, "neon", token, Spec::London)).unwrap();
+        let chain = decode_chain_info(&data).unwrap();
+        assert_eq!((chain.id, chain.name.as_str()), (111, "neon"));
+        assert_eq!((chain.token, chain.spec), (token, Spec::London));
+
+        let data = bincode::serialize(&(112_u64, "sol", token)).unwrap();
+        let chain = decode_chain_info(&data).unwrap();
+        assert_eq!((chain.id, chain.name.as_str()), (112, "sol"));
+        assert_eq!((chain.token, chain.spec), (token, Spec::default()));
+
+        // Unknown spec is not mistaken for the old format
+        let data = bincode::serialize(&(113_u64, "eth", token, 7_u32)).unwrap();
+        assert!(decode_chain_info(&data).is_err());
+    }
+
     #[test]
     fn test_bpf_loader_pubkey() {
         let pubkey = Pubkey::from([
@@ -11,6 +11,8 @@ use solana_sdk::{
     transaction::Transaction,
 };
 
+pub mod account_block_add;
+pub mod account_reclaim;
 pub mod cancel_trx;
 pub mod collect_treasury;
 pub mod emulate;
@@ -21,6 +23,7 @@ pub mod get_holder;
 pub mod get_neon_elf;
 pub mod get_storage_at;
 pub mod init_environment;
+pub mod operator_registry;
 pub mod trace;
 mod transaction_executor;
 
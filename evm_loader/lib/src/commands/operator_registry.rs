@@ -0,0 +1,74 @@
+use evm_loader::account::OperatorRegistry;
+use log::info;
+
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    bpf_loader_upgradeable,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::Signer,
+    system_program,
+};
+
+use crate::{commands::send_transaction, NeonResult};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum OperatorRegistryAction {
+    Add,
+    Remove,
+    Suspend,
+}
+
+impl OperatorRegistryAction {
+    fn tag(self) -> u8 {
+        match self {
+            Self::Add => 0x40,
+            Self::Remove => 0x41,
+            Self::Suspend => 0x42,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OperatorRegistryReturn {
+    pub registry: String,
+    pub transaction: Signature,
+}
+
+/// Adds, removes or suspends `operator` in the Operator Registry.
+/// `signer` must be the program upgrade authority, it also pays for the registry account.
+pub async fn execute(
+    rpc_client: &RpcClient,
+    signer: &dyn Signer,
+    evm_loader: Pubkey,
+    action: OperatorRegistryAction,
+    operator: Pubkey,
+) -> NeonResult<OperatorRegistryReturn> {
+    let (registry, _) = OperatorRegistry::address(&evm_loader);
+    let program_data = bpf_loader_upgradeable::get_program_data_address(&evm_loader);
+
+    info!("{action:?} operator {operator} in registry {registry}");
+
+    let accounts_meta = vec![
+        AccountMeta::new(registry, false),              // Operator Registry
+        AccountMeta::new_readonly(program_data, false), // Program data
+        AccountMeta::new_readonly(signer.pubkey(), true), // Upgrade authority
+        AccountMeta::new(signer.pubkey(), true),        // Payer
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    let instruction = Instruction::new_with_bincode(
+        evm_loader,
+        &(action.tag(), operator.to_bytes()),
+        accounts_meta,
+    );
+
+    let signature = send_transaction(rpc_client, signer, &[instruction]).await?;
+
+    Ok(OperatorRegistryReturn {
+        registry: registry.to_string(),
+        transaction: signature,
+    })
+}
@@ -1,24 +1,37 @@
 use log::info;
-use solana_sdk::{program_error::ProgramError, program_stubs::SyscallStubs, sysvar::rent::Rent};
+use serde::de::DeserializeOwned;
+use solana_sdk::{
+    program_error::ProgramError,
+    program_stubs::SyscallStubs,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, rent::Rent},
+};
 
 use crate::{errors::NeonError, rpc::Rpc};
 
 pub struct EmulatorStubs {
     rent: Rent,
+    clock: Clock,
+}
+
+async fn get_sysvar<T: DeserializeOwned>(rpc: &impl Rpc, pubkey: &Pubkey) -> Result<T, NeonError> {
+    let data = rpc
+        .get_account(pubkey)
+        .await?
+        .value
+        .map(|a| a.data)
+        .unwrap_or_default();
+    let sysvar = bincode::deserialize(&data).map_err(|_| ProgramError::InvalidArgument)?;
+
+    Ok(sysvar)
 }
 
 impl EmulatorStubs {
     pub async fn new(rpc: &impl Rpc) -> Result<Box<EmulatorStubs>, NeonError> {
-        let rent_pubkey = solana_sdk::sysvar::rent::id();
-        let data = rpc
-            .get_account(&rent_pubkey)
-            .await?
-            .value
-            .map(|a| a.data)
-            .unwrap_or_default();
-        let rent = bincode::deserialize(&data).map_err(|_| ProgramError::InvalidArgument)?;
-
-        Ok(Box::new(Self { rent }))
+        let rent = get_sysvar(rpc, &solana_sdk::sysvar::rent::id()).await?;
+        let clock = get_sysvar(rpc, &solana_sdk::sysvar::clock::id()).await?;
+
+        Ok(Box::new(Self { rent, clock }))
     }
 }
 
@@ -33,6 +46,16 @@ impl SyscallStubs for EmulatorStubs {
         0
     }
 
+    fn sol_get_clock_sysvar(&self, pointer: *mut u8) -> u64 {
+        unsafe {
+            #[allow(clippy::cast_ptr_alignment)]
+            let clock = pointer.cast::<Clock>();
+            *clock = self.clock.clone();
+        }
+
+        0
+    }
+
     fn sol_log(&self, message: &str) {
         info!("{}", message);
     }
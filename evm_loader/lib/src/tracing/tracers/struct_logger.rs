@@ -73,6 +73,7 @@ impl StructLog {
     pub fn new(
         opcode: u8,
         pc: u64,
+        gas: u64,
         gas_cost: u64,
         depth: usize,
         memory: Option<Vec<String>>,
@@ -82,7 +83,7 @@ impl StructLog {
         Self {
             pc,
             op,
-            gas: 0,
+            gas,
             gas_cost,
             depth,
             memory,
@@ -122,6 +123,7 @@ pub struct StructLogger {
     depth: usize,
     storage_access: Option<(U256, U256)>,
     exit_status: Option<ExitStatus>,
+    gas_used: u64,
 }
 
 impl StructLogger {
@@ -133,6 +135,7 @@ impl StructLogger {
             depth: 0,
             storage_access: None,
             exit_status: None,
+            gas_used: 0,
         }
     }
 }
@@ -143,15 +146,18 @@ impl EventListener for StructLogger {
             Event::BeginVM { .. } => {
                 self.depth += 1;
             }
-            Event::EndVM { status } => {
+            Event::EndVM { status, gas_used } => {
                 if self.depth == 1 {
                     self.exit_status = Some(status);
+                    self.gas_used = gas_used;
                 }
                 self.depth -= 1;
             }
             Event::BeginStep {
                 opcode,
                 pc,
+                gas,
+                gas_cost,
                 stack,
                 memory,
             } => {
@@ -172,18 +178,15 @@ impl EventListener for StructLogger {
                     None
                 };
 
-                let log = StructLog::new(opcode, pc as u64, 0, self.depth, memory, stack);
+                let log =
+                    StructLog::new(opcode, pc as u64, gas, gas_cost, self.depth, memory, stack);
                 self.logs.push(log);
             }
-            Event::EndStep {
-                gas_used,
-                return_data,
-            } => {
+            Event::EndStep { return_data } => {
                 let last = self
                     .logs
                     .last_mut()
                     .expect("`EndStep` event before `BeginStep`");
-                last.gas = gas_used;
                 if !self.config.disable_storage {
                     if let Some((index, value)) = self.storage_access.take() {
                         last.storage.get_or_insert_with(Default::default).insert(
@@ -207,7 +210,7 @@ impl EventListener for StructLogger {
     fn into_traces(self: Box<Self>) -> Value {
         let exit_status = self.exit_status.expect("Emulation is not completed");
         let result = StructLoggerResult {
-            gas: 0,
+            gas: self.gas_used,
             failed: !exit_status
                 .is_succeed()
                 .expect("Emulation is not completed"),
@@ -1,11 +1,12 @@
 pub mod tracer_ch_common;
 mod tracer_ch_db;
 
+use evm_loader::evm::Spec;
 pub use evm_loader::types::Address;
 use evm_loader::types::{StorageKey, Transaction};
 use evm_loader::{
     account_storage::AccountStorage,
-    types::{AccessListTx, LegacyTx, TransactionPayload},
+    types::{AccessListTx, DynamicFeeTx, LegacyTx, TransactionPayload},
 };
 use serde_with::skip_serializing_none;
 use solana_sdk::pubkey::Pubkey;
@@ -47,6 +48,8 @@ pub struct TxParams {
     pub value: Option<U256>,
     pub gas_limit: Option<U256>,
     pub gas_price: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    pub max_priority_fee_per_gas: Option<U256>,
     pub access_list: Option<Vec<AccessListItem>>,
     pub chain_id: Option<u64>,
 }
@@ -58,7 +61,33 @@ impl TxParams {
         let origin_nonce = backend.nonce(self.from, chain_id).await;
         let nonce = self.nonce.unwrap_or(origin_nonce);
 
-        let payload = if let Some(access_list) = self.access_list {
+        let is_dynamic_fee =
+            self.max_fee_per_gas.is_some() || self.max_priority_fee_per_gas.is_some();
+
+        let payload = if is_dynamic_fee {
+            let access_list: Vec<_> = self
+                .access_list
+                .unwrap_or_default()
+                .into_iter()
+                .map(|a| (a.address, a.storage_keys))
+                .collect();
+
+            let dynamic_fee_tx = DynamicFeeTx {
+                nonce,
+                max_priority_fee_per_gas: U256::ZERO,
+                max_fee_per_gas: U256::ZERO,
+                gas_limit: self.gas_limit.unwrap_or(U256::MAX),
+                target: self.to,
+                value: self.value.unwrap_or_default(),
+                call_data: self.data.unwrap_or_default(),
+                chain_id: U256::from(chain_id),
+                access_list,
+                r: U256::ZERO,
+                s: U256::ZERO,
+                recovery_id: 0,
+            };
+            TransactionPayload::DynamicFee(dynamic_fee_tx)
+        } else if let Some(access_list) = self.access_list {
             let access_list: Vec<_> = access_list
                 .into_iter()
                 .map(|a| (a.address, a.storage_keys))
@@ -120,6 +149,8 @@ pub struct EmulateRequest {
     pub tx: TxParams,
     pub step_limit: Option<u64>,
     pub chains: Option<Vec<ChainInfo>>,
+    /// Overrides EVM spec of all chains
+    pub spec: Option<Spec>,
     pub trace_config: Option<TraceCallConfig>,
     #[serde_as(as = "Vec<DisplayFromStr>")]
     pub accounts: Vec<Pubkey>,
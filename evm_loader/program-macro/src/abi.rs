@@ -0,0 +1,367 @@
+use std::fmt::Write;
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{braced, Attribute, Ident, LitStr, Result, Token, Visibility};
+use tiny_keccak::{Hasher, Keccak};
+
+/// ```ignore
+/// precompile_abi! {
+///     #[interface = "SPLToken"]
+///     #[declarations = "struct Account { ... }"]
+///     pub enum SplTokenCall {
+///         /// Comment copied to the Solidity interface
+///         FindAccount = "function findAccount(bytes32 salt) external pure returns(bytes32)",
+///         /// `#[grouped]` puts the method right after the previous one, without a blank line
+///         #[grouped]
+///         GetAccount = "function getAccount(bytes32 account) external view returns(Account memory)",
+///     }
+/// }
+/// ```
+pub struct AbiInput {
+    attrs: Vec<Attribute>,
+    interface: LitStr,
+    declarations: Option<LitStr>,
+    vis: Visibility,
+    name: Ident,
+    methods: Vec<Method>,
+}
+
+struct Method {
+    docs: Vec<String>,
+    grouped: bool,
+    name: Ident,
+    declaration: LitStr,
+}
+
+impl Parse for Method {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let declaration = input.parse()?;
+
+        Ok(Self {
+            docs: attrs.iter().filter_map(doc_string).collect(),
+            grouped: attrs.iter().any(|attr| attr.path.is_ident("grouped")),
+            name,
+            declaration,
+        })
+    }
+}
+
+fn doc_string(attr: &Attribute) -> Option<String> {
+    if !attr.path.is_ident("doc") {
+        return None;
+    }
+
+    match attr.parse_meta().ok()? {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            lit: syn::Lit::Str(s),
+            ..
+        }) => Some(s.value().trim().to_string()),
+        _ => None,
+    }
+}
+
+fn string_attribute(attr: &Attribute) -> Result<LitStr> {
+    match attr.parse_meta()? {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            lit: syn::Lit::Str(s),
+            ..
+        }) => Ok(s),
+        meta => Err(syn::Error::new_spanned(
+            meta,
+            "expected `#[name = \"...\"]`",
+        )),
+    }
+}
+
+impl Parse for AbiInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut attrs = Vec::new();
+        let mut interface = None;
+        let mut declarations = None;
+
+        for attr in input.call(Attribute::parse_outer)? {
+            if attr.path.is_ident("interface") {
+                interface = Some(string_attribute(&attr)?);
+            } else if attr.path.is_ident("declarations") {
+                declarations = Some(string_attribute(&attr)?);
+            } else {
+                attrs.push(attr);
+            }
+        }
+
+        let vis = input.parse()?;
+        input.parse::<Token![enum]>()?;
+        let name: Ident = input.parse()?;
+
+        let content;
+        braced!(content in input);
+        let methods = Punctuated::<Method, Token![,]>::parse_terminated(&content)?;
+
+        let interface = interface
+            .ok_or_else(|| syn::Error::new(name.span(), "missing `#[interface = \"...\"]`"))?;
+
+        Ok(Self {
+            attrs,
+            interface,
+            declarations,
+            vis,
+            name,
+            methods: methods.into_iter().collect(),
+        })
+    }
+}
+
+struct Parameter {
+    solidity_type: String,
+    name: Ident,
+}
+
+/// Parsed `function name(type name, ...) external [view|pure] [returns(...)]`
+struct Function {
+    name: String,
+    parameters: Vec<Parameter>,
+    is_view: bool,
+}
+
+impl Function {
+    fn signature(&self) -> String {
+        let types: Vec<&str> = self
+            .parameters
+            .iter()
+            .map(|p| p.solidity_type.as_str())
+            .collect();
+
+        format!("{}({})", self.name, types.join(","))
+    }
+
+    fn selector(&self) -> [u8; 4] {
+        let mut hash = [0_u8; 32];
+
+        let mut keccak = Keccak::v256();
+        keccak.update(self.signature().as_bytes());
+        keccak.finalize(&mut hash);
+
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+}
+
+/// `mintAuthority` and `_mint` become `mint_authority` and `mint`
+fn field_name(name: &str) -> String {
+    let mut field = String::new();
+    for c in name.trim_start_matches('_').chars() {
+        if c.is_ascii_uppercase() {
+            if !field.is_empty() && !field.ends_with('_') {
+                field.push('_');
+            }
+            field.push(c.to_ascii_lowercase());
+        } else {
+            field.push(c);
+        }
+    }
+
+    field
+}
+
+fn parse_function(declaration: &LitStr) -> Result<Function> {
+    let error = |message: &str| syn::Error::new(declaration.span(), message);
+
+    let text = declaration.value();
+    let text = text
+        .trim()
+        .strip_prefix("function ")
+        .ok_or_else(|| error("expected `function name(...)`"))?;
+
+    let open = text.find('(').ok_or_else(|| error("expected `(`"))?;
+    let close = text.find(')').ok_or_else(|| error("expected `)`"))?;
+
+    let name = text[..open].trim().to_string();
+
+    let mut parameters = Vec::new();
+    for (i, parameter) in text[open + 1..close].split(',').enumerate() {
+        let words: Vec<&str> = parameter.split_whitespace().collect();
+        let Some(solidity_type) = words.first() else {
+            continue;
+        };
+
+        let name = match words.as_slice() {
+            [_] | [_, "memory" | "calldata"] => format!("arg{i}"),
+            [.., name] => field_name(name),
+            [] => unreachable!(),
+        };
+
+        parameters.push(Parameter {
+            solidity_type: (*solidity_type).to_string(),
+            name: Ident::new(&name, Span::call_site()),
+        });
+    }
+
+    let modifiers = text[close + 1..]
+        .split("returns")
+        .next()
+        .unwrap_or_default();
+    let is_view = modifiers
+        .split_whitespace()
+        .any(|m| m == "view" || m == "pure");
+
+    Ok(Function {
+        name,
+        parameters,
+        is_view,
+    })
+}
+
+fn rust_type(solidity_type: &str, span: Span) -> Result<TokenStream> {
+    let tokens = match solidity_type {
+        "bytes32" => quote!([u8; 32]),
+        "bool" => quote!(bool),
+        "uint8" => quote!(u8),
+        "uint16" => quote!(u16),
+        "uint32" => quote!(u32),
+        "uint64" => quote!(u64),
+        "uint128" => quote!(u128),
+        "uint256" => quote!(::ethnum::U256),
+        "address" => quote!(crate::types::Address),
+        "string" => quote!(::std::string::String),
+        "bytes" => quote!(::std::vec::Vec<u8>),
+        _ => {
+            let message = format!("unsupported ABI type `{solidity_type}`");
+            return Err(syn::Error::new(span, message));
+        }
+    };
+
+    Ok(tokens)
+}
+
+fn solidity_interface(input: &AbiInput) -> String {
+    let mut interface = String::from(
+        "// SPDX-License-Identifier: MIT\n\npragma solidity >= 0.7.0;\npragma abicoder v2;\n\n",
+    );
+
+    writeln!(interface, "interface {} {{", input.interface.value()).unwrap();
+
+    if let Some(declarations) = &input.declarations {
+        let declarations = declarations.value();
+        for line in declarations.trim_matches('\n').lines() {
+            interface += line.trim_end();
+            interface += "\n";
+        }
+    }
+
+    for (i, method) in input.methods.iter().enumerate() {
+        if (i > 0 && !method.grouped) || (i == 0 && input.declarations.is_some()) {
+            interface += "\n";
+        }
+        for doc in &method.docs {
+            if doc.is_empty() {
+                interface += "    //\n";
+            } else {
+                writeln!(interface, "    // {doc}").unwrap();
+            }
+        }
+        writeln!(interface, "    {};", method.declaration.value().trim()).unwrap();
+    }
+
+    interface += "}\n";
+    interface
+}
+
+pub fn expand(input: &AbiInput) -> Result<TokenStream> {
+    let AbiInput {
+        attrs, vis, name, ..
+    } = input;
+
+    let mut variants = Vec::new();
+    let mut decoders = Vec::new();
+    let mut views = Vec::new();
+
+    for method in &input.methods {
+        let variant = &method.name;
+        let function = parse_function(&method.declaration)?;
+
+        let selector = function.selector();
+        let signature = function.signature();
+
+        let mut fields = Vec::new();
+        let mut decoded = Vec::new();
+        for (i, parameter) in function.parameters.iter().enumerate() {
+            let field = &parameter.name;
+            let field_type = rust_type(&parameter.solidity_type, method.declaration.span())?;
+            let position = i * 32;
+
+            fields.push(quote! { #field: #field_type });
+            decoded.push(quote! {
+                #field: <#field_type as AbiDecode>::decode(arguments, #position)?
+            });
+        }
+
+        let doc = format!("`{signature}`");
+        variants.push(quote! {
+            #[doc = #doc]
+            #variant { #(#fields),* }
+        });
+
+        decoders.push(quote! {
+            [#(#selector),*] => Self::#variant { #(#decoded),* }
+        });
+
+        if function.is_view {
+            views.push(quote! { Self::#variant { .. } });
+        }
+    }
+
+    let interface = solidity_interface(input);
+    let is_view = if views.is_empty() {
+        quote! { false }
+    } else {
+        quote! { matches!(self, #(#views)|*) }
+    };
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis enum #name {
+            #(#variants),*
+        }
+
+        impl #name {
+            /// Solidity interface of the precompile
+            pub const SOLIDITY_INTERFACE: &'static str = #interface;
+
+            /// Decodes method call from the transaction input
+            pub fn decode(
+                address: &crate::types::Address,
+                input: &[u8],
+            ) -> crate::error::Result<Self> {
+                use crate::executor::precompile_extension::abi::AbiDecode;
+
+                let mut selector = [0_u8; 4];
+                let selector_len = input.len().min(4);
+                selector[..selector_len].copy_from_slice(&input[..selector_len]);
+
+                let arguments = &input[selector_len..];
+
+                let call = match selector {
+                    #(#decoders,)*
+                    _ => {
+                        return Err(crate::error::Error::UnknownPrecompileMethodSelector(
+                            *address, selector,
+                        ))
+                    }
+                };
+
+                Ok(call)
+            }
+
+            /// Method doesn't change state and can be called from a static context
+            #[must_use]
+            pub const fn is_view(&self) -> bool {
+                #is_view
+            }
+        }
+    })
+}
@@ -17,6 +17,7 @@ pub struct NetSpecificConfig {
     pub neon_chain_id: u64,
     pub neon_token_mint: String,
     pub chains: Vec<Chain>,
+    pub code: CodeRules,
 }
 
 impl Parse for NetSpecificConfig {
@@ -48,10 +49,26 @@ impl Parse for NetSpecificConfig {
                     id: table["id"].as_integer().unwrap().try_into().unwrap(),
                     name: name.clone(),
                     token: table["token"].as_str().unwrap().to_string(),
+                    spec: table["spec"].as_str().unwrap().to_string(),
                 }
             })
             .collect::<Vec<_>>();
 
+        let code = root["code"].as_table().unwrap();
+        let code = CodeRules {
+            max_code_size: code["max_code_size"]
+                .as_integer()
+                .unwrap()
+                .try_into()
+                .unwrap(),
+            max_initcode_size: code["max_initcode_size"]
+                .as_integer()
+                .unwrap()
+                .try_into()
+                .unwrap(),
+            reject_ef_prefix: code["reject_ef_prefix"].as_bool().unwrap(),
+        };
+
         let (neon_chain_id, neon_token_mint) = chains
             .iter()
             .find_map(|c| {
@@ -69,6 +86,7 @@ impl Parse for NetSpecificConfig {
             neon_chain_id,
             neon_token_mint,
             chains,
+            code,
         })
     }
 }
@@ -78,6 +96,15 @@ pub struct Chain {
     pub id: u64,
     pub name: String,
     pub token: String,
+    pub spec: String,
+}
+
+/// Contract code validation rules: EIP-170, EIP-3860 and EIP-3541
+#[derive(Deserialize, Debug)]
+pub struct CodeRules {
+    pub max_code_size: usize,
+    pub max_initcode_size: usize,
+    pub reject_ef_prefix: bool,
 }
 
 pub struct CommonVariable {
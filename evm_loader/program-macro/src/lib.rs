@@ -1,6 +1,7 @@
 #![deny(warnings)]
 #![deny(clippy::all, clippy::pedantic, clippy::nursery)]
 
+mod abi;
 mod config_parser;
 
 use std::collections::BTreeMap;
@@ -14,6 +15,11 @@ use quote::quote;
 
 extern crate proc_macro;
 
+/// Names of `evm_loader::evm::Spec` variants
+const SPECS: [&str; 7] = [
+    "Istanbul", "Berlin", "London", "Shanghai", "Cancun", "Prague", "Osaka",
+];
+
 struct ElfParamInput {
     name: Ident,
     _separator: Token![,],
@@ -67,6 +73,7 @@ pub fn net_specific_config_parser(tokens: TokenStream) -> TokenStream {
         neon_token_mint,
         operators_whitelist,
         mut chains,
+        code,
     } = parse_macro_input!(tokens as NetSpecificConfig);
 
     let mut operators: Vec<Vec<u8>> = operators_whitelist
@@ -86,9 +93,29 @@ pub fn net_specific_config_parser(tokens: TokenStream) -> TokenStream {
         .iter()
         .map(|c| bs58::decode(&c.token).into_vec().unwrap())
         .collect::<Vec<_>>();
+    let chain_specs = chains
+        .iter()
+        .map(|c| {
+            assert!(
+                SPECS.contains(&c.spec.as_str()),
+                "Unknown EVM spec {} of chain {}",
+                c.spec,
+                c.name
+            );
+            Ident::new(&c.spec, proc_macro2::Span::call_site())
+        })
+        .collect::<Vec<_>>();
 
     let neon_chain_id_str = neon_chain_id.to_string();
 
+    let max_code_size = code.max_code_size;
+    let max_initcode_size = code.max_initcode_size;
+    let reject_ef_prefix = code.reject_ef_prefix;
+
+    let max_code_size_str = max_code_size.to_string();
+    let max_initcode_size_str = max_initcode_size.to_string();
+    let reject_ef_prefix_str = reject_ef_prefix.to_string();
+
     quote! {
         pub const PROGRAM_ID: solana_program::pubkey::Pubkey = solana_program::pubkey!(#program_id);
         pub const DEFAULT_CHAIN_ID: u64 = #neon_chain_id;
@@ -96,12 +123,20 @@ pub fn net_specific_config_parser(tokens: TokenStream) -> TokenStream {
         neon_elf_param!(NEON_CHAIN_ID, #neon_chain_id_str);
         neon_elf_param!(NEON_TOKEN_MINT, #neon_token_mint);
 
+        pub const MAX_CONTRACT_CODE_SIZE: usize = #max_code_size;
+        pub const MAX_INITCODE_SIZE: usize = #max_initcode_size;
+        pub const REJECT_EF_CONTRACT_CODE: bool = #reject_ef_prefix;
+
+        neon_elf_param!(NEON_MAX_CONTRACT_CODE_SIZE, #max_code_size_str);
+        neon_elf_param!(NEON_MAX_INITCODE_SIZE, #max_initcode_size_str);
+        neon_elf_param!(NEON_REJECT_EF_CONTRACT_CODE, #reject_ef_prefix_str);
+
         pub const AUTHORIZED_OPERATOR_LIST: [::solana_program::pubkey::Pubkey; #operators_len] = [
             #(::solana_program::pubkey::Pubkey::new_from_array([#((#operators),)*]),)*
         ];
 
-        pub const CHAIN_ID_LIST: [(u64, &str, ::solana_program::pubkey::Pubkey); #chains_len] = [
-            #( (#chain_ids, #chain_names, ::solana_program::pubkey::Pubkey::new_from_array([#(#chain_tokens),*])) ),*
+        pub const CHAIN_ID_LIST: [(u64, &str, ::solana_program::pubkey::Pubkey, crate::evm::Spec); #chains_len] = [
+            #( (#chain_ids, #chain_names, ::solana_program::pubkey::Pubkey::new_from_array([#(#chain_tokens),*]), crate::evm::Spec::#chain_specs) ),*
         ];
     }
     .into()
@@ -150,3 +185,14 @@ pub fn common_config_parser(tokens: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+/// Precompile extension methods declared with Solidity function signatures.
+/// Generates an enum of calls with selector and argument decoding, and the matching Solidity interface.
+#[proc_macro]
+pub fn precompile_abi(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as abi::AbiInput);
+
+    abi::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
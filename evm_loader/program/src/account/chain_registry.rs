@@ -0,0 +1,393 @@
+use std::cell::{Ref, RefMut};
+use std::mem::size_of;
+
+use crate::config::{CHAIN_ID_LIST, DEFAULT_CHAIN_ID};
+use crate::error::{Error, Result};
+use crate::evm::Spec;
+use solana_program::{
+    account_info::AccountInfo, pubkey::Pubkey, rent::Rent, system_program, sysvar::Sysvar,
+};
+
+use super::program::System;
+use super::{AccountsDB, Operator, ACCOUNT_PREFIX_LEN, ACCOUNT_SEED_VERSION, TAG_CHAIN_REGISTRY};
+
+const CHAIN_NAME_LEN: usize = 32;
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
+pub struct Chain {
+    pub id: u64,
+    pub token: Pubkey,
+    spec: u8,
+    pub enabled: bool,
+    name: [u8; CHAIN_NAME_LEN],
+}
+
+impl Chain {
+    pub fn new(id: u64, name: &str, token: Pubkey, spec: Spec) -> Result<Self> {
+        if name.is_empty() || (name.len() > CHAIN_NAME_LEN) {
+            return Err(Error::Custom(format!(
+                "Chain name must be 1..={CHAIN_NAME_LEN} bytes"
+            )));
+        }
+
+        let mut name_bytes = [0_u8; CHAIN_NAME_LEN];
+        name_bytes[..name.len()].copy_from_slice(name.as_bytes());
+
+        Ok(Self {
+            id,
+            token,
+            spec: spec as u8,
+            enabled: true,
+            name: name_bytes,
+        })
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        let len = self
+            .name
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(CHAIN_NAME_LEN);
+        std::str::from_utf8(&self.name[..len]).unwrap_or_default()
+    }
+
+    pub fn spec(&self) -> Result<Spec> {
+        Spec::try_from(self.spec)
+    }
+}
+
+/// Chains added by the program upgrade authority after deployment.
+/// Until the registry is created, chains compiled into the program are used.
+pub struct ChainRegistry<'a> {
+    account: AccountInfo<'a>,
+}
+
+const CHAINS_OFFSET: usize = ACCOUNT_PREFIX_LEN;
+
+impl<'a> ChainRegistry<'a> {
+    #[must_use]
+    pub fn address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[&[ACCOUNT_SEED_VERSION], b"ChainRegistry"], program_id)
+    }
+
+    #[must_use]
+    pub fn required_account_size(chains: usize) -> usize {
+        CHAINS_OFFSET + chains * size_of::<Chain>()
+    }
+
+    pub fn from_account(program_id: &Pubkey, account: AccountInfo<'a>) -> Result<Self> {
+        super::validate_tag(program_id, &account, TAG_CHAIN_REGISTRY)?;
+
+        Ok(Self { account })
+    }
+
+    /// Registry account, `None` if it is not created yet
+    pub fn load(program_id: &Pubkey, account: AccountInfo<'a>) -> Result<Option<Self>> {
+        if system_program::check_id(account.owner) {
+            return Ok(None);
+        }
+
+        Self::from_account(program_id, account).map(Some)
+    }
+
+    /// Registry from the transaction accounts, `None` if it is not created yet.
+    /// The registry account must always be passed, otherwise disabled chains
+    /// could be used by omitting it.
+    pub fn from_accounts_db(
+        program_id: &Pubkey,
+        accounts: &AccountsDB<'a>,
+    ) -> Result<Option<Self>> {
+        let Some(account) = accounts.chain_registry() else {
+            let (pubkey, _) = Self::address(program_id);
+            return Err(Error::ChainRegistryNotFound(pubkey));
+        };
+
+        Self::load(program_id, account.clone())
+    }
+
+    /// Registry passed as the instruction account, `None` if it is not created yet
+    pub fn from_instruction_account(
+        program_id: &Pubkey,
+        account: Option<&AccountInfo<'a>>,
+    ) -> Result<Option<Self>> {
+        let (expected_key, _) = Self::address(program_id);
+
+        let Some(account) = account else {
+            return Err(Error::ChainRegistryNotFound(expected_key));
+        };
+
+        if *account.key != expected_key {
+            return Err(Error::AccountInvalidKey(*account.key, expected_key));
+        }
+
+        Self::load(program_id, account.clone())
+    }
+
+    /// Registry passed as an optional account to the read-only config instructions
+    pub fn from_optional_account(
+        program_id: &Pubkey,
+        account: Option<&AccountInfo<'a>>,
+    ) -> Result<Option<Self>> {
+        let Some(account) = account else {
+            return Ok(None);
+        };
+
+        let (expected_key, _) = Self::address(program_id);
+        if *account.key != expected_key {
+            return Err(Error::AccountInvalidKey(*account.key, expected_key));
+        }
+
+        Self::load(program_id, account.clone())
+    }
+
+    /// Creates the registry with the chains compiled into the program
+    pub fn create(
+        program_id: &Pubkey,
+        account: AccountInfo<'a>,
+        system: &System<'a>,
+        payer: &Operator<'a>,
+    ) -> Result<Self> {
+        let (expected_key, bump_seed) = Self::address(program_id);
+        if *account.key != expected_key {
+            return Err(Error::AccountInvalidKey(*account.key, expected_key));
+        }
+
+        let space = Self::required_account_size(CHAIN_ID_LIST.len());
+        let seeds: &[&[u8]] = &[&[ACCOUNT_SEED_VERSION], b"ChainRegistry", &[bump_seed]];
+        system.create_pda_account(program_id, payer, &account, seeds, space)?;
+
+        super::set_tag(program_id, &account, TAG_CHAIN_REGISTRY)?;
+
+        let mut registry = Self { account };
+        for (chain, (id, name, token, spec)) in registry.chains_mut().iter_mut().zip(CHAIN_ID_LIST)
+        {
+            *chain = Chain::new(id, name, token, spec)?;
+        }
+
+        Ok(registry)
+    }
+
+    #[must_use]
+    pub fn chains(&self) -> Ref<[Chain]> {
+        let data = self.account.data.borrow();
+        let data = Ref::map(data, |d| &d[CHAINS_OFFSET..]);
+
+        Ref::map(data, |bytes| {
+            static_assertions::assert_eq_align!(Chain, u8);
+            assert_eq!(bytes.len() % size_of::<Chain>(), 0);
+
+            // SAFETY: Chain has the same alignment as bytes
+            unsafe {
+                let ptr = bytes.as_ptr().cast::<Chain>();
+                let len = bytes.len() / size_of::<Chain>();
+                std::slice::from_raw_parts(ptr, len)
+            }
+        })
+    }
+
+    #[must_use]
+    fn chains_mut(&mut self) -> RefMut<[Chain]> {
+        let data = self.account.data.borrow_mut();
+        let data = RefMut::map(data, |d| &mut d[CHAINS_OFFSET..]);
+
+        RefMut::map(data, |bytes| {
+            static_assertions::assert_eq_align!(Chain, u8);
+            assert_eq!(bytes.len() % size_of::<Chain>(), 0);
+
+            // SAFETY: Chain has the same alignment as bytes
+            unsafe {
+                let ptr = bytes.as_mut_ptr().cast::<Chain>();
+                let len = bytes.len() / size_of::<Chain>();
+                std::slice::from_raw_parts_mut(ptr, len)
+            }
+        })
+    }
+
+    /// Overwrites the registry with `chains`, they must be sorted by id
+    #[cfg(test)]
+    pub fn set_chains(&mut self, chains: &[Chain]) {
+        self.chains_mut().copy_from_slice(chains);
+    }
+
+    /// Enabled chain with `chain_id`
+    #[must_use]
+    pub fn find(&self, chain_id: u64) -> Option<Chain> {
+        let chains = self.chains();
+        let index = chains.binary_search_by_key(&chain_id, |c| c.id).ok()?;
+
+        Some(chains[index]).filter(|c| c.enabled)
+    }
+
+    /// Adds a new chain or enables the disabled one with the same token.
+    /// Chains are kept sorted by id.
+    pub fn add(&mut self, chain: Chain, system: &System<'a>, payer: &Operator<'a>) -> Result<()> {
+        let chain_id = chain.id;
+        let search = self.chains().binary_search_by_key(&chain_id, |c| c.id);
+        let index = match search {
+            Ok(index) => {
+                let mut chains = self.chains_mut();
+                let existing = &mut chains[index];
+                if existing.enabled || (existing.token != chain.token) {
+                    return Err(Error::Custom(format!("Chain {chain_id} already exists")));
+                }
+
+                *existing = chain;
+                return Ok(());
+            }
+            Err(index) => index,
+        };
+
+        let old_len = self.account.data_len();
+        let new_len = old_len + size_of::<Chain>();
+        self.account.realloc(new_len, false)?;
+
+        let minimum_balance = Rent::get()?.minimum_balance(new_len);
+        if self.account.lamports() < minimum_balance {
+            let lamports = minimum_balance - self.account.lamports();
+            system.transfer(payer, &self.account, lamports)?;
+        }
+
+        let offset = CHAINS_OFFSET + index * size_of::<Chain>();
+        {
+            let mut data = self.account.data.borrow_mut();
+            data.copy_within(offset..old_len, offset + size_of::<Chain>());
+        }
+
+        self.chains_mut()[index] = chain;
+
+        Ok(())
+    }
+
+    pub fn disable(&mut self, chain_id: u64) -> Result<()> {
+        if chain_id == DEFAULT_CHAIN_ID {
+            return Err(Error::Custom(format!(
+                "Default chain {chain_id} can't be disabled"
+            )));
+        }
+
+        let mut chains = self.chains_mut();
+        let index = chains
+            .binary_search_by_key(&chain_id, |c| c.id)
+            .map_err(|_| Error::InvalidChainId(chain_id))?;
+
+        chains[index].enabled = false;
+
+        Ok(())
+    }
+}
+
+/// Token mint and EVM spec of the enabled chain.
+/// Uses chains compiled into the program if the registry is not created.
+pub fn find_chain(
+    registry: Option<&ChainRegistry>,
+    chain_id: u64,
+) -> Result<Option<(Pubkey, Spec)>> {
+    let Some(registry) = registry else {
+        let chain = CHAIN_ID_LIST
+            .binary_search_by_key(&chain_id, |c| c.0)
+            .ok()
+            .map(|index| (CHAIN_ID_LIST[index].2, CHAIN_ID_LIST[index].3));
+
+        return Ok(chain);
+    };
+
+    registry
+        .find(chain_id)
+        .map(|chain| Ok((chain.token, chain.spec()?)))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::{AccountsDB, TAG_CHAIN_REGISTRY};
+
+    fn accounts_db<'a>(
+        operator: &'a AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+    ) -> AccountsDB<'a> {
+        AccountsDB::new(accounts, Operator { info: operator }, None, None, None)
+    }
+
+    #[test]
+    fn registry_must_be_passed() {
+        let program_id = crate::ID;
+        let (registry_key, _) = ChainRegistry::address(&program_id);
+
+        let operator_key = Pubkey::new_unique();
+        let (mut operator_lamports, mut operator_data) = (0, []);
+        let operator = AccountInfo::new(
+            &operator_key,
+            true,
+            true,
+            &mut operator_lamports,
+            &mut operator_data,
+            &system_program::ID,
+            false,
+            0,
+        );
+
+        let accounts = accounts_db(&operator, &[]);
+        assert!(matches!(
+            ChainRegistry::from_accounts_db(&program_id, &accounts),
+            Err(Error::ChainRegistryNotFound(key)) if key == registry_key
+        ));
+
+        // Not created yet, chains compiled into the program are used
+        let (mut lamports, mut data) = (0, []);
+        let registry = AccountInfo::new(
+            &registry_key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &system_program::ID,
+            false,
+            0,
+        );
+        let accounts = accounts_db(&operator, &[registry]);
+        let registry = ChainRegistry::from_accounts_db(&program_id, &accounts).unwrap();
+        assert!(registry.is_none());
+
+        let (token, _) = find_chain(None, DEFAULT_CHAIN_ID).unwrap().unwrap();
+        assert_eq!(token, CHAIN_ID_LIST[0].2);
+    }
+
+    #[test]
+    fn unknown_spec_is_an_error() {
+        let program_id = crate::ID;
+        let (registry_key, _) = ChainRegistry::address(&program_id);
+
+        let mut lamports = 0;
+        let mut data = vec![0_u8; ChainRegistry::required_account_size(2)];
+        data[0] = TAG_CHAIN_REGISTRY;
+        let info = AccountInfo::new(
+            &registry_key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+
+        let mut registry = ChainRegistry::from_account(&program_id, info).unwrap();
+        {
+            let mut chains = registry.chains_mut();
+            chains[0] = Chain::new(1, "one", Pubkey::new_unique(), Spec::Cancun).unwrap();
+            chains[1] = Chain::new(2, "two", Pubkey::new_unique(), Spec::Cancun).unwrap();
+            chains[1].spec = u8::MAX;
+        }
+
+        let (_, spec) = find_chain(Some(&registry), 1).unwrap().unwrap();
+        assert_eq!(spec, Spec::Cancun);
+        assert!(find_chain(Some(&registry), 2).is_err());
+        assert!(find_chain(Some(&registry), 3).unwrap().is_none());
+
+        registry.disable(1).unwrap();
+        assert!(find_chain(Some(&registry), 1).unwrap().is_none());
+    }
+}
@@ -211,6 +211,16 @@ impl<'a> ContractAccount<'a> {
         storage[index]
     }
 
+    /// Contract without code and with zero storage, the account can be closed.
+    /// Selfdestructed legacy contracts (generation > 0) are kept,
+    /// their generation is compared during the conversion of the remaining legacy storage cells.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        (self.generation() == 0)
+            && (self.code_len() == 0)
+            && self.storage().iter().all(|value| value == &[0_u8; 32])
+    }
+
     pub fn set_storage_value(&mut self, index: usize, value: &[u8; 32]) {
         assert!(index < STORAGE_ENTRIES_IN_CONTRACT_ACCOUNT);
 
@@ -176,6 +176,12 @@ impl<'a> StorageCell<'a> {
         [0_u8; 32]
     }
 
+    /// All cells are zero, the account can be closed
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells().iter().all(|cell| cell.value == [0_u8; 32])
+    }
+
     pub fn update(&mut self, subindex: u8, value: &[u8; 32]) -> Result<()> {
         // todo: if value is zero - destroy cell
 
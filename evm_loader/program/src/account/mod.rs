@@ -6,18 +6,21 @@ use std::cell::{Ref, RefMut};
 
 pub use crate::config::ACCOUNT_SEED_VERSION;
 
+pub use chain_registry::{find_chain, Chain, ChainRegistry};
 pub use ether_balance::BalanceAccount;
 pub use ether_contract::{AllocateResult, ContractAccount};
 pub use ether_storage::{StorageCell, StorageCellAddress};
 pub use holder::Holder;
 pub use incinerator::Incinerator;
 pub use operator::Operator;
+pub use operator_registry::{is_authorized_operator, OperatorEntry, OperatorRegistry};
 pub use state::StateAccount;
 pub use state_finalized::StateFinalizedAccount;
 pub use treasury::{MainTreasury, Treasury};
 
 use self::program::System;
 
+mod chain_registry;
 mod ether_balance;
 mod ether_contract;
 mod ether_storage;
@@ -25,6 +28,7 @@ mod holder;
 mod incinerator;
 pub mod legacy;
 mod operator;
+mod operator_registry;
 pub mod program;
 mod state;
 mod state_finalized;
@@ -39,6 +43,8 @@ pub const TAG_HOLDER: u8 = 52;
 pub const TAG_ACCOUNT_BALANCE: u8 = 60;
 pub const TAG_ACCOUNT_CONTRACT: u8 = 70;
 pub const TAG_STORAGE_CELL: u8 = 43;
+pub const TAG_CHAIN_REGISTRY: u8 = 80;
+pub const TAG_OPERATOR_REGISTRY: u8 = 81;
 
 const ACCOUNT_PREFIX_LEN: usize = 2;
 
@@ -145,6 +151,13 @@ fn set_block(program_id: &Pubkey, info: &AccountInfo, block: bool) -> Result<()>
     Ok(())
 }
 
+/// Registries are only read by transactions, they are never part of the blocked accounts list
+#[must_use]
+pub fn is_registry(program_id: &Pubkey, key: &Pubkey) -> bool {
+    (*key == ChainRegistry::address(program_id).0)
+        || (*key == OperatorRegistry::address(program_id).0)
+}
+
 pub fn block(program_id: &Pubkey, info: &AccountInfo) -> Result<()> {
     set_block(program_id, info, true)
 }
@@ -165,8 +178,24 @@ pub unsafe fn delete(account: &AccountInfo, operator: &Operator) {
     data.fill(0);
 }
 
+/// Close the account and transfer its lamports to `recipient`.
+/// The account is returned to the System Program and deleted at the end of the transaction.
+pub fn close(account: &AccountInfo, recipient: &AccountInfo) -> Result<()> {
+    debug_print!("CLOSE ACCOUNT {}", account.key);
+
+    let lamports = account.lamports();
+    **recipient.try_borrow_mut_lamports()? += lamports;
+    **account.try_borrow_mut_lamports()? = 0;
+
+    account.realloc(0, false)?;
+    account.assign(&solana_program::system_program::ID);
+
+    Ok(())
+}
+
 pub struct AccountsDB<'a> {
     sorted_accounts: Vec<AccountInfo<'a>>,
+    chain_registry: Option<AccountInfo<'a>>,
     operator: Operator<'a>,
     operator_balance: Option<BalanceAccount<'a>>,
     system: Option<System<'a>>,
@@ -182,12 +211,25 @@ impl<'a> AccountsDB<'a> {
         system: Option<System<'a>>,
         treasury: Option<Treasury<'a>>,
     ) -> Self {
-        let mut sorted_accounts = accounts.to_vec();
+        // Registries are kept out of the transaction accounts, so the State account never lists them.
+        // Transactions started before the registries were introduced can be continued and canceled.
+        let (chain_registry_key, _) = ChainRegistry::address(&crate::ID);
+        let chain_registry = accounts
+            .iter()
+            .find(|a| *a.key == chain_registry_key)
+            .cloned();
+
+        let mut sorted_accounts: Vec<AccountInfo<'a>> = accounts
+            .iter()
+            .filter(|a| !is_registry(&crate::ID, a.key))
+            .cloned()
+            .collect();
         sorted_accounts.sort_unstable_by_key(|a| a.key);
         sorted_accounts.dedup_by_key(|a| a.key);
 
         Self {
             sorted_accounts,
+            chain_registry,
             operator,
             operator_balance,
             system,
@@ -251,6 +293,21 @@ impl<'a> AccountsDB<'a> {
         &self.operator
     }
 
+    #[must_use]
+    pub fn chain_registry(&self) -> Option<&AccountInfo<'a>> {
+        self.chain_registry.as_ref()
+    }
+
+    #[must_use]
+    pub fn find(&self, pubkey: &Pubkey) -> Option<&AccountInfo<'a>> {
+        let index = self
+            .sorted_accounts
+            .binary_search_by_key(&pubkey, |a| a.key)
+            .ok()?;
+
+        self.sorted_accounts.get(index)
+    }
+
     #[must_use]
     pub fn get(&self, pubkey: &Pubkey) -> &AccountInfo<'a> {
         let Ok(index) = self.sorted_accounts.binary_search_by_key(&pubkey, |a| a.key) else {
@@ -1,6 +1,7 @@
 use crate::error::Error;
 use solana_program::account_info::AccountInfo;
 use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
 use std::ops::Deref;
 
 #[derive(Clone)]
@@ -9,10 +10,14 @@ pub struct Operator<'a> {
 }
 
 impl<'a> Operator<'a> {
-    pub fn from_account(info: &'a AccountInfo<'a>) -> Result<Self, ProgramError> {
-        let is_authorized = crate::config::AUTHORIZED_OPERATOR_LIST
-            .binary_search(info.key)
-            .is_ok();
+    /// Operator authorized by the Operator Registry passed among `accounts`
+    pub fn from_account(
+        program_id: &Pubkey,
+        info: &'a AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+    ) -> Result<Self, ProgramError> {
+        let registry = super::OperatorRegistry::from_instruction_accounts(program_id, accounts)?;
+        let is_authorized = super::is_authorized_operator(registry.as_ref(), info.key);
 
         if !is_authorized {
             return Err!(Error::UnauthorizedOperator.into(); "Account {} - expected authorized operator", info.key);
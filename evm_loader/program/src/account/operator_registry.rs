@@ -0,0 +1,358 @@
+use std::cell::{Ref, RefMut};
+use std::mem::size_of;
+
+use crate::config::AUTHORIZED_OPERATOR_LIST;
+use crate::error::{Error, Result};
+use solana_program::{
+    account_info::AccountInfo, pubkey::Pubkey, rent::Rent, system_program, sysvar::Sysvar,
+};
+
+use super::program::System;
+use super::{Operator, ACCOUNT_PREFIX_LEN, ACCOUNT_SEED_VERSION, TAG_OPERATOR_REGISTRY};
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
+pub struct OperatorEntry {
+    pub key: Pubkey,
+    pub suspended: bool,
+}
+
+/// Operators managed by the program upgrade authority after deployment.
+/// Until the registry is created, operators compiled into the program are authorized.
+pub struct OperatorRegistry<'a> {
+    account: AccountInfo<'a>,
+}
+
+const OPERATORS_OFFSET: usize = ACCOUNT_PREFIX_LEN;
+
+impl<'a> OperatorRegistry<'a> {
+    #[must_use]
+    pub fn address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[&[ACCOUNT_SEED_VERSION], b"OperatorRegistry"], program_id)
+    }
+
+    #[must_use]
+    pub fn required_account_size(operators: usize) -> usize {
+        OPERATORS_OFFSET + operators * size_of::<OperatorEntry>()
+    }
+
+    pub fn from_account(program_id: &Pubkey, account: AccountInfo<'a>) -> Result<Self> {
+        super::validate_tag(program_id, &account, TAG_OPERATOR_REGISTRY)?;
+
+        Ok(Self { account })
+    }
+
+    /// Registry account, `None` if it is not created yet
+    pub fn load(program_id: &Pubkey, account: AccountInfo<'a>) -> Result<Option<Self>> {
+        if system_program::check_id(account.owner) {
+            return Ok(None);
+        }
+
+        Self::from_account(program_id, account).map(Some)
+    }
+
+    /// Registry from the instruction accounts, `None` if it is not created yet.
+    /// The registry account must always be passed, otherwise revoked operators
+    /// could skip the check by omitting it.
+    pub fn from_instruction_accounts(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo<'a>],
+    ) -> Result<Option<Self>> {
+        let (pubkey, _) = Self::address(program_id);
+
+        let Some(account) = accounts.iter().find(|a| *a.key == pubkey) else {
+            return Err(Error::OperatorRegistryNotFound(pubkey));
+        };
+
+        Self::load(program_id, account.clone())
+    }
+
+    /// Creates the registry with the operators compiled into the program
+    pub fn create(
+        program_id: &Pubkey,
+        account: AccountInfo<'a>,
+        system: &System<'a>,
+        payer: &Operator<'a>,
+    ) -> Result<Self> {
+        let (expected_key, bump_seed) = Self::address(program_id);
+        if *account.key != expected_key {
+            return Err(Error::AccountInvalidKey(*account.key, expected_key));
+        }
+
+        let space = Self::required_account_size(AUTHORIZED_OPERATOR_LIST.len());
+        let seeds: &[&[u8]] = &[&[ACCOUNT_SEED_VERSION], b"OperatorRegistry", &[bump_seed]];
+        system.create_pda_account(program_id, payer, &account, seeds, space)?;
+
+        super::set_tag(program_id, &account, TAG_OPERATOR_REGISTRY)?;
+
+        let mut registry = Self { account };
+        for (entry, key) in registry
+            .operators_mut()
+            .iter_mut()
+            .zip(AUTHORIZED_OPERATOR_LIST)
+        {
+            *entry = OperatorEntry {
+                key,
+                suspended: false,
+            };
+        }
+
+        Ok(registry)
+    }
+
+    #[must_use]
+    pub fn operators(&self) -> Ref<[OperatorEntry]> {
+        let data = self.account.data.borrow();
+        let data = Ref::map(data, |d| &d[OPERATORS_OFFSET..]);
+
+        Ref::map(data, |bytes| {
+            static_assertions::assert_eq_align!(OperatorEntry, u8);
+            assert_eq!(bytes.len() % size_of::<OperatorEntry>(), 0);
+
+            // SAFETY: OperatorEntry has the same alignment as bytes
+            unsafe {
+                let ptr = bytes.as_ptr().cast::<OperatorEntry>();
+                let len = bytes.len() / size_of::<OperatorEntry>();
+                std::slice::from_raw_parts(ptr, len)
+            }
+        })
+    }
+
+    #[must_use]
+    fn operators_mut(&mut self) -> RefMut<[OperatorEntry]> {
+        let data = self.account.data.borrow_mut();
+        let data = RefMut::map(data, |d| &mut d[OPERATORS_OFFSET..]);
+
+        RefMut::map(data, |bytes| {
+            static_assertions::assert_eq_align!(OperatorEntry, u8);
+            assert_eq!(bytes.len() % size_of::<OperatorEntry>(), 0);
+
+            // SAFETY: OperatorEntry has the same alignment as bytes
+            unsafe {
+                let ptr = bytes.as_mut_ptr().cast::<OperatorEntry>();
+                let len = bytes.len() / size_of::<OperatorEntry>();
+                std::slice::from_raw_parts_mut(ptr, len)
+            }
+        })
+    }
+
+    fn search(&self, key: &Pubkey) -> std::result::Result<usize, usize> {
+        self.operators().binary_search_by_key(key, |e| e.key)
+    }
+
+    /// Operator is in the registry and not suspended
+    #[must_use]
+    pub fn is_authorized(&self, key: &Pubkey) -> bool {
+        matches!(self.search(key), Ok(index) if !self.operators()[index].suspended)
+    }
+
+    /// Adds a new operator or resumes the suspended one.
+    /// Operators are kept sorted by key.
+    pub fn add(&mut self, key: Pubkey, system: &System<'a>, payer: &Operator<'a>) -> Result<()> {
+        let index = match self.search(&key) {
+            Ok(index) => {
+                let mut operators = self.operators_mut();
+                if !operators[index].suspended {
+                    return Err(Error::Custom(format!("Operator {key} already exists")));
+                }
+
+                operators[index].suspended = false;
+                return Ok(());
+            }
+            Err(index) => index,
+        };
+
+        let old_len = self.account.data_len();
+        let new_len = old_len + size_of::<OperatorEntry>();
+        self.account.realloc(new_len, false)?;
+
+        let minimum_balance = Rent::get()?.minimum_balance(new_len);
+        if self.account.lamports() < minimum_balance {
+            let lamports = minimum_balance - self.account.lamports();
+            system.transfer(payer, &self.account, lamports)?;
+        }
+
+        let offset = OPERATORS_OFFSET + index * size_of::<OperatorEntry>();
+        {
+            let mut data = self.account.data.borrow_mut();
+            data.copy_within(offset..old_len, offset + size_of::<OperatorEntry>());
+        }
+
+        self.operators_mut()[index] = OperatorEntry {
+            key,
+            suspended: false,
+        };
+
+        Ok(())
+    }
+
+    /// Removes the operator, excessive rent stays in the registry account
+    pub fn remove(&mut self, key: &Pubkey) -> Result<()> {
+        let index = self
+            .search(key)
+            .map_err(|_| Error::Custom(format!("Operator {key} not found")))?;
+
+        let old_len = self.account.data_len();
+        let new_len = old_len - size_of::<OperatorEntry>();
+
+        let offset = OPERATORS_OFFSET + index * size_of::<OperatorEntry>();
+        {
+            let mut data = self.account.data.borrow_mut();
+            data.copy_within(offset + size_of::<OperatorEntry>()..old_len, offset);
+        }
+
+        self.account.realloc(new_len, false)?;
+
+        Ok(())
+    }
+
+    /// Suspends the operator until it is added again
+    pub fn suspend(&mut self, key: &Pubkey) -> Result<()> {
+        let index = self
+            .search(key)
+            .map_err(|_| Error::Custom(format!("Operator {key} not found")))?;
+
+        self.operators_mut()[index].suspended = true;
+
+        Ok(())
+    }
+}
+
+/// Checks the operator against the registry.
+/// Uses operators compiled into the program if the registry is not created.
+#[must_use]
+pub fn is_authorized_operator(registry: Option<&OperatorRegistry>, key: &Pubkey) -> bool {
+    if let Some(registry) = registry {
+        return registry.is_authorized(key);
+    }
+
+    AUTHORIZED_OPERATOR_LIST.binary_search(key).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::TAG_OPERATOR_REGISTRY;
+    use crate::account_storage::test_storage::install_syscall_stubs;
+    use solana_program::entrypoint::{deserialize, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER};
+
+    /// Program input with the registry account, leaves room for `realloc` like the runtime does
+    fn serialize(program_id: &Pubkey, operators: &[Pubkey]) -> Vec<u64> {
+        let key = OperatorRegistry::address(program_id).0;
+        let data_len = OperatorRegistry::required_account_size(operators.len());
+
+        let mut input = Vec::new();
+        input.extend_from_slice(&1_u64.to_le_bytes());
+        input.extend_from_slice(&[NON_DUP_MARKER, 0, 1, 0, 0, 0, 0, 0]);
+        input.extend_from_slice(key.as_ref());
+        input.extend_from_slice(program_id.as_ref());
+        input.extend_from_slice(&u64::MAX.to_le_bytes());
+        input.extend_from_slice(&(data_len as u64).to_le_bytes());
+        input.push(TAG_OPERATOR_REGISTRY);
+        input.resize(input.len() + OPERATORS_OFFSET - 1, 0);
+        for operator in operators {
+            input.extend_from_slice(operator.as_ref());
+            input.push(0);
+        }
+        input.resize(input.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+        input.resize(input.len() + (8 - input.len() % 8) % 8, 0);
+        input.extend_from_slice(&0_u64.to_le_bytes());
+        input.extend_from_slice(&0_u64.to_le_bytes());
+        input.extend_from_slice(program_id.as_ref());
+
+        input
+            .chunks(8)
+            .map(|chunk| {
+                let mut word = [0_u8; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u64::from_le_bytes(word)
+            })
+            .collect()
+    }
+
+    fn keys(registry: &OperatorRegistry) -> Vec<(Pubkey, bool)> {
+        registry
+            .operators()
+            .iter()
+            .map(|entry| (entry.key, entry.suspended))
+            .collect()
+    }
+
+    #[test]
+    fn operators_are_sorted() {
+        install_syscall_stubs();
+
+        let program_id = crate::ID;
+        let mut k = [(); 4].map(|()| Pubkey::new_unique());
+        k.sort();
+        let [k0, k1, k2, k3] = k;
+
+        let mut input = serialize(&program_id, &[k1, k3]);
+        // SAFETY: input is aligned and follows the runtime serialization format
+        let (_, accounts, _) = unsafe { deserialize(input.as_mut_ptr().cast()) };
+        let mut registry =
+            OperatorRegistry::from_account(&program_id, accounts[0].clone()).unwrap();
+
+        let (mut lamports, mut data) = (0, []);
+        let system_info = AccountInfo::new(
+            &system_program::ID,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &system_program::ID,
+            true,
+            0,
+        );
+        let system = System::from_account(&system_info).unwrap();
+        let payer_key = Pubkey::new_unique();
+        let (mut payer_lamports, mut payer_data) = (0, []);
+        let payer_info = AccountInfo::new(
+            &payer_key,
+            true,
+            true,
+            &mut payer_lamports,
+            &mut payer_data,
+            &system_program::ID,
+            false,
+            0,
+        );
+        let payer = Operator { info: &payer_info };
+
+        registry.add(k2, &system, &payer).unwrap();
+        registry.add(k0, &system, &payer).unwrap();
+        assert_eq!(
+            keys(&registry),
+            vec![(k0, false), (k1, false), (k2, false), (k3, false)]
+        );
+        assert!(registry.add(k2, &system, &payer).is_err());
+
+        registry.suspend(&k1).unwrap();
+        assert!(!registry.is_authorized(&k1));
+        assert!(registry.is_authorized(&k2));
+        assert!(!is_authorized_operator(Some(&registry), &k1));
+
+        // Suspended operator is resumed in place
+        registry.add(k1, &system, &payer).unwrap();
+        assert!(registry.is_authorized(&k1));
+
+        registry.remove(&k2).unwrap();
+        registry.remove(&k0).unwrap();
+        assert_eq!(keys(&registry), vec![(k1, false), (k3, false)]);
+        assert_eq!(
+            accounts[0].data_len(),
+            OperatorRegistry::required_account_size(2)
+        );
+        assert!(!registry.is_authorized(&k0));
+        assert!(registry.remove(&k0).is_err());
+        assert!(registry.suspend(&k0).is_err());
+    }
+
+    #[test]
+    fn compiled_operators_without_registry() {
+        for operator in &AUTHORIZED_OPERATOR_LIST {
+            assert!(is_authorized_operator(None, operator));
+        }
+        assert!(!is_authorized_operator(None, &Pubkey::new_unique()));
+    }
+}
@@ -1,7 +1,7 @@
 use std::cell::{Ref, RefMut};
 use std::mem::size_of;
 
-use crate::config::GAS_LIMIT_MULTIPLIER_NO_CHAINID;
+use crate::config::{GAS_LIMIT_MULTIPLIER_NO_CHAINID, OPERATOR_PRIORITY_SLOTS};
 use crate::error::{Error, Result};
 use crate::types::{Address, Transaction};
 use ethnum::U256;
@@ -32,7 +32,8 @@ pub struct Header {
     pub gas_used: U256,
     /// Operator public key
     pub operator: Pubkey,
-    /// Starting slot for this operator
+    /// Slot of the last step made by this operator.
+    /// After `OPERATOR_PRIORITY_SLOTS` without progress any operator can take over the transaction.
     pub slot: u64,
     /// Stored accounts length
     pub accounts_len: usize,
@@ -144,7 +145,7 @@ impl<'a> StateAccount<'a> {
             }
         }
 
-        state.update_current_operator(&accounts.operator);
+        state.update_current_operator(&accounts.operator)?;
 
         Ok(state)
     }
@@ -172,6 +173,82 @@ impl<'a> StateAccount<'a> {
         super::set_tag(account.owner, &account, TAG_STATE_FINALIZED)
     }
 
+    /// Adds accounts missed by the emulation to the transaction in progress.
+    /// Blocked accounts stay sorted by key, the EVM state buffer is moved to make room for them.
+    pub fn add_blocked_accounts(
+        &mut self,
+        program_id: &Pubkey,
+        operator: &Operator,
+        accounts: &[AccountInfo<'a>],
+    ) -> Result<()> {
+        self.update_current_operator(operator)?;
+
+        let mut blocked_accounts: Vec<(Pubkey, bool, bool)> = self
+            .blocked_accounts()
+            .iter()
+            .map(|block| (block.key, block.is_writable, block.blocked))
+            .collect();
+        let old_len = blocked_accounts.len();
+
+        for account in accounts {
+            if super::is_registry(program_id, account.key) {
+                continue;
+            }
+
+            if account.key == self.account.key {
+                return Err(Error::Custom(format!(
+                    "State account {} can't be blocked",
+                    account.key
+                )));
+            }
+
+            match blocked_accounts.binary_search_by_key(account.key, |(key, _, _)| *key) {
+                Ok(index) => {
+                    blocked_accounts[index].1 |= account.is_writable;
+                }
+                Err(index) => {
+                    let blocked = (account.owner == program_id) && !account.data_is_empty();
+                    if blocked {
+                        super::block(program_id, account)?;
+                    }
+
+                    blocked_accounts.insert(index, (*account.key, account.is_writable, blocked));
+                }
+            }
+        }
+
+        let new_len = blocked_accounts.len();
+        if new_len > old_len {
+            let (evm_state_len, evm_machine_len) = self.buffer_variables();
+            let buffer_len = evm_state_len + evm_machine_len;
+
+            let old_offset = BLOCKED_ACCOUNTS_OFFSET + old_len * size_of::<BlockedAccount>();
+            let new_offset = BLOCKED_ACCOUNTS_OFFSET + new_len * size_of::<BlockedAccount>();
+
+            let required_len = new_offset + buffer_len;
+            if self.account.data_len() < required_len {
+                return Err(Error::HolderInsufficientSize(
+                    self.account.data_len(),
+                    required_len,
+                ));
+            }
+
+            let mut data = self.account.data.borrow_mut();
+            data.copy_within(old_offset..old_offset + buffer_len, new_offset);
+        }
+
+        self.header_mut().accounts_len = new_len;
+        for (block, (key, is_writable, blocked)) in
+            self.blocked_accounts_mut().iter_mut().zip(blocked_accounts)
+        {
+            block.key = key;
+            block.is_writable = is_writable;
+            block.blocked = blocked;
+        }
+
+        Ok(())
+    }
+
     #[inline]
     #[must_use]
     fn header(&self) -> Ref<Header> {
@@ -270,9 +347,24 @@ impl<'a> StateAccount<'a> {
         Ok(())
     }
 
-    fn update_current_operator(&mut self, operator: &Operator) {
+    /// The operator which started the transaction has priority to continue or cancel it.
+    /// Other operators can take it over once it is abandoned for `OPERATOR_PRIORITY_SLOTS`.
+    fn update_current_operator(&mut self, operator: &Operator) -> Result<()> {
+        let slot = Clock::get()?.slot;
+
         let mut header = self.header_mut();
-        header.operator = *operator.key;
+        if header.operator != *operator.key {
+            let timeout_slot = header.slot.saturating_add(OPERATOR_PRIORITY_SLOTS);
+            if slot < timeout_slot {
+                return Err(Error::OperatorPriority(header.operator, timeout_slot));
+            }
+
+            header.operator = *operator.key;
+        }
+
+        header.slot = slot;
+
+        Ok(())
     }
 
     #[must_use]
@@ -360,3 +452,355 @@ impl<'a> StateAccount<'a> {
         header.gas_limit = header.gas_limit.saturating_mul(gas_multiplier);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::{AccountsDB, ChainRegistry, OperatorRegistry};
+    use crate::account_storage::test_storage::{install_syscall_stubs, set_clock_slot};
+    use solana_program::system_program;
+
+    const BUFFER: [u8; 5] = [1, 2, 3, 4, 5];
+
+    fn state_data(blocked_accounts: &[(Pubkey, bool)], room: usize) -> Vec<u8> {
+        let accounts_len_bytes = blocked_accounts.len() * size_of::<BlockedAccount>();
+        let mut data = vec![0_u8; BLOCKED_ACCOUNTS_OFFSET + accounts_len_bytes + BUFFER.len()];
+        data.resize(data.len() + room * size_of::<BlockedAccount>(), 0);
+        data[0] = TAG_STATE;
+
+        data
+    }
+
+    fn init_state(
+        state: &mut StateAccount,
+        operator: &Pubkey,
+        slot: u64,
+        blocked_accounts: &[(Pubkey, bool)],
+    ) {
+        {
+            let mut header = state.header_mut();
+            header.owner = *operator;
+            header.operator = *operator;
+            header.slot = slot;
+            header.accounts_len = blocked_accounts.len();
+        }
+
+        for (block, (key, is_writable)) in state
+            .blocked_accounts_mut()
+            .iter_mut()
+            .zip(blocked_accounts)
+        {
+            block.key = *key;
+            block.is_writable = *is_writable;
+            block.blocked = false;
+        }
+
+        state.set_buffer_variables(3, 2);
+        state.buffer_mut()[..BUFFER.len()].copy_from_slice(&BUFFER);
+    }
+
+    fn blocked_accounts(state: &StateAccount) -> Vec<(Pubkey, bool)> {
+        state
+            .blocked_accounts()
+            .iter()
+            .map(|block| (block.key, block.is_writable))
+            .collect()
+    }
+
+    fn sorted_keys<const N: usize>() -> [Pubkey; N] {
+        let mut keys = [(); N].map(|()| Pubkey::new_unique());
+        keys.sort();
+        keys
+    }
+
+    fn system_account<'a>(
+        key: &'a Pubkey,
+        is_writable: bool,
+        lamports: &'a mut u64,
+        data: &'a mut [u8],
+    ) -> AccountInfo<'a> {
+        AccountInfo::new(
+            key,
+            false,
+            is_writable,
+            lamports,
+            data,
+            &system_program::ID,
+            false,
+            0,
+        )
+    }
+
+    fn state_account<'a>(
+        key: &'a Pubkey,
+        lamports: &'a mut u64,
+        data: &'a mut [u8],
+    ) -> AccountInfo<'a> {
+        AccountInfo::new(key, false, true, lamports, data, &crate::ID, false, 0)
+    }
+
+    fn add<'a>(
+        state_info: &AccountInfo<'a>,
+        operator: &'a AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+    ) -> Result<()> {
+        let mut state = StateAccount::from_account(&crate::ID, state_info.clone())?;
+        state.add_blocked_accounts(&crate::ID, &Operator { info: operator }, accounts)
+    }
+
+    /// Restores the state to cancel the transaction, returns the current operator
+    fn restore<'a>(
+        state_info: &AccountInfo<'a>,
+        operator: &'a AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+    ) -> Result<Pubkey> {
+        let accounts = AccountsDB::new(accounts, Operator { info: operator }, None, None, None);
+        let state = StateAccount::restore(&crate::ID, state_info.clone(), &accounts, true)?;
+        let operator = state.header().operator;
+
+        Ok(operator)
+    }
+
+    #[test]
+    fn add_blocked_accounts_keeps_order_and_buffer() {
+        install_syscall_stubs();
+
+        let program_id = crate::ID;
+        let [k0, k1, k2, k3, k4] = sorted_keys();
+        let existing = [(k1, false), (k3, true)];
+
+        let state_key = Pubkey::new_unique();
+        let (mut state_lamports, mut state_data) = (0, state_data(&existing, 3));
+        let state_info = state_account(&state_key, &mut state_lamports, &mut state_data);
+
+        let operator_key = Pubkey::new_unique();
+        let (mut operator_lamports, mut operator_data) = (0, []);
+        let operator_info = system_account(
+            &operator_key,
+            true,
+            &mut operator_lamports,
+            &mut operator_data,
+        );
+        let operator = Operator {
+            info: &operator_info,
+        };
+
+        let mut state = StateAccount::from_account(&program_id, state_info.clone()).unwrap();
+        init_state(&mut state, &operator_key, 0, &existing);
+
+        let mut lamports = [0_u64; 4];
+        let mut data: [[u8; 0]; 4] = [[]; 4];
+        let added = [(k4, false), (k1, true), (k2, true), (k0, false)];
+        let accounts: Vec<AccountInfo> = added
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|(((key, is_writable), lamports), data)| {
+                system_account(key, *is_writable, lamports, data)
+            })
+            .collect();
+
+        state
+            .add_blocked_accounts(&program_id, &operator, &accounts)
+            .unwrap();
+
+        // k1 is upgraded to writable, new keys are merged in order
+        assert_eq!(
+            blocked_accounts(&state),
+            vec![(k0, false), (k1, true), (k2, true), (k3, true), (k4, false)]
+        );
+        assert_eq!(state.buffer_variables(), (3, 2));
+        assert_eq!(state.buffer()[..BUFFER.len()], BUFFER);
+
+        // Adding known accounts doesn't move the buffer
+        state
+            .add_blocked_accounts(&program_id, &operator, &accounts[..2])
+            .unwrap();
+        assert_eq!(blocked_accounts(&state).len(), 5);
+        assert_eq!(state.buffer()[..BUFFER.len()], BUFFER);
+
+        // The state account itself can't be blocked
+        assert!(state
+            .add_blocked_accounts(&program_id, &operator, &[state_info])
+            .is_err());
+    }
+
+    #[test]
+    fn add_blocked_accounts_insufficient_size() {
+        install_syscall_stubs();
+
+        let program_id = crate::ID;
+        let [k0, k1] = sorted_keys();
+        let existing = [(k1, false)];
+
+        let state_key = Pubkey::new_unique();
+        let (mut state_lamports, mut state_data) = (0, state_data(&existing, 0));
+        let state_len = state_data.len();
+        let state_info = state_account(&state_key, &mut state_lamports, &mut state_data);
+
+        let operator_key = Pubkey::new_unique();
+        let (mut operator_lamports, mut operator_data) = (0, []);
+        let operator_info = system_account(
+            &operator_key,
+            true,
+            &mut operator_lamports,
+            &mut operator_data,
+        );
+        let operator = Operator {
+            info: &operator_info,
+        };
+
+        let mut state = StateAccount::from_account(&program_id, state_info).unwrap();
+        init_state(&mut state, &operator_key, 0, &existing);
+
+        let (mut lamports, mut data) = (0, []);
+        let account = system_account(&k0, true, &mut lamports, &mut data);
+
+        let result = state.add_blocked_accounts(&program_id, &operator, &[account]);
+        assert!(matches!(
+            result,
+            Err(Error::HolderInsufficientSize(len, required))
+                if (len == state_len) && (required == state_len + size_of::<BlockedAccount>())
+        ));
+
+        // Nothing is changed
+        assert_eq!(blocked_accounts(&state), vec![(k1, false)]);
+        assert_eq!(state.buffer()[..BUFFER.len()], BUFFER);
+    }
+
+    #[test]
+    fn operator_priority() {
+        const START_SLOT: u64 = 100;
+
+        install_syscall_stubs();
+
+        let program_id = crate::ID;
+        let [k0, k1] = sorted_keys();
+        let existing = [(k1, true)];
+
+        let state_key = Pubkey::new_unique();
+        let (mut state_lamports, mut state_data) = (0, state_data(&existing, 1));
+        let state_info = state_account(&state_key, &mut state_lamports, &mut state_data);
+
+        let [first_key, second_key] = sorted_keys();
+        let (mut first_lamports, mut first_data) = (0, []);
+        let first_info = system_account(&first_key, true, &mut first_lamports, &mut first_data);
+        let (mut second_lamports, mut second_data) = (0, []);
+        let second_info = system_account(&second_key, true, &mut second_lamports, &mut second_data);
+
+        let (mut lamports, mut data) = ([0_u64; 2], [[]; 2]);
+        let [lamports0, lamports1] = &mut lamports;
+        let [data0, data1] = &mut data;
+        let missed = system_account(&k0, true, lamports0, data0);
+        let blocked = system_account(&k1, true, lamports1, data1);
+
+        let mut state = StateAccount::from_account(&program_id, state_info.clone()).unwrap();
+        init_state(&mut state, &first_key, START_SLOT, &existing);
+        drop(state);
+
+        // The last slot of the priority window
+        let timeout_slot = START_SLOT + OPERATOR_PRIORITY_SLOTS;
+        set_clock_slot(timeout_slot - 1);
+        assert!(matches!(
+            add(&state_info, &second_info, std::slice::from_ref(&missed)),
+            Err(Error::OperatorPriority(operator, slot))
+                if (operator == first_key) && (slot == timeout_slot)
+        ));
+        assert!(matches!(
+            restore(&state_info, &second_info, std::slice::from_ref(&blocked)),
+            Err(Error::OperatorPriority(..))
+        ));
+
+        // Progress of the first operator extends the window
+        add(&state_info, &first_info, std::slice::from_ref(&missed)).unwrap();
+        set_clock_slot(timeout_slot);
+        assert!(add(&state_info, &second_info, std::slice::from_ref(&missed)).is_err());
+
+        // Abandoned transaction is canceled by another operator
+        let accounts = [missed, blocked];
+        set_clock_slot(timeout_slot - 1 + OPERATOR_PRIORITY_SLOTS);
+        assert_eq!(
+            restore(&state_info, &second_info, &accounts).unwrap(),
+            second_key
+        );
+
+        // Now the first operator has to wait
+        assert!(matches!(
+            add(&state_info, &first_info, &accounts),
+            Err(Error::OperatorPriority(operator, _)) if operator == second_key
+        ));
+    }
+
+    #[test]
+    fn registries_are_not_blocked() {
+        install_syscall_stubs();
+        set_clock_slot(0);
+
+        let program_id = crate::ID;
+        let [k0, k1] = sorted_keys();
+        // Transaction started before the registries were introduced
+        let existing = [(k1, true)];
+
+        let state_key = Pubkey::new_unique();
+        let (mut state_lamports, mut state_data) = (0, state_data(&existing, 1));
+        let state_info = state_account(&state_key, &mut state_lamports, &mut state_data);
+
+        let operator_key = Pubkey::new_unique();
+        let (mut operator_lamports, mut operator_data) = (0, []);
+        let operator_info = system_account(
+            &operator_key,
+            true,
+            &mut operator_lamports,
+            &mut operator_data,
+        );
+
+        let mut state = StateAccount::from_account(&program_id, state_info.clone()).unwrap();
+        init_state(&mut state, &operator_key, 0, &existing);
+        drop(state);
+
+        let (chain_registry_key, _) = ChainRegistry::address(&program_id);
+        let (operator_registry_key, _) = OperatorRegistry::address(&program_id);
+
+        let (mut lamports, mut data) = ([0_u64; 4], [[]; 4]);
+        let [lamports0, lamports1, lamports2, lamports3] = &mut lamports;
+        let [data0, data1, data2, data3] = &mut data;
+        let missed = system_account(&k0, true, lamports0, data0);
+        let blocked = system_account(&k1, true, lamports1, data1);
+        let chain_registry = system_account(&chain_registry_key, false, lamports2, data2);
+        let operator_registry = system_account(&operator_registry_key, false, lamports3, data3);
+
+        let accounts = [
+            blocked.clone(),
+            chain_registry.clone(),
+            operator_registry.clone(),
+        ];
+        assert_eq!(
+            restore(&state_info, &operator_info, &accounts).unwrap(),
+            operator_key
+        );
+
+        // Registries passed to the block instruction are skipped
+        add(
+            &state_info,
+            &operator_info,
+            &[operator_registry, missed.clone(), chain_registry],
+        )
+        .unwrap();
+
+        let state = StateAccount::from_account(&program_id, state_info.clone()).unwrap();
+        assert_eq!(blocked_accounts(&state), vec![(k0, true), (k1, true)]);
+        drop(state);
+
+        let accounts = AccountsDB::new(
+            &[missed, blocked],
+            Operator {
+                info: &operator_info,
+            },
+            None,
+            None,
+            None,
+        );
+        assert!(accounts.chain_registry().is_none());
+        StateAccount::restore(&program_id, state_info, &accounts, true).unwrap();
+    }
+}
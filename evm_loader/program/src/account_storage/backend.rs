@@ -1,6 +1,8 @@
+use crate::account::find_chain;
 use crate::account_storage::{AccountStorage, ProgramAccountStorage};
 use crate::config::STORAGE_ENTRIES_IN_CONTRACT_ACCOUNT;
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::evm::Spec;
 use crate::executor::OwnedAccountInfo;
 use crate::types::Address;
 use ethnum::U256;
@@ -46,17 +48,23 @@ impl<'a> AccountStorage for ProgramAccountStorage<'a> {
     }
 
     fn is_valid_chain_id(&self, chain_id: u64) -> bool {
-        crate::config::CHAIN_ID_LIST
-            .binary_search_by_key(&chain_id, |c| c.0)
-            .is_ok()
+        // Chains with unknown EVM spec are not valid
+        matches!(
+            find_chain(self.chain_registry.as_ref(), chain_id),
+            Ok(Some(_))
+        )
     }
 
-    fn chain_id_to_token(&self, chain_id: u64) -> Pubkey {
-        let index = crate::config::CHAIN_ID_LIST
-            .binary_search_by_key(&chain_id, |c| c.0)
-            .unwrap();
+    fn chain_id_to_token(&self, chain_id: u64) -> Result<Pubkey> {
+        let (token, _) = find_chain(self.chain_registry.as_ref(), chain_id)?
+            .ok_or(Error::InvalidChainId(chain_id))?;
+        Ok(token)
+    }
 
-        crate::config::CHAIN_ID_LIST[index].2
+    fn chain_spec(&self, chain_id: u64) -> Result<Spec> {
+        let (_, spec) = find_chain(self.chain_registry.as_ref(), chain_id)?
+            .ok_or(Error::InvalidChainId(chain_id))?;
+        Ok(spec)
     }
 
     fn default_chain_id(&self) -> u64 {
@@ -1,5 +1,5 @@
 use crate::account::{
-    AccountsDB, BalanceAccount, ContractAccount, Operator, StorageCell, Treasury,
+    AccountsDB, BalanceAccount, ChainRegistry, ContractAccount, Operator, StorageCell, Treasury,
 };
 use crate::account_storage::ProgramAccountStorage;
 use crate::config::DEFAULT_CHAIN_ID;
@@ -16,6 +16,7 @@ impl<'a> ProgramAccountStorage<'a> {
     pub fn new(accounts: AccountsDB<'a>) -> Result<Self> {
         Ok(Self {
             clock: Clock::get()?,
+            chain_registry: ChainRegistry::from_accounts_db(&crate::ID, &accounts)?,
             accounts,
             keys: KeysCache::new(),
         })
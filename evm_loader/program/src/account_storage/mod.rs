@@ -1,11 +1,15 @@
 use crate::error::Result;
+use crate::evm::Spec;
 use crate::executor::OwnedAccountInfo;
 use crate::types::Address;
 use ethnum::U256;
 use maybe_async::maybe_async;
 use solana_program::account_info::AccountInfo;
 #[cfg(target_os = "solana")]
-use {crate::account::AccountsDB, solana_program::clock::Clock};
+use {
+    crate::account::{AccountsDB, ChainRegistry},
+    solana_program::clock::Clock,
+};
 
 use solana_program::pubkey::Pubkey;
 
@@ -21,10 +25,14 @@ pub use block_hash::find_slot_hash;
 mod keys_cache;
 pub use keys_cache::KeysCache;
 
+#[cfg(test)]
+pub mod test_storage;
+
 #[cfg(target_os = "solana")]
 pub struct ProgramAccountStorage<'a> {
     clock: Clock,
     accounts: AccountsDB<'a>,
+    chain_registry: Option<ChainRegistry<'a>>,
     keys: keys_cache::KeysCache,
 }
 
@@ -50,7 +58,8 @@ pub trait AccountStorage {
     async fn balance(&self, address: Address, chain_id: u64) -> U256;
 
     fn is_valid_chain_id(&self, chain_id: u64) -> bool;
-    fn chain_id_to_token(&self, chain_id: u64) -> Pubkey;
+    fn chain_id_to_token(&self, chain_id: u64) -> Result<Pubkey>;
+    fn chain_spec(&self, chain_id: u64) -> Result<Spec>;
     fn default_chain_id(&self) -> u64;
 
     /// Get contract chain_id
@@ -0,0 +1,254 @@
+//! In-memory `AccountStorage` for executor and precompile tests.
+
+use std::collections::BTreeMap;
+
+use ethnum::U256;
+use maybe_async::maybe_async;
+use solana_program::account_info::{AccountInfo, IntoAccountInfo};
+use solana_program::program_stubs::{self, SyscallStubs};
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar::{clock::Clock, rent::Rent};
+
+use crate::account::{find_chain, Chain, ChainRegistry, TAG_CHAIN_REGISTRY};
+use crate::account_storage::AccountStorage;
+use crate::error::{Error, Result};
+use crate::evm::{Buffer, Spec};
+use crate::executor::OwnedAccountInfo;
+use crate::types::Address;
+
+pub const CHAIN_ID: u64 = crate::config::DEFAULT_CHAIN_ID;
+
+thread_local! {
+    static CLOCK_SLOT: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+}
+
+/// Slot returned by the `Clock` sysvar in the current test thread
+pub fn set_clock_slot(slot: u64) {
+    CLOCK_SLOT.with(|clock_slot| clock_slot.set(slot));
+}
+
+struct TestSyscallStubs;
+
+impl SyscallStubs for TestSyscallStubs {
+    fn sol_get_rent_sysvar(&self, pointer: *mut u8) -> u64 {
+        unsafe {
+            #[allow(clippy::cast_ptr_alignment)]
+            let rent = pointer.cast::<Rent>();
+            *rent = Rent::default();
+        }
+
+        0
+    }
+
+    fn sol_get_clock_sysvar(&self, pointer: *mut u8) -> u64 {
+        unsafe {
+            #[allow(clippy::cast_ptr_alignment)]
+            let clock = pointer.cast::<Clock>();
+            *clock = Clock {
+                slot: CLOCK_SLOT.with(std::cell::Cell::get),
+                ..Clock::default()
+            };
+        }
+
+        0
+    }
+}
+
+/// `Rent` and `Clock` sysvars, required by the emulated token programs and the state account
+pub fn install_syscall_stubs() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+    });
+}
+
+#[derive(Default)]
+pub struct TestAccountStorage {
+    pub operator: Pubkey,
+    pub spec: Spec,
+    pub balances: BTreeMap<Address, U256>,
+    pub nonces: BTreeMap<Address, u64>,
+    pub code: BTreeMap<Address, Vec<u8>>,
+    pub storage: BTreeMap<(Address, U256), [u8; 32]>,
+    pub solana_accounts: BTreeMap<Pubkey, OwnedAccountInfo>,
+    /// Chain lookups use the registry once it is set, `CHAIN_ID` and `spec` otherwise
+    pub chain_registry: Option<OwnedAccountInfo>,
+}
+
+impl TestAccountStorage {
+    pub fn set_balance(&mut self, address: Address, balance: U256) {
+        self.balances.insert(address, balance);
+    }
+
+    pub fn set_code(&mut self, address: Address, code: Vec<u8>) {
+        self.nonces.entry(address).or_insert(1);
+        self.code.insert(address, code);
+    }
+
+    pub fn add_solana_account(&mut self, account: OwnedAccountInfo) {
+        self.solana_accounts.insert(account.key, account);
+    }
+
+    /// System owned account without data
+    pub fn add_wallet(&mut self, key: Pubkey, lamports: u64) {
+        self.add_solana_account(OwnedAccountInfo {
+            key,
+            is_signer: false,
+            is_writable: false,
+            lamports,
+            data: vec![],
+            owner: solana_program::system_program::ID,
+            executable: false,
+            rent_epoch: 0,
+        });
+    }
+
+    /// Creates the Chain Registry account with `chains`, sorted by id
+    pub fn set_chains(&mut self, chains: &[Chain]) {
+        let (key, _) = ChainRegistry::address(&crate::ID);
+        let mut account = OwnedAccountInfo {
+            key,
+            is_signer: false,
+            is_writable: true,
+            lamports: 0,
+            data: vec![0; ChainRegistry::required_account_size(chains.len())],
+            owner: crate::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        account.data[0] = TAG_CHAIN_REGISTRY;
+
+        ChainRegistry::from_account(&crate::ID, account.into_account_info())
+            .unwrap()
+            .set_chains(chains);
+
+        self.chain_registry = Some(account);
+    }
+
+    /// Same as the `ChainRegistryDisable` instruction
+    pub fn disable_chain(&mut self, chain_id: u64) -> Result<()> {
+        let account = self.chain_registry.as_mut().expect("Chain registry is set");
+        ChainRegistry::from_account(&crate::ID, account.into_account_info())?.disable(chain_id)
+    }
+
+    fn find_chain(&self, chain_id: u64) -> Result<Option<(Pubkey, Spec)>> {
+        let Some(account) = &self.chain_registry else {
+            return Ok((chain_id == CHAIN_ID).then_some((Pubkey::default(), self.spec)));
+        };
+
+        let mut account = account.clone();
+        let registry = ChainRegistry::from_account(&crate::ID, account.into_account_info())?;
+        find_chain(Some(&registry), chain_id)
+    }
+
+    fn solana_account(&self, address: &Pubkey) -> OwnedAccountInfo {
+        self.solana_accounts
+            .get(address)
+            .cloned()
+            .unwrap_or_else(|| OwnedAccountInfo {
+                key: *address,
+                is_signer: false,
+                is_writable: false,
+                lamports: 0,
+                data: vec![],
+                owner: solana_program::system_program::ID,
+                executable: false,
+                rent_epoch: 0,
+            })
+    }
+}
+
+#[maybe_async(?Send)]
+impl AccountStorage for TestAccountStorage {
+    fn program_id(&self) -> &Pubkey {
+        &crate::ID
+    }
+
+    fn operator(&self) -> Pubkey {
+        self.operator
+    }
+
+    fn block_number(&self) -> U256 {
+        U256::new(1000)
+    }
+
+    fn block_timestamp(&self) -> U256 {
+        U256::new(1_700_000_000)
+    }
+
+    async fn block_hash(&self, _number: u64) -> [u8; 32] {
+        [0; 32]
+    }
+
+    async fn nonce(&self, address: Address, _chain_id: u64) -> u64 {
+        self.nonces.get(&address).copied().unwrap_or_default()
+    }
+
+    async fn balance(&self, address: Address, _chain_id: u64) -> U256 {
+        self.balances.get(&address).copied().unwrap_or_default()
+    }
+
+    fn is_valid_chain_id(&self, chain_id: u64) -> bool {
+        matches!(self.find_chain(chain_id), Ok(Some(_)))
+    }
+
+    fn chain_id_to_token(&self, chain_id: u64) -> Result<Pubkey> {
+        let (token, _) = self
+            .find_chain(chain_id)?
+            .ok_or(Error::InvalidChainId(chain_id))?;
+        Ok(token)
+    }
+
+    fn chain_spec(&self, chain_id: u64) -> Result<Spec> {
+        let (_, spec) = self
+            .find_chain(chain_id)?
+            .ok_or(Error::InvalidChainId(chain_id))?;
+        Ok(spec)
+    }
+
+    fn default_chain_id(&self) -> u64 {
+        CHAIN_ID
+    }
+
+    async fn contract_chain_id(&self, address: Address) -> Result<u64> {
+        if self.code.contains_key(&address) {
+            Ok(CHAIN_ID)
+        } else {
+            Err(Error::Custom(format!("Contract {address} not found")))
+        }
+    }
+
+    fn contract_pubkey(&self, address: Address) -> (Pubkey, u8) {
+        address.find_solana_address(self.program_id())
+    }
+
+    async fn code_size(&self, address: Address) -> usize {
+        self.code.get(&address).map_or(0, Vec::len)
+    }
+
+    async fn code(&self, address: Address) -> Buffer {
+        self.code
+            .get(&address)
+            .map_or_else(Buffer::empty, |code| Buffer::from_slice(code))
+    }
+
+    async fn storage(&self, address: Address, index: U256) -> [u8; 32] {
+        self.storage
+            .get(&(address, index))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    async fn clone_solana_account(&self, address: &Pubkey) -> OwnedAccountInfo {
+        self.solana_account(address)
+    }
+
+    async fn map_solana_account<F, R>(&self, address: &Pubkey, action: F) -> R
+    where
+        F: FnOnce(&AccountInfo) -> R,
+    {
+        let mut account = self.solana_account(address);
+        let info = (&mut account).into_account_info();
+        action(&info)
+    }
+}
@@ -128,6 +128,24 @@ fn process_instruction<'a>(
         EvmInstruction::AccountBlockAdd => {
             instruction::account_block_add::process(program_id, accounts, instruction)
         }
+        EvmInstruction::AccountReclaim => {
+            instruction::account_reclaim::process(program_id, accounts, instruction)
+        }
+        EvmInstruction::ChainRegistryAdd => {
+            instruction::chain_registry_add::process(program_id, accounts, instruction)
+        }
+        EvmInstruction::ChainRegistryDisable => {
+            instruction::chain_registry_disable::process(program_id, accounts, instruction)
+        }
+        EvmInstruction::OperatorRegistryAdd => {
+            instruction::operator_registry_add::process(program_id, accounts, instruction)
+        }
+        EvmInstruction::OperatorRegistryRemove => {
+            instruction::operator_registry_remove::process(program_id, accounts, instruction)
+        }
+        EvmInstruction::OperatorRegistrySuspend => {
+            instruction::operator_registry_suspend::process(program_id, accounts, instruction)
+        }
         EvmInstruction::AccountCreateBalance => {
             instruction::account_create_balance::process(program_id, accounts, instruction)
         }
@@ -58,6 +58,9 @@ pub enum Error {
     #[error("Account {0} - invalid tag, expected {1}")]
     AccountInvalidTag(Pubkey, u8),
 
+    #[error("Account {0} - invalid tag, expected one of {1:?}")]
+    AccountInvalidTags(Pubkey, &'static [u8]),
+
     #[error("Account {0} - invalid owner, expected {1}")]
     AccountInvalidOwner(Pubkey, Pubkey),
 
@@ -82,9 +85,21 @@ pub enum Error {
     #[error("Account {0} - in legacy format")]
     AccountLegacy(Pubkey),
 
+    #[error("Account {0} - not empty")]
+    AccountNotEmpty(Pubkey),
+
     #[error("Operator is not authorized")]
     UnauthorizedOperator,
 
+    #[error("Operator registry {0} is not passed")]
+    OperatorRegistryNotFound(Pubkey),
+
+    #[error("Chain registry {0} is not passed")]
+    ChainRegistryNotFound(Pubkey),
+
+    #[error("Transaction is in progress by operator {0} until slot {1}")]
+    OperatorPriority(Pubkey, u64),
+
     #[error("Storage Account is uninitialized")]
     StorageAccountUninitialized,
 
@@ -94,6 +109,9 @@ pub enum Error {
     #[error("Unknown extension method selector {1:?}, contract {0}")]
     UnknownPrecompileMethodSelector(Address, [u8; 4]),
 
+    #[error("Precompile failed: {0}")]
+    PrecompileFailed(&'static str),
+
     #[error("Insufficient balance for transfer, account = {0}, chain = {1}, required = {2}")]
     InsufficientBalance(Address, u64, U256),
 
@@ -148,8 +166,18 @@ pub enum Error {
     #[error("New contract code starting with the 0xEF byte (EIP-3541), contract = {0}")]
     EVMObjectFormatNotSupported(Address),
 
-    #[error("New contract code size exceeds 24kb (EIP-170), contract = {0}, size = {1}")]
-    ContractCodeSizeLimit(Address, usize),
+    #[error(
+        "New contract code size exceeds limit (EIP-170), contract = {0}, size = {1}, limit = {2}"
+    )]
+    ContractCodeSizeLimit(Address, usize, usize),
+
+    #[error(
+        "Contract initcode size exceeds limit (EIP-3860), caller = {0}, size = {1}, limit = {2}"
+    )]
+    ContractInitCodeSizeLimit(Address, usize, usize),
+
+    #[error("EIP-4844 blob transactions are not supported, hash = {}, blobs = {1}", hex::encode(.0))]
+    BlobTransactionNotSupported([u8; 32], usize),
 
     #[error("Transaction is rejected from a sender with deployed code (EIP-3607), contract = {0}")]
     SenderHasDeployedCode(Address),
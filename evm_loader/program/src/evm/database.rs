@@ -1,4 +1,4 @@
-use super::{Buffer, Context};
+use super::{Buffer, Context, Spec};
 use crate::{error::Result, types::Address};
 use ethnum::U256;
 use maybe_async::maybe_async;
@@ -8,6 +8,7 @@ use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
 pub trait Database {
     fn default_chain_id(&self) -> u64;
     fn is_valid_chain_id(&self, chain_id: u64) -> bool;
+    fn chain_spec(&self, chain_id: u64) -> Result<Spec>;
     async fn contract_chain_id(&self, address: Address) -> Result<u64>;
 
     async fn nonce(&self, address: Address, chain_id: u64) -> Result<u64>;
@@ -25,10 +26,19 @@ pub trait Database {
     async fn code(&self, address: Address) -> Result<Buffer>;
     fn set_code(&mut self, address: Address, chain_id: u64, code: Vec<u8>) -> Result<()>;
     fn selfdestruct(&mut self, address: Address) -> Result<()>;
+    fn is_deployed_in_transaction(&self, address: Address) -> bool;
 
     async fn storage(&self, address: Address, index: U256) -> Result<[u8; 32]>;
     fn set_storage(&mut self, address: Address, index: U256, value: [u8; 32]) -> Result<()>;
 
+    fn transient_storage(&self, address: Address, index: U256) -> Result<[u8; 32]>;
+    fn set_transient_storage(
+        &mut self,
+        address: Address,
+        index: U256,
+        value: [u8; 32],
+    ) -> Result<()>;
+
     async fn block_hash(&self, number: U256) -> Result<[u8; 32]>;
     fn block_number(&self) -> Result<U256>;
     fn block_timestamp(&self) -> Result<U256>;
@@ -150,6 +160,10 @@ mod tests {
             unimplemented!();
         }
 
+        fn chain_spec(&self, chain_id: u64) -> Result<Spec> {
+            unimplemented!();
+        }
+
         async fn contract_chain_id(&self, address: Address) -> Result<u64> {
             unimplemented!();
         }
@@ -204,6 +218,10 @@ mod tests {
             unimplemented!();
         }
 
+        fn is_deployed_in_transaction(&self, address: Address) -> bool {
+            unimplemented!();
+        }
+
         async fn storage(&self, address: Address, index: U256) -> Result<[u8; 32]> {
             unimplemented!();
         }
@@ -212,6 +230,19 @@ mod tests {
             unimplemented!();
         }
 
+        fn transient_storage(&self, address: Address, index: U256) -> Result<[u8; 32]> {
+            unimplemented!();
+        }
+
+        fn set_transient_storage(
+            &mut self,
+            address: Address,
+            index: U256,
+            value: [u8; 32],
+        ) -> Result<()> {
+            unimplemented!();
+        }
+
         async fn block_hash(&self, number: U256) -> Result<[u8; 32]> {
             unimplemented!();
         }
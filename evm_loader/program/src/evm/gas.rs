@@ -0,0 +1,665 @@
+//! Ethereum gas schedule (Berlin/London with Shanghai and Cancun additions).
+//!
+//! Neon EVM charges lamports for the Solana resources used by a transaction.
+//! Ethereum gas is only tracked by the emulator to report realistic numbers in
+//! traces, gas reports and `gasleft()`. Running out of Ethereum gas is not an error.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+
+use ethnum::U256;
+
+use super::{
+    database::{Database, DatabaseExt},
+    precompile::precompiles,
+    Machine,
+};
+use crate::{
+    error::Result,
+    types::{Address, StorageKey, Transaction},
+};
+
+pub const GAS_ZERO: u64 = 0;
+pub const GAS_JUMPDEST: u64 = 1;
+pub const GAS_BASE: u64 = 2;
+pub const GAS_VERY_LOW: u64 = 3;
+pub const GAS_LOW: u64 = 5;
+pub const GAS_MID: u64 = 8;
+pub const GAS_HIGH: u64 = 10;
+pub const GAS_BLOCKHASH: u64 = 20;
+pub const GAS_KECCAK256: u64 = 30;
+pub const GAS_KECCAK256_WORD: u64 = 6;
+pub const GAS_COPY_WORD: u64 = 3;
+pub const GAS_MEMORY_WORD: u64 = 3;
+pub const GAS_QUAD_COEFF_DIV: u64 = 512;
+pub const GAS_EXP_BYTE: u64 = 50;
+pub const GAS_LOG: u64 = 375;
+pub const GAS_LOG_TOPIC: u64 = 375;
+pub const GAS_LOG_DATA: u64 = 8;
+pub const GAS_CREATE: u64 = 32000;
+pub const GAS_CODE_DEPOSIT: u64 = 200;
+pub const GAS_INITCODE_WORD: u64 = 2;
+pub const GAS_CALL_VALUE: u64 = 9000;
+pub const GAS_CALL_STIPEND: u64 = 2300;
+pub const GAS_NEW_ACCOUNT: u64 = 25000;
+pub const GAS_SELFDESTRUCT: u64 = 5000;
+pub const GAS_TRANSIENT_STORAGE: u64 = 100;
+
+/// EIP-2929
+pub const GAS_WARM_ACCESS: u64 = 100;
+pub const GAS_COLD_ACCOUNT_ACCESS: u64 = 2600;
+pub const GAS_COLD_SLOAD: u64 = 2100;
+
+/// EIP-2200 with EIP-2929 and EIP-3529 adjustments
+pub const GAS_SSTORE_SET: u64 = 20000;
+pub const GAS_SSTORE_RESET: u64 = 5000 - GAS_COLD_SLOAD;
+pub const REFUND_SSTORE_CLEARS: i64 = 4800;
+/// EIP-3529
+pub const MAX_REFUND_QUOTIENT: u64 = 5;
+
+pub const GAS_TRANSACTION: u64 = 21000;
+pub const GAS_TRANSACTION_CREATE: u64 = 32000;
+pub const GAS_TRANSACTION_ZERO_DATA: u64 = 4;
+pub const GAS_TRANSACTION_NON_ZERO_DATA: u64 = 16;
+pub const GAS_ACCESS_LIST_ADDRESS: u64 = 2400;
+pub const GAS_ACCESS_LIST_STORAGE_KEY: u64 = 1900;
+
+/// Static part of the opcode cost.
+/// Account access, memory expansion and other dynamic costs are calculated in `Machine::dynamic_gas`.
+#[must_use]
+pub const fn static_gas(opcode: u8) -> u64 {
+    match opcode {
+        0x5B => GAS_JUMPDEST,
+        0x30
+        | 0x32..=0x34
+        | 0x36
+        | 0x38
+        | 0x3A
+        | 0x3D
+        | 0x41..=0x46
+        | 0x48
+        | 0x4A
+        | 0x50
+        | 0x58..=0x5A
+        | 0x5F => GAS_BASE,
+        0x01
+        | 0x03
+        | 0x10..=0x1D
+        | 0x35
+        | 0x37
+        | 0x39
+        | 0x3E
+        | 0x49
+        | 0x51..=0x53
+        | 0x5E
+        | 0x60..=0x9F => GAS_VERY_LOW,
+        0x02 | 0x04..=0x07 | 0x0B | 0x47 => GAS_LOW,
+        0x08 | 0x09 | 0x56 => GAS_MID,
+        0x0A | 0x57 => GAS_HIGH,
+        0x40 => GAS_BLOCKHASH,
+        0x20 => GAS_KECCAK256,
+        0x5C | 0x5D => GAS_TRANSIENT_STORAGE,
+        0xA0..=0xA4 => GAS_LOG + GAS_LOG_TOPIC * (opcode - 0xA0) as u64,
+        0xF0 | 0xF5 => GAS_CREATE,
+        0xFF => GAS_SELFDESTRUCT,
+        // STOP, SSTORE, RETURN, REVERT, INVALID cost nothing or are fully dynamic,
+        // as well as BALANCE, EXTCODESIZE, EXTCODECOPY, EXTCODEHASH, SLOAD, CALL*
+        _ => GAS_ZERO,
+    }
+}
+
+#[must_use]
+pub(crate) fn words(size: u64) -> u64 {
+    size.saturating_add(31) / 32
+}
+
+#[must_use]
+fn memory_cost(size: u64) -> u64 {
+    let words = u128::from(words(size));
+    let cost = u128::from(GAS_MEMORY_WORD) * words + words * words / u128::from(GAS_QUAD_COEFF_DIV);
+
+    cost.try_into().unwrap_or(u64::MAX)
+}
+
+/// Cost to expand memory from `current_size` to `new_size` bytes
+#[must_use]
+pub fn memory_expansion_cost(current_size: usize, new_size: u64) -> u64 {
+    let current_size = current_size as u64;
+    if new_size <= current_size {
+        return 0;
+    }
+
+    memory_cost(new_size).saturating_sub(memory_cost(current_size))
+}
+
+/// Memory size required to access `length` bytes at `offset`
+#[must_use]
+pub fn memory_size(offset: U256, length: U256) -> u64 {
+    if length == U256::ZERO {
+        return 0;
+    }
+
+    offset
+        .checked_add(length)
+        .and_then(|size| u64::try_from(size).ok())
+        .unwrap_or(u64::MAX)
+}
+
+#[must_use]
+pub fn copy_cost(length: U256) -> u64 {
+    let length = u64::try_from(length).unwrap_or(u64::MAX);
+    GAS_COPY_WORD.saturating_mul(words(length))
+}
+
+/// Gas charged before the execution starts
+#[must_use]
+pub fn intrinsic_gas(trx: &Transaction) -> u64 {
+    let mut gas = GAS_TRANSACTION;
+
+    for byte in trx.call_data() {
+        gas += if *byte == 0 {
+            GAS_TRANSACTION_ZERO_DATA
+        } else {
+            GAS_TRANSACTION_NON_ZERO_DATA
+        };
+    }
+
+    if trx.target().is_none() {
+        // EIP-3860
+        let initcode_words = words(trx.call_data().len() as u64);
+        gas += GAS_TRANSACTION_CREATE + GAS_INITCODE_WORD * initcode_words;
+    }
+
+    if let Some(access_list) = trx.access_list() {
+        for (_, keys) in access_list {
+            gas += GAS_ACCESS_LIST_ADDRESS + GAS_ACCESS_LIST_STORAGE_KEY * keys.len() as u64;
+        }
+    }
+
+    gas
+}
+
+enum AccessJournalEntry {
+    Address(Address),
+    Storage(Address, U256),
+}
+
+/// EIP-2929 accessed addresses and storage keys.
+/// Shared by all frames of the transaction, changes made by a reverted frame are rolled back.
+#[derive(Default)]
+pub struct AccessedState {
+    addresses: BTreeSet<Address>,
+    /// Storage value at the beginning of the transaction is kept for SSTORE gas calculation
+    storage: BTreeMap<(Address, U256), [u8; 32]>,
+    journal: Vec<AccessJournalEntry>,
+}
+
+impl AccessedState {
+    /// Returns `true` if the address was cold
+    pub fn warm_address(&mut self, address: Address) -> bool {
+        let is_cold = self.addresses.insert(address);
+        if is_cold {
+            self.journal.push(AccessJournalEntry::Address(address));
+        }
+
+        is_cold
+    }
+
+    #[must_use]
+    pub fn original_storage(&self, address: Address, index: U256) -> Option<[u8; 32]> {
+        self.storage.get(&(address, index)).copied()
+    }
+
+    pub fn warm_storage(&mut self, address: Address, index: U256, original: [u8; 32]) {
+        self.storage.insert((address, index), original);
+        self.journal
+            .push(AccessJournalEntry::Storage(address, index));
+    }
+
+    fn checkpoint(&self) -> usize {
+        self.journal.len()
+    }
+
+    fn revert(&mut self, checkpoint: usize) {
+        for entry in self.journal.drain(checkpoint..) {
+            match entry {
+                AccessJournalEntry::Address(address) => {
+                    self.addresses.remove(&address);
+                }
+                AccessJournalEntry::Storage(address, index) => {
+                    self.storage.remove(&(address, index));
+                }
+            }
+        }
+    }
+}
+
+/// Gas accounting of a single call frame
+#[derive(Default)]
+pub struct GasState {
+    used: u64,
+    refund: i64,
+    stipend: u64,
+    checkpoint: usize,
+    accessed: Rc<RefCell<AccessedState>>,
+}
+
+impl GasState {
+    #[must_use]
+    pub fn new(intrinsic_gas: u64) -> Self {
+        Self {
+            used: intrinsic_gas,
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn child(&self) -> Self {
+        let accessed = Rc::clone(&self.accessed);
+        let checkpoint = accessed.borrow().checkpoint();
+
+        Self {
+            used: 0,
+            refund: 0,
+            stipend: 0,
+            checkpoint,
+            accessed,
+        }
+    }
+
+    #[must_use]
+    pub fn used(&self) -> u64 {
+        self.used
+    }
+
+    #[must_use]
+    pub fn refund(&self) -> i64 {
+        self.refund
+    }
+
+    #[must_use]
+    pub fn remaining(&self, gas_limit: U256) -> u64 {
+        let gas_limit = u64::try_from(gas_limit).unwrap_or(u64::MAX);
+        gas_limit
+            .saturating_add(self.stipend)
+            .saturating_sub(self.used)
+    }
+
+    pub fn charge(&mut self, gas: u64) {
+        self.used = self.used.saturating_add(gas);
+    }
+
+    pub fn add_refund(&mut self, refund: i64) {
+        self.refund = self.refund.saturating_add(refund);
+    }
+
+    /// Exceptional halt consumes all gas given to the frame
+    pub fn consume_all(&mut self, gas_limit: U256) {
+        let gas_limit = u64::try_from(gas_limit).unwrap_or(u64::MAX);
+        self.used = self.used.max(gas_limit.saturating_add(self.stipend));
+    }
+
+    pub fn warm_address(&self, address: Address) {
+        self.accessed.borrow_mut().warm_address(address);
+    }
+
+    /// Marks the address as accessed, returns `true` if it was cold
+    #[must_use]
+    pub fn access_address(&self, address: Address) -> bool {
+        self.accessed.borrow_mut().warm_address(address)
+    }
+
+    /// Merges gas of the returned child frame.
+    /// Refunds and accessed state of a reverted frame are dropped.
+    /// The call stipend is not paid by the parent frame.
+    pub fn join(&mut self, child: &Self, is_succeed: bool) {
+        self.used = self
+            .used
+            .saturating_add(child.used)
+            .saturating_sub(child.stipend);
+
+        if is_succeed {
+            self.add_refund(child.refund);
+        } else {
+            self.accessed.borrow_mut().revert(child.checkpoint);
+        }
+    }
+
+    /// Used gas after EIP-3529 refund
+    #[must_use]
+    pub fn used_with_refund(&self) -> u64 {
+        let refund = u64::try_from(self.refund).unwrap_or(0);
+        let max_refund = self.used / MAX_REFUND_QUOTIENT;
+
+        self.used - refund.min(max_refund)
+    }
+}
+
+impl<B: Database> Machine<B> {
+    /// Setups gas accounting of the top level frame
+    pub(super) async fn begin_gas_accounting(
+        &mut self,
+        intrinsic_gas: u64,
+        access_list: Option<&[(Address, Vec<StorageKey>)]>,
+        backend: &B,
+    ) -> Result<()> {
+        self.gas = GasState::new(intrinsic_gas);
+
+        self.gas.warm_address(self.origin);
+        self.gas.warm_address(self.context.contract);
+        self.gas.warm_address(Address::default()); // EIP-3651: coinbase
+
+        for precompile in precompiles(self.spec) {
+            self.gas.warm_address(precompile.address);
+        }
+
+        if let Some(access_list) = access_list {
+            for (address, keys) in access_list {
+                self.gas.warm_address(*address);
+
+                for key in keys {
+                    let index = U256::from_be_bytes(*arrayref::array_ref![key.as_ref(), 0, 32]);
+                    let original = backend.storage(*address, index).await?;
+                    self.gas
+                        .accessed
+                        .borrow_mut()
+                        .warm_storage(*address, index, original);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn peek_u256(&self, index: usize) -> U256 {
+        self.stack
+            .peek(index)
+            .map_or(U256::ZERO, |value| U256::from_be_bytes(*value))
+    }
+
+    fn peek_address(&self, index: usize) -> Address {
+        self.stack
+            .peek(index)
+            .map_or_else(Address::default, |value| {
+                Address::from(*arrayref::array_ref![value, 12, 20])
+            })
+    }
+
+    fn memory_gas(&self, regions: &[(usize, usize)]) -> u64 {
+        let new_size = regions
+            .iter()
+            .map(|(offset, length)| memory_size(self.peek_u256(*offset), self.peek_u256(*length)))
+            .max()
+            .unwrap_or(0);
+
+        memory_expansion_cost(self.memory.size(), new_size)
+    }
+
+    fn account_access_gas(&self, address: Address) -> u64 {
+        if self.gas.access_address(address) {
+            GAS_COLD_ACCOUNT_ACCESS
+        } else {
+            GAS_WARM_ACCESS
+        }
+    }
+
+    /// Returns whether the storage slot was cold and its value at the beginning of the transaction
+    async fn storage_access(&self, index: U256, backend: &B) -> Result<(bool, [u8; 32])> {
+        let address = self.context.contract;
+
+        let original = self.gas.accessed.borrow().original_storage(address, index);
+
+        if let Some(original) = original {
+            return Ok((false, original));
+        }
+
+        let original = backend.storage(address, index).await?;
+        self.gas
+            .accessed
+            .borrow_mut()
+            .warm_storage(address, index, original);
+
+        Ok((true, original))
+    }
+
+    async fn sstore_gas(&mut self, backend: &B) -> Result<u64> {
+        let index = self.peek_u256(0);
+        let new = self.peek_u256(1).to_be_bytes();
+
+        let (is_cold, original) = self.storage_access(index, backend).await?;
+        let cold_gas = if is_cold { GAS_COLD_SLOAD } else { 0 };
+        let current = backend.storage(self.context.contract, index).await?;
+
+        let zero = [0_u8; 32];
+        let gas = if current == new {
+            GAS_WARM_ACCESS
+        } else if original == current {
+            if original == zero {
+                GAS_SSTORE_SET
+            } else {
+                if new == zero {
+                    self.gas.add_refund(REFUND_SSTORE_CLEARS);
+                }
+                GAS_SSTORE_RESET
+            }
+        } else {
+            if original != zero {
+                if current == zero {
+                    self.gas.add_refund(-REFUND_SSTORE_CLEARS);
+                } else if new == zero {
+                    self.gas.add_refund(REFUND_SSTORE_CLEARS);
+                }
+            }
+
+            if original == new {
+                let refund = if original == zero {
+                    GAS_SSTORE_SET - GAS_WARM_ACCESS
+                } else {
+                    GAS_SSTORE_RESET - GAS_WARM_ACCESS
+                };
+                #[allow(clippy::cast_possible_wrap)]
+                self.gas.add_refund(refund as i64);
+            }
+
+            GAS_WARM_ACCESS
+        };
+
+        Ok(cold_gas + gas)
+    }
+
+    async fn call_gas(&self, opcode: u8, backend: &B) -> Result<u64> {
+        let address = self.peek_address(1);
+        let mut gas = self.account_access_gas(address);
+
+        let memory = match opcode {
+            // CALL, CALLCODE
+            0xF1 | 0xF2 => {
+                let value = self.peek_u256(2);
+                if value != U256::ZERO {
+                    gas += GAS_CALL_VALUE;
+
+                    let chain_id = self.context.contract_chain_id;
+                    if (opcode == 0xF1) && !backend.account_exists(address, chain_id).await? {
+                        gas += GAS_NEW_ACCOUNT;
+                    }
+                }
+
+                self.memory_gas(&[(3, 4), (5, 6)])
+            }
+            // DELEGATECALL, STATICCALL
+            _ => self.memory_gas(&[(2, 3), (4, 5)]),
+        };
+
+        Ok(gas.saturating_add(memory))
+    }
+
+    /// Value transfer gives the callee a free call stipend.
+    /// Only the gas accounting is affected, the frame gas limit is the same as in the program.
+    pub(super) fn add_call_stipend(&mut self) {
+        self.gas.stipend = GAS_CALL_STIPEND;
+    }
+
+    /// Dynamic part of the opcode cost. Calculated before the opcode execution.
+    /// Stack errors are ignored here, they are reported by the opcode itself.
+    pub(super) async fn dynamic_gas(&mut self, opcode: u8, backend: &B) -> Result<u64> {
+        let gas = match opcode {
+            // EXP
+            0x0A => {
+                let exponent = self.peek_u256(1);
+                let bytes = 32 - u64::from(exponent.leading_zeros() / 8);
+                GAS_EXP_BYTE * bytes
+            }
+            // KECCAK256
+            0x20 => {
+                let length = u64::try_from(self.peek_u256(1)).unwrap_or(u64::MAX);
+                self.memory_gas(&[(0, 1)])
+                    .saturating_add(GAS_KECCAK256_WORD.saturating_mul(words(length)))
+            }
+            // BALANCE, EXTCODESIZE, EXTCODEHASH
+            0x31 | 0x3B | 0x3F => self.account_access_gas(self.peek_address(0)),
+            // CALLDATACOPY, CODECOPY, RETURNDATACOPY
+            0x37 | 0x39 | 0x3E => self
+                .memory_gas(&[(0, 2)])
+                .saturating_add(copy_cost(self.peek_u256(2))),
+            // EXTCODECOPY
+            0x3C => self
+                .account_access_gas(self.peek_address(0))
+                .saturating_add(self.memory_gas(&[(1, 3)]))
+                .saturating_add(copy_cost(self.peek_u256(3))),
+            // MLOAD, MSTORE
+            0x51 | 0x52 => {
+                let size = memory_size(self.peek_u256(0), U256::new(32));
+                memory_expansion_cost(self.memory.size(), size)
+            }
+            // MSTORE8
+            0x53 => {
+                let size = memory_size(self.peek_u256(0), U256::ONE);
+                memory_expansion_cost(self.memory.size(), size)
+            }
+            // SLOAD
+            0x54 => {
+                let (is_cold, _) = self.storage_access(self.peek_u256(0), backend).await?;
+                if is_cold {
+                    GAS_COLD_SLOAD
+                } else {
+                    GAS_WARM_ACCESS
+                }
+            }
+            // SSTORE
+            0x55 => self.sstore_gas(backend).await?,
+            // MCOPY
+            0x5E => self
+                .memory_gas(&[(0, 2), (1, 2)])
+                .saturating_add(copy_cost(self.peek_u256(2))),
+            // LOG0..LOG4
+            0xA0..=0xA4 => {
+                let length = u64::try_from(self.peek_u256(1)).unwrap_or(u64::MAX);
+                self.memory_gas(&[(0, 1)])
+                    .saturating_add(GAS_LOG_DATA.saturating_mul(length))
+            }
+            // CREATE, CREATE2
+            0xF0 | 0xF5 => {
+                let length = u64::try_from(self.peek_u256(2)).unwrap_or(u64::MAX);
+                let mut gas = self
+                    .memory_gas(&[(1, 2)])
+                    .saturating_add(GAS_INITCODE_WORD.saturating_mul(words(length)));
+                if opcode == 0xF5 {
+                    gas = gas.saturating_add(GAS_KECCAK256_WORD.saturating_mul(words(length)));
+                }
+                gas
+            }
+            // CALL, CALLCODE, DELEGATECALL, STATICCALL
+            0xF1 | 0xF2 | 0xF4 | 0xFA => self.call_gas(opcode, backend).await?,
+            // RETURN, REVERT
+            0xF3 | 0xFD => self.memory_gas(&[(0, 1)]),
+            // SELFDESTRUCT
+            0xFF => {
+                let beneficiary = self.peek_address(0);
+
+                let mut gas = 0;
+                if self.gas.access_address(beneficiary) {
+                    gas += GAS_COLD_ACCOUNT_ACCESS;
+                }
+
+                let chain_id = self.context.contract_chain_id;
+                let balance = backend.balance(self.context.contract, chain_id).await?;
+                if (balance != U256::ZERO) && !backend.account_exists(beneficiary, chain_id).await?
+                {
+                    gas += GAS_NEW_ACCOUNT;
+                }
+
+                gas
+            }
+            _ => 0,
+        };
+
+        Ok(gas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_expansion() {
+        assert_eq!(memory_expansion_cost(0, 0), 0);
+        assert_eq!(memory_expansion_cost(0, 1), 3);
+        assert_eq!(memory_expansion_cost(0, 32), 3);
+        assert_eq!(memory_expansion_cost(32, 64), 3);
+        assert_eq!(memory_expansion_cost(64, 32), 0);
+        // 1024 words: 3 * 1024 + 1024 * 1024 / 512
+        assert_eq!(memory_expansion_cost(0, 32 * 1024), 5120);
+        assert_eq!(memory_expansion_cost(0, u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn memory_size_of_empty_region() {
+        assert_eq!(memory_size(U256::MAX, U256::ZERO), 0);
+        assert_eq!(memory_size(U256::MAX, U256::ONE), u64::MAX);
+        assert_eq!(memory_size(U256::new(10), U256::new(32)), 42);
+    }
+
+    #[test]
+    fn refund_is_capped() {
+        let mut gas = GasState::new(21000);
+        gas.charge(29000);
+        gas.add_refund(20000);
+
+        assert_eq!(gas.used_with_refund(), 50000 - 10000);
+    }
+
+    #[test]
+    fn reverted_frame_drops_accessed_state() {
+        let parent = GasState::default();
+        let address = Address::from([1; 20]);
+
+        let mut child = parent.child();
+        assert!(child.access_address(address));
+        child.add_refund(100);
+
+        let mut parent = parent;
+        parent.join(&child, false);
+
+        assert_eq!(parent.refund(), 0);
+        assert!(parent.access_address(address));
+    }
+
+    #[test]
+    fn call_stipend_is_not_charged_to_parent() {
+        let mut parent = GasState::new(21000);
+
+        let mut child = parent.child();
+        child.stipend = GAS_CALL_STIPEND;
+        child.charge(1000);
+        parent.join(&child, true);
+        assert_eq!(parent.used(), 21000 - (GAS_CALL_STIPEND - 1000));
+
+        let mut child = parent.child();
+        child.stipend = GAS_CALL_STIPEND;
+        child.charge(GAS_CALL_STIPEND + 500);
+        parent.join(&child, true);
+        assert_eq!(parent.used(), 21000 - (GAS_CALL_STIPEND - 1000) + 500);
+    }
+}
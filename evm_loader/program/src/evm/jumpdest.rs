@@ -0,0 +1,85 @@
+const JUMPDEST: u8 = 0x5B;
+const PUSH1: u8 = 0x60;
+const PUSH32: u8 = 0x7F;
+
+/// Bitmap of valid `JUMPDEST` positions in a code buffer.
+/// Bytes inside of `PUSH` immediate data are not valid jump destinations.
+#[derive(Debug)]
+pub struct JumpDestinations(Vec<u8>);
+
+impl JumpDestinations {
+    #[must_use]
+    pub fn new(code: &[u8]) -> Self {
+        let mut bitmap = vec![0_u8; (code.len() + 7) / 8];
+
+        let mut pc = 0_usize;
+        while pc < code.len() {
+            let opcode = code[pc];
+            match opcode {
+                JUMPDEST => {
+                    bitmap[pc / 8] |= 1 << (pc % 8);
+                    pc += 1;
+                }
+                PUSH1..=PUSH32 => {
+                    pc += 1 + usize::from(opcode - PUSH1 + 1);
+                }
+                _ => {
+                    pc += 1;
+                }
+            }
+        }
+
+        Self(bitmap)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_valid(&self, pc: usize) -> bool {
+        self.0
+            .get(pc / 8)
+            .map_or(false, |byte| (byte & (1 << (pc % 8))) != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jumpdest_is_valid() {
+        let code = [0x00, JUMPDEST, 0x00, JUMPDEST];
+        let jumpdests = JumpDestinations::new(&code);
+
+        assert!(!jumpdests.is_valid(0));
+        assert!(jumpdests.is_valid(1));
+        assert!(!jumpdests.is_valid(2));
+        assert!(jumpdests.is_valid(3));
+        assert!(!jumpdests.is_valid(4));
+        assert!(!jumpdests.is_valid(usize::MAX));
+    }
+
+    #[test]
+    fn jumpdest_inside_push_data_is_invalid() {
+        // PUSH2 0x5B5B; JUMPDEST; PUSH32 0x5B..5B; JUMPDEST
+        let mut code = vec![PUSH1 + 1, JUMPDEST, JUMPDEST, JUMPDEST, PUSH32];
+        code.extend_from_slice(&[JUMPDEST; 32]);
+        code.push(JUMPDEST);
+
+        let jumpdests = JumpDestinations::new(&code);
+
+        assert!(!jumpdests.is_valid(1));
+        assert!(!jumpdests.is_valid(2));
+        assert!(jumpdests.is_valid(3));
+        assert!((5..37).all(|pc| !jumpdests.is_valid(pc)));
+        assert!(jumpdests.is_valid(37));
+    }
+
+    #[test]
+    fn truncated_push_data() {
+        let code = [PUSH32, JUMPDEST, JUMPDEST];
+        let jumpdests = JumpDestinations::new(&code);
+
+        assert!(!jumpdests.is_valid(1));
+        assert!(!jumpdests.is_valid(2));
+    }
+}
@@ -1,7 +1,7 @@
 use std::alloc::{GlobalAlloc, Layout};
 use std::ops::Range;
 
-use solana_program::program_memory::{sol_memcpy, sol_memset};
+use solana_program::program_memory::{sol_memcpy, sol_memmove, sol_memset};
 
 use crate::error::Error;
 
@@ -200,6 +200,30 @@ impl Memory {
         Ok(())
     }
 
+    /// Copies `length` bytes from `source_offset` to `offset`.
+    /// Source and destination may overlap.
+    pub fn copy_within(
+        &mut self,
+        offset: usize,
+        source_offset: usize,
+        length: usize,
+    ) -> Result<(), Error> {
+        if length == 0_usize {
+            return Ok(());
+        }
+
+        self.realloc(source_offset, length)?;
+        self.realloc(offset, length)?;
+
+        unsafe {
+            let source = self.data.add(source_offset);
+            let data = self.data.add(offset);
+            sol_memmove(data, source, length);
+        }
+
+        Ok(())
+    }
+
     #[inline]
     pub fn write_range(&mut self, range: &Range<usize>, source: &[u8]) -> Result<(), Error> {
         self.write_buffer(range.start, range.len(), source, 0)
@@ -260,3 +284,48 @@ impl<'de> serde::Deserialize<'de> for Memory {
         deserializer.deserialize_bytes(BytesVisitor)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory_from(v: &[u8]) -> Memory {
+        let mut data = v.to_vec();
+        data.resize(32, 0);
+        Memory::from_buffer(&data)
+    }
+
+    #[test]
+    fn copy_within_forward_overlap() {
+        let mut memory = memory_from(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        memory.copy_within(2, 0, 6).unwrap();
+
+        assert_eq!(&memory.to_vec()[..8], &[1, 2, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn copy_within_backward_overlap() {
+        let mut memory = memory_from(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        memory.copy_within(0, 2, 6).unwrap();
+
+        assert_eq!(&memory.to_vec()[..8], &[3, 4, 5, 6, 7, 8, 7, 8]);
+    }
+
+    #[test]
+    fn copy_within_expands_memory() {
+        let mut memory = memory_from(&[0xFF; 32]);
+        memory.copy_within(40, 16, 32).unwrap();
+
+        assert_eq!(memory.size(), 96);
+        assert_eq!(&memory.to_vec()[40..56], &[0xFF; 16]);
+        assert_eq!(&memory.to_vec()[56..72], &[0; 16]);
+    }
+
+    #[test]
+    fn copy_within_zero_length() {
+        let mut memory = memory_from(&[]);
+        memory.copy_within(usize::MAX, usize::MAX, 0).unwrap();
+
+        assert_eq!(memory.size(), 32);
+    }
+}
@@ -2,7 +2,7 @@
 #![allow(clippy::type_repetition_in_bounds)]
 #![allow(clippy::unsafe_derive_deserialize)]
 
-use std::{fmt::Display, marker::PhantomData, ops::Range};
+use std::{collections::BTreeMap, fmt::Display, marker::PhantomData, ops::Range, rc::Rc};
 
 use ethnum::U256;
 use maybe_async::maybe_async;
@@ -10,24 +10,32 @@ use serde::{Deserialize, Serialize};
 use solana_program::log::sol_log_data;
 
 pub use buffer::Buffer;
+pub use spec::Spec;
 
 #[cfg(not(target_os = "solana"))]
 use crate::evm::tracing::TracerTypeOpt;
 use crate::{
+    config::{MAX_CONTRACT_CODE_SIZE, MAX_INITCODE_SIZE, REJECT_EF_CONTRACT_CODE},
     error::{build_revert_message, Error, Result},
     evm::{opcode::Action, precompile::is_precompile_address},
     types::{Address, Transaction},
 };
 
-use self::{database::Database, memory::Memory, stack::Stack};
+use self::{database::Database, jumpdest::JumpDestinations, memory::Memory, stack::Stack};
 
 mod buffer;
 pub mod database;
+#[cfg(not(target_os = "solana"))]
+pub mod gas;
+mod jumpdest;
 mod memory;
 mod opcode;
 pub mod opcode_table;
-mod precompile;
+pub mod precompile;
+mod spec;
 mod stack;
+#[cfg(test)]
+mod tests;
 #[cfg(not(target_os = "solana"))]
 pub mod tracing;
 mod utils;
@@ -56,7 +64,6 @@ macro_rules! trace_end_step {
             tracer
                 .borrow_mut()
                 .event(crate::evm::tracing::Event::EndStep {
-                    gas_used: 0_u64,
                     return_data: $return_data,
                 })
         }
@@ -137,6 +144,7 @@ pub struct Context {
 pub struct Machine<B: Database> {
     origin: Address,
     chain_id: u64,
+    spec: Spec,
     context: Context,
 
     #[serde(with = "ethnum::serde::bytes::le")]
@@ -145,6 +153,12 @@ pub struct Machine<B: Database> {
     gas_limit: U256,
 
     execution_code: Buffer,
+    /// Rebuilt on the first jump after deserialization
+    #[serde(skip)]
+    jump_destinations: Option<Rc<JumpDestinations>>,
+    /// Bitmaps of the deployed code, shared by the call frames. Held by the current frame.
+    #[serde(skip)]
+    jump_destinations_cache: BTreeMap<Address, Rc<JumpDestinations>>,
     call_data: Buffer,
     return_data: Buffer,
     return_range: Range<usize>,
@@ -156,6 +170,7 @@ pub struct Machine<B: Database> {
     is_static: bool,
     reason: Reason,
 
+    depth: usize,
     parent: Option<Box<Self>>,
 
     #[serde(skip)]
@@ -164,6 +179,10 @@ pub struct Machine<B: Database> {
     #[cfg(not(target_os = "solana"))]
     #[serde(skip)]
     tracer: TracerTypeOpt,
+
+    #[cfg(not(target_os = "solana"))]
+    #[serde(skip)]
+    gas: gas::GasState,
 }
 
 impl<B: Database> Machine<B> {
@@ -240,7 +259,11 @@ impl<B: Database> Machine<B> {
         //     return Err(Error::SenderHasDeployedCode(origin));
         // }
 
-        if trx.target().is_some() {
+        #[cfg(not(target_os = "solana"))]
+        let (intrinsic_gas, access_list) = (gas::intrinsic_gas(&trx), trx.access_list().cloned());
+
+        #[allow(unused_mut)]
+        let mut evm = if trx.target().is_some() {
             Self::new_call(
                 trx_chain_id,
                 trx,
@@ -249,7 +272,7 @@ impl<B: Database> Machine<B> {
                 #[cfg(not(target_os = "solana"))]
                 tracer,
             )
-            .await
+            .await?
         } else {
             Self::new_create(
                 trx_chain_id,
@@ -259,8 +282,14 @@ impl<B: Database> Machine<B> {
                 #[cfg(not(target_os = "solana"))]
                 tracer,
             )
-            .await
-        }
+            .await?
+        };
+
+        #[cfg(not(target_os = "solana"))]
+        evm.begin_gas_accounting(intrinsic_gas, access_list.as_deref(), backend)
+            .await?;
+
+        Ok(evm)
     }
 
     #[maybe_async]
@@ -288,6 +317,7 @@ impl<B: Database> Machine<B> {
         Ok(Self {
             origin,
             chain_id,
+            spec: backend.chain_spec(chain_id)?,
             context: Context {
                 caller: origin,
                 contract: target,
@@ -298,6 +328,8 @@ impl<B: Database> Machine<B> {
             gas_price: trx.gas_price(),
             gas_limit: trx.gas_limit(),
             execution_code,
+            jump_destinations: None,
+            jump_destinations_cache: BTreeMap::new(),
             call_data: trx.into_call_data(),
             return_data: Buffer::empty(),
             return_range: 0..0,
@@ -306,10 +338,13 @@ impl<B: Database> Machine<B> {
             pc: 0_usize,
             is_static: false,
             reason: Reason::Call,
+            depth: 0,
             parent: None,
             phantom: PhantomData,
             #[cfg(not(target_os = "solana"))]
             tracer,
+            #[cfg(not(target_os = "solana"))]
+            gas: gas::GasState::default(),
         })
     }
 
@@ -326,6 +361,8 @@ impl<B: Database> Machine<B> {
         let target = Address::from_create(&origin, trx.nonce());
         sol_log_data(&[b"ENTER", b"CREATE", target.as_bytes()]);
 
+        check_initcode_size(origin, trx.call_data().len())?;
+
         if (backend.nonce(target, chain_id).await? != 0) || (backend.code_size(target).await? != 0)
         {
             return Err(Error::DeployToExistingAccount(target, origin));
@@ -342,6 +379,7 @@ impl<B: Database> Machine<B> {
         Ok(Self {
             origin,
             chain_id,
+            spec: backend.chain_spec(chain_id)?,
             context: Context {
                 caller: origin,
                 contract: target,
@@ -359,11 +397,16 @@ impl<B: Database> Machine<B> {
             is_static: false,
             reason: Reason::Create,
             execution_code: trx.into_call_data(),
+            jump_destinations: None,
+            jump_destinations_cache: BTreeMap::new(),
             call_data: Buffer::empty(),
+            depth: 0,
             parent: None,
             phantom: PhantomData,
             #[cfg(not(target_os = "solana"))]
             tracer,
+            #[cfg(not(target_os = "solana"))]
+            gas: gas::GasState::default(),
         })
     }
 
@@ -383,11 +426,27 @@ impl<B: Database> Machine<B> {
             }
         );
 
-        let status = if is_precompile_address(&self.context.contract) {
-            let value = Self::precompile(&self.context.contract, &self.call_data).unwrap();
-            backend.commit_snapshot();
+        let status = if is_precompile_address(self.spec, &self.context.contract) {
+            let result =
+                Self::precompile(self.spec, &self.context.contract, &self.call_data).unwrap();
+
+            #[cfg(not(target_os = "solana"))]
+            self.gas.charge(precompile::precompile_gas(
+                self.spec,
+                &self.context.contract,
+                &self.call_data,
+            ));
+
+            if let Ok(value) = result {
+                backend.commit_snapshot();
+                ExitStatus::Return(value)
+            } else {
+                #[cfg(not(target_os = "solana"))]
+                self.gas.consume_all(self.gas_limit);
 
-            ExitStatus::Return(value)
+                backend.revert_snapshot();
+                ExitStatus::Revert(Vec::new())
+            }
         } else {
             loop {
                 step += 1;
@@ -397,19 +456,33 @@ impl<B: Database> Machine<B> {
 
                 let opcode = self.execution_code.get_or_default(self.pc);
 
+                #[cfg(not(target_os = "solana"))]
+                let gas_cost = {
+                    let dynamic_gas = self.dynamic_gas(opcode, backend).await?;
+                    gas::static_gas(opcode).saturating_add(dynamic_gas)
+                };
+
                 tracing_event!(
                     self,
                     tracing::Event::BeginStep {
                         opcode,
                         pc: self.pc,
+                        gas: self.gas.remaining(self.gas_limit),
+                        gas_cost,
                         stack: self.stack.to_vec(),
                         memory: self.memory.to_vec()
                     }
                 );
 
+                #[cfg(not(target_os = "solana"))]
+                self.gas.charge(gas_cost);
+
                 let opcode_result = match self.execute_opcode(backend, opcode).await {
                     Ok(result) => result,
                     Err(e) => {
+                        #[cfg(not(target_os = "solana"))]
+                        self.gas.consume_all(self.gas_limit);
+
                         let message = build_revert_message(&e.to_string());
                         self.opcode_revert_impl(message, backend).await?
                     }
@@ -435,13 +508,22 @@ impl<B: Database> Machine<B> {
         tracing_event!(
             self,
             tracing::Event::EndVM {
-                status: status.clone()
+                status: status.clone(),
+                gas_used: self.gas.used_with_refund(),
             }
         );
 
         Ok((status, step))
     }
 
+    /// Ethereum gas used by the transaction, after refund.
+    /// Only tracked by the emulator, see `evm::gas`.
+    #[cfg(not(target_os = "solana"))]
+    #[must_use]
+    pub fn gas_used(&self) -> u64 {
+        self.gas.used_with_refund()
+    }
+
     fn fork(
         &mut self,
         reason: Reason,
@@ -454,10 +536,13 @@ impl<B: Database> Machine<B> {
         let mut other = Self {
             origin: self.origin,
             chain_id,
+            spec: self.spec,
             context,
             gas_price: self.gas_price,
             gas_limit: gas_limit.unwrap_or(self.gas_limit),
             execution_code,
+            jump_destinations: None,
+            jump_destinations_cache: core::mem::take(&mut self.jump_destinations_cache),
             call_data,
             return_data: Buffer::empty(),
             return_range: 0..0,
@@ -466,10 +551,13 @@ impl<B: Database> Machine<B> {
             pc: 0_usize,
             is_static: self.is_static,
             reason,
+            depth: self.depth + 1,
             parent: None,
             phantom: PhantomData,
             #[cfg(not(target_os = "solana"))]
             tracer: self.tracer.clone(),
+            #[cfg(not(target_os = "solana"))]
+            gas: self.gas.child(),
         };
 
         core::mem::swap(self, &mut other);
@@ -481,7 +569,39 @@ impl<B: Database> Machine<B> {
 
         let mut other = *self.parent.take().unwrap();
         core::mem::swap(self, &mut other);
+        self.jump_destinations_cache = core::mem::take(&mut other.jump_destinations_cache);
 
         other
     }
 }
+
+/// Shanghai hardfork, EIP-3860: limit and meter initcode
+fn check_initcode_size(caller: Address, size: usize) -> Result<()> {
+    if size > MAX_INITCODE_SIZE {
+        return Err(Error::ContractInitCodeSizeLimit(
+            caller,
+            size,
+            MAX_INITCODE_SIZE,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Spurious Dragon hardfork, EIP-170: contract code size limit
+/// London hardfork, EIP-3541: reject new contract code starting with the 0xEF byte
+fn check_contract_code(address: Address, code: &[u8]) -> Result<()> {
+    if REJECT_EF_CONTRACT_CODE && code.starts_with(&[0xEF]) {
+        return Err(Error::EVMObjectFormatNotSupported(address));
+    }
+
+    if code.len() > MAX_CONTRACT_CODE_SIZE {
+        return Err(Error::ContractCodeSizeLimit(
+            address,
+            code.len(),
+            MAX_CONTRACT_CODE_SIZE,
+        ));
+    }
+
+    Ok(())
+}
@@ -2,12 +2,16 @@
 use ethnum::{I256, U256};
 use maybe_async::maybe_async;
 use solana_program::log::sol_log_data;
+use std::rc::Rc;
 
 use super::{
+    check_contract_code, check_initcode_size,
     database::{Database, DatabaseExt},
-    tracing_event, Context, Machine, Reason,
+    jumpdest::JumpDestinations,
+    tracing_event, Context, Machine, Reason, Spec,
 };
 use crate::{
+    config::EVM_CALL_DEPTH_LIMIT,
     error::{Error, Result},
     evm::{trace_end_step, Buffer},
     types::Address,
@@ -752,6 +756,25 @@ impl<B: Database> Machine<B> {
         Ok(Action::Continue)
     }
 
+    /// Cancun hardfork, EIP-4844: versioned hash of the transaction blob
+    /// NOT SUPPORTED: Neon transactions don't carry blobs
+    #[maybe_async]
+    pub async fn opcode_blobhash(&mut self, _backend: &mut B) -> Result<Action> {
+        self.stack.discard()?;
+        self.stack.push_zero()?;
+
+        Ok(Action::Continue)
+    }
+
+    /// Cancun hardfork, EIP-7516: current block's blob base fee
+    /// NOT SUPPORTED
+    #[maybe_async]
+    pub async fn opcode_blobbasefee(&mut self, _backend: &mut B) -> Result<Action> {
+        self.stack.push_zero()?;
+
+        Ok(Action::Continue)
+    }
+
     /// pops a (u)int256 off the stack and discards it
     #[maybe_async]
     pub async fn opcode_pop(&mut self, _backend: &mut B) -> Result<Action> {
@@ -793,6 +816,19 @@ impl<B: Database> Machine<B> {
         Ok(Action::Continue)
     }
 
+    /// Cancun hardfork, EIP-5656: copies memory area, areas may overlap
+    #[maybe_async]
+    pub async fn opcode_mcopy(&mut self, _backend: &mut B) -> Result<Action> {
+        let memory_offset = self.stack.pop_usize()?;
+        let data_offset = self.stack.pop_usize()?;
+        let length = self.stack.pop_usize()?;
+
+        self.memory
+            .copy_within(memory_offset, data_offset, length)?;
+
+        Ok(Action::Continue)
+    }
+
     /// reads a (u)int256 from storage
     #[maybe_async]
     pub async fn opcode_sload(&mut self, backend: &mut B) -> Result<Action> {
@@ -823,14 +859,57 @@ impl<B: Database> Machine<B> {
         Ok(Action::Continue)
     }
 
+    /// Cancun hardfork, EIP-1153: reads a (u)int256 from transient storage
+    #[maybe_async]
+    pub async fn opcode_tload(&mut self, backend: &mut B) -> Result<Action> {
+        let index = self.stack.pop_u256()?;
+        let value = backend.transient_storage(self.context.contract, index)?;
+
+        self.stack.push_array(&value)?;
+
+        Ok(Action::Continue)
+    }
+
+    /// Cancun hardfork, EIP-1153: writes a (u)int256 to transient storage
+    #[maybe_async]
+    pub async fn opcode_tstore(&mut self, backend: &mut B) -> Result<Action> {
+        if self.is_static {
+            return Err(Error::StaticModeViolation(self.context.contract));
+        }
+
+        let index = self.stack.pop_u256()?;
+        let value = *self.stack.pop_array()?;
+
+        backend.set_transient_storage(self.context.contract, index, value)?;
+
+        Ok(Action::Continue)
+    }
+
+    /// Checks the jump target against the `JUMPDEST` bitmap of the executing code.
+    /// The bitmap is built on the first jump and cached by the code address,
+    /// so calls into the same contract share it. Init code is not cached.
+    fn is_valid_jump_destination(&mut self, target: usize) -> bool {
+        let execution_code = &self.execution_code;
+        let jump_destinations =
+            self.jump_destinations
+                .get_or_insert_with(|| match self.context.code_address {
+                    Some(address) if self.reason == Reason::Call => Rc::clone(
+                        self.jump_destinations_cache
+                            .entry(address)
+                            .or_insert_with(|| Rc::new(JumpDestinations::new(execution_code))),
+                    ),
+                    _ => Rc::new(JumpDestinations::new(execution_code)),
+                });
+
+        jump_destinations.is_valid(target)
+    }
+
     /// unconditional jump
     #[maybe_async]
     pub async fn opcode_jump(&mut self, _backend: &mut B) -> Result<Action> {
-        const JUMPDEST: u8 = 0x5B;
-
         let value = self.stack.pop_usize()?;
 
-        if self.execution_code.get(value) == Some(&JUMPDEST) {
+        if self.is_valid_jump_destination(value) {
             Ok(Action::Jump(value))
         } else {
             Err(Error::InvalidJump(self.context.contract, value))
@@ -840,8 +919,6 @@ impl<B: Database> Machine<B> {
     /// conditional jump
     #[maybe_async]
     pub async fn opcode_jumpi(&mut self, _backend: &mut B) -> Result<Action> {
-        const JUMPDEST: u8 = 0x5B;
-
         let value = self.stack.pop_usize()?;
         let condition = self.stack.pop_array()?;
 
@@ -849,7 +926,7 @@ impl<B: Database> Machine<B> {
             return Ok(Action::Continue);
         }
 
-        if self.execution_code.get(value) == Some(&JUMPDEST) {
+        if self.is_valid_jump_destination(value) {
             Ok(Action::Jump(value))
         } else {
             Err(Error::InvalidJump(self.context.contract, value))
@@ -873,9 +950,15 @@ impl<B: Database> Machine<B> {
     }
 
     /// remaining gas
+    /// Ethereum gas is only tracked by the emulator, the program reports the frame gas limit
     #[maybe_async]
     pub async fn opcode_gas(&mut self, _backend: &mut B) -> Result<Action> {
-        self.stack.push_u256(self.gas_limit)?;
+        #[cfg(not(target_os = "solana"))]
+        let gas = U256::from(self.gas.remaining(self.gas_limit));
+        #[cfg(target_os = "solana")]
+        let gas = self.gas_limit;
+
+        self.stack.push_u256(gas)?;
 
         Ok(Action::Continue)
     }
@@ -1006,6 +1089,8 @@ impl<B: Database> Machine<B> {
         let offset = self.stack.pop_usize()?;
         let length = self.stack.pop_usize()?;
 
+        check_initcode_size(self.context.contract, length)?;
+
         let created_address = {
             let source = self.context.contract;
             let chain_id = self.context.contract_chain_id;
@@ -1031,6 +1116,8 @@ impl<B: Database> Machine<B> {
         let length = self.stack.pop_usize()?;
         let salt = *self.stack.pop_array()?;
 
+        check_initcode_size(self.context.contract, length)?;
+
         let created_address = {
             let initialization_code = self.memory.read(offset, length)?;
             Address::from_create2(&self.context.contract, &salt, initialization_code)
@@ -1049,6 +1136,14 @@ impl<B: Database> Machine<B> {
         length: usize,
         backend: &mut B,
     ) -> Result<Action> {
+        self.return_data = Buffer::empty();
+        self.return_range = 0..0;
+
+        if self.is_call_depth_exceeded() {
+            self.stack.push_zero()?;
+            return Ok(Action::Continue);
+        }
+
         let chain_id = self.context.contract_chain_id;
 
         let contract_nonce = backend.nonce(self.context.contract, chain_id).await?;
@@ -1058,11 +1153,11 @@ impl<B: Database> Machine<B> {
 
         backend.increment_nonce(self.context.contract, chain_id)?;
 
-        self.return_data = Buffer::empty();
-        self.return_range = 0..0;
-
         let init_code = self.memory.read_buffer(offset, length)?;
 
+        #[cfg(not(target_os = "solana"))]
+        self.gas.warm_address(address);
+
         let context = Context {
             caller: self.context.contract,
             contract: address,
@@ -1079,13 +1174,14 @@ impl<B: Database> Machine<B> {
             }
         );
 
+        let gas_limit = self.call_gas_limit(U256::MAX);
         self.fork(
             Reason::Create,
             chain_id,
             context,
             init_code,
             Buffer::empty(),
-            None,
+            Some(gas_limit),
         );
         backend.snapshot();
 
@@ -1105,6 +1201,19 @@ impl<B: Database> Machine<B> {
         Ok(Action::Noop)
     }
 
+    /// Ethereum fails a call or create, without an exception, when the call depth limit is reached
+    fn is_call_depth_exceeded(&self) -> bool {
+        self.depth >= EVM_CALL_DEPTH_LIMIT
+    }
+
+    /// Tangerine Whistle hardfork, EIP-150: all but one 64th of the frame gas limit is forwarded.
+    /// Ethereum gas is not tracked by the program, so the same limit is used by the emulator.
+    fn call_gas_limit(&self, requested: U256) -> U256 {
+        let available = self.gas_limit;
+
+        requested.min(available - (available / 64))
+    }
+
     /// Message-call into an account
     #[maybe_async]
     pub async fn opcode_call(&mut self, backend: &mut B) -> Result<Action> {
@@ -1119,6 +1228,11 @@ impl<B: Database> Machine<B> {
         self.return_data = Buffer::empty();
         self.return_range = return_offset..(return_offset + return_length);
 
+        if self.is_call_depth_exceeded() {
+            self.stack.push_bool(false)?;
+            return Ok(Action::Continue);
+        }
+
         let call_data = self.memory.read_buffer(args_offset, args_length)?;
         let code = backend.code(address).await?;
 
@@ -1145,10 +1259,15 @@ impl<B: Database> Machine<B> {
             context,
             code,
             call_data,
-            Some(gas_limit),
+            Some(self.call_gas_limit(gas_limit)),
         );
         backend.snapshot();
 
+        #[cfg(not(target_os = "solana"))]
+        if value != U256::ZERO {
+            self.add_call_stipend();
+        }
+
         sol_log_data(&[b"ENTER", b"CALL", address.as_bytes()]);
 
         if self.is_static && (value != U256::ZERO) {
@@ -1176,6 +1295,11 @@ impl<B: Database> Machine<B> {
         self.return_data = Buffer::empty();
         self.return_range = return_offset..(return_offset + return_length);
 
+        if self.is_call_depth_exceeded() {
+            self.stack.push_bool(false)?;
+            return Ok(Action::Continue);
+        }
+
         let call_data = self.memory.read_buffer(args_offset, args_length)?;
         let code = backend.code(address).await?;
 
@@ -1200,10 +1324,15 @@ impl<B: Database> Machine<B> {
             context,
             code,
             call_data,
-            Some(gas_limit),
+            Some(self.call_gas_limit(gas_limit)),
         );
         backend.snapshot();
 
+        #[cfg(not(target_os = "solana"))]
+        if value != U256::ZERO {
+            self.add_call_stipend();
+        }
+
         sol_log_data(&[b"ENTER", b"CALLCODE", address.as_bytes()]);
 
         if backend.balance(self.context.caller, chain_id).await? < value {
@@ -1231,6 +1360,11 @@ impl<B: Database> Machine<B> {
         self.return_data = Buffer::empty();
         self.return_range = return_offset..(return_offset + return_length);
 
+        if self.is_call_depth_exceeded() {
+            self.stack.push_bool(false)?;
+            return Ok(Action::Continue);
+        }
+
         let call_data = self.memory.read_buffer(args_offset, args_length)?;
         let code = backend.code(address).await?;
 
@@ -1253,7 +1387,7 @@ impl<B: Database> Machine<B> {
             context,
             code,
             call_data,
-            Some(gas_limit),
+            Some(self.call_gas_limit(gas_limit)),
         );
         backend.snapshot();
 
@@ -1276,6 +1410,11 @@ impl<B: Database> Machine<B> {
         self.return_data = Buffer::empty();
         self.return_range = return_offset..(return_offset + return_length);
 
+        if self.is_call_depth_exceeded() {
+            self.stack.push_bool(false)?;
+            return Ok(Action::Continue);
+        }
+
         let call_data = self.memory.read_buffer(args_offset, args_length)?;
         let code = backend.code(address).await?;
 
@@ -1302,7 +1441,7 @@ impl<B: Database> Machine<B> {
             context,
             code,
             call_data,
-            Some(gas_limit),
+            Some(self.call_gas_limit(gas_limit)),
         );
         self.is_static = true;
 
@@ -1321,14 +1460,28 @@ impl<B: Database> Machine<B> {
         backend: &mut B,
         address: &Address,
     ) -> Result<Action> {
-        let result = match Self::precompile(address, &self.call_data).map(Ok) {
-            Some(x) => Some(x),
-            None => {
-                backend
-                    .precompile_extension(&self.context, address, &self.call_data, self.is_static)
-                    .await
+        if let Some(result) = Self::precompile(self.spec, address, &self.call_data) {
+            #[cfg(not(target_os = "solana"))]
+            self.gas.charge(super::precompile::precompile_gas(
+                self.spec,
+                address,
+                &self.call_data,
+            ));
+
+            if let Ok(return_data) = result {
+                return self.opcode_return_impl(return_data, backend).await;
             }
-        };
+
+            // Invalid input: the call fails with empty output and consumes all of its gas
+            #[cfg(not(target_os = "solana"))]
+            self.gas.consume_all(self.gas_limit);
+
+            return self.opcode_revert_impl(Vec::new(), backend).await;
+        }
+
+        let result = backend
+            .precompile_extension(&self.context, address, &self.call_data, self.is_static)
+            .await;
 
         if let Some(return_data) = result.transpose()? {
             return self.opcode_return_impl(return_data, backend).await;
@@ -1357,7 +1510,14 @@ impl<B: Database> Machine<B> {
     ) -> Result<Action> {
         if self.reason == Reason::Create {
             let code = std::mem::take(&mut return_data);
+            check_contract_code(self.context.contract, &code)?;
+
+            #[cfg(not(target_os = "solana"))]
+            self.gas
+                .charge(super::gas::GAS_CODE_DEPOSIT * code.len() as u64);
+
             backend.set_code(self.context.contract, self.chain_id, code)?;
+            self.jump_destinations_cache.remove(&self.context.contract);
         }
 
         backend.commit_snapshot();
@@ -1371,11 +1531,15 @@ impl<B: Database> Machine<B> {
         tracing_event!(
             self,
             super::tracing::Event::EndVM {
-                status: super::ExitStatus::Return(return_data.clone())
+                status: super::ExitStatus::Return(return_data.clone()),
+                gas_used: self.gas.used(),
             }
         );
 
         let returned = self.join();
+        #[cfg(not(target_os = "solana"))]
+        self.gas.join(&returned.gas, true);
+
         match returned.reason {
             Reason::Call => {
                 self.memory.write_range(&self.return_range, &return_data)?;
@@ -1420,11 +1584,15 @@ impl<B: Database> Machine<B> {
         tracing_event!(
             self,
             super::tracing::Event::EndVM {
-                status: super::ExitStatus::Revert(return_data.clone())
+                status: super::ExitStatus::Revert(return_data.clone()),
+                gas_used: self.gas.used(),
             }
         );
 
         let returned = self.join();
+        #[cfg(not(target_os = "solana"))]
+        self.gas.join(&returned.gas, false);
+
         match returned.reason {
             Reason::Call => {
                 self.memory.write_range(&self.return_range, &return_data)?;
@@ -1449,6 +1617,22 @@ impl<B: Database> Machine<B> {
         ))
     }
 
+    /// Contract is created in the current transaction,
+    /// either by one of the calling frames or by a finished create
+    fn is_created_in_transaction(&self, address: Address, backend: &B) -> bool {
+        let mut frame = self;
+        loop {
+            if (frame.reason == Reason::Create) && (frame.context.contract == address) {
+                return true;
+            }
+
+            match &frame.parent {
+                Some(parent) => frame = parent,
+                None => return backend.is_deployed_in_transaction(address),
+            }
+        }
+    }
+
     /// Halt execution, destroys the contract and send all funds to address
     #[maybe_async]
     pub async fn opcode_selfdestruct(&mut self, backend: &mut B) -> Result<Action> {
@@ -1463,7 +1647,13 @@ impl<B: Database> Machine<B> {
         backend
             .transfer(self.context.contract, address, chain_id, value)
             .await?;
-        backend.selfdestruct(self.context.contract)?;
+
+        // Cancun hardfork, EIP-6780: SELFDESTRUCT only in the same transaction
+        if !self.spec.is_enabled(Spec::Cancun)
+            || self.is_created_in_transaction(self.context.contract, backend)
+        {
+            backend.selfdestruct(self.context.contract)?;
+        }
 
         backend.commit_snapshot();
         sol_log_data(&[b"EXIT", b"SELFDESTRUCT"]);
@@ -1476,11 +1666,15 @@ impl<B: Database> Machine<B> {
         tracing_event!(
             self,
             super::tracing::Event::EndVM {
-                status: super::ExitStatus::Suicide
+                status: super::ExitStatus::Suicide,
+                gas_used: self.gas.used(),
             }
         );
 
         let returned = self.join();
+        #[cfg(not(target_os = "solana"))]
+        self.gas.join(&returned.gas, true);
+
         match returned.reason {
             Reason::Call => {
                 self.memory.write_range(&self.return_range, &[])?;
@@ -1508,11 +1702,15 @@ impl<B: Database> Machine<B> {
         tracing_event!(
             self,
             super::tracing::Event::EndVM {
-                status: super::ExitStatus::Stop
+                status: super::ExitStatus::Stop,
+                gas_used: self.gas.used(),
             }
         );
 
         let returned = self.join();
+        #[cfg(not(target_os = "solana"))]
+        self.gas.join(&returned.gas, true);
+
         match returned.reason {
             Reason::Call => {
                 self.memory.write_range(&self.return_range, &[])?;
@@ -1,9 +1,18 @@
 use crate::error::Result;
 
-use super::{database::Database, opcode::Action, Machine};
+use super::{database::Database, opcode::Action, Machine, Spec};
 
 macro_rules! opcode_table {
-    ($( $opcode:literal, $opname:literal, $op:path;)*) => {
+    ($( $opcode:literal, $opname:literal, $op:path $(, $spec:ident)?;)*) => {
+        /// Hardfork which introduced the opcode
+        pub const OPCODE_SPECS: [Spec; 256] = {
+            let mut specs: [Spec; 256] = [Spec::Istanbul; 256];
+
+            $($(specs[$opcode as usize] = Spec::$spec;)?)*
+
+            specs
+        };
+
         #[cfg(target_os = "solana")]
         type OpCode<B> = fn(&mut Machine<B>, &mut B) -> Result<Action>;
 
@@ -18,6 +27,10 @@ macro_rules! opcode_table {
             };
 
             pub fn execute_opcode(&mut self, backend: &mut B, opcode: u8) -> Result<Action> {
+                if !self.spec.is_enabled(OPCODE_SPECS[opcode as usize]) {
+                    return self.opcode_unknown(backend);
+                }
+
                 // SAFETY: OPCODES.len() == 256, opcode <= 255
                 let opcode_fn = unsafe { Self::OPCODES.get_unchecked(opcode as usize) };
                 opcode_fn(self, backend)
@@ -27,6 +40,10 @@ macro_rules! opcode_table {
         #[cfg(not(target_os = "solana"))]
         impl<B: Database> Machine<B> {
             pub async fn execute_opcode(&mut self, backend: &mut B, opcode: u8) -> Result<Action> {
+                if !self.spec.is_enabled(OPCODE_SPECS[opcode as usize]) {
+                    return self.opcode_unknown(backend).await;
+                }
+
                 match opcode {
                     $($opcode => $op(self, backend).await,)*
                     _ => Self::opcode_unknown(self, backend).await,
@@ -100,7 +117,9 @@ opcode_table![
         0x45, "GASLIMIT", Self::opcode_gaslimit;
         0x46, "CHAINID", Self::opcode_chainid;
         0x47, "SELFBALANCE", Self::opcode_selfbalance;
-        0x48, "BASEFEE", Self::opcode_basefee;
+        0x48, "BASEFEE", Self::opcode_basefee, London;
+        0x49, "BLOBHASH", Self::opcode_blobhash, Cancun;
+        0x4A, "BLOBBASEFEE", Self::opcode_blobbasefee, Cancun;
 
         0x50, "POP", Self::opcode_pop;
         0x51, "MLOAD", Self::opcode_mload;
@@ -114,8 +133,11 @@ opcode_table![
         0x59, "MSIZE", Self::opcode_msize;
         0x5A, "GAS", Self::opcode_gas;
         0x5B, "JUMPDEST", Self::opcode_jumpdest;
+        0x5C, "TLOAD", Self::opcode_tload, Cancun;
+        0x5D, "TSTORE", Self::opcode_tstore, Cancun;
+        0x5E, "MCOPY", Self::opcode_mcopy, Cancun;
 
-        0x5F, "PUSH0", Self::opcode_push_0;
+        0x5F, "PUSH0", Self::opcode_push_0, Shanghai;
         0x60, "PUSH1", Self::opcode_push_1;
         0x61, "PUSH2", Self::opcode_push_2_31::<2>;
         0x62, "PUSH3", Self::opcode_push_2_31::<3>;
@@ -1,32 +1,124 @@
-use ethnum::U256;
+use ethnum::{AsU256, U256};
 
+use crate::error::{Error, Result};
+
+/// Operands may extend past the end of the input, the missing bytes are zeros.
+/// Operands are allocated on the program heap, inputs which declare more than this
+/// in total are rejected before anything is allocated.
+const MAX_OPERANDS_LEN: usize = 64 * 1024;
+
+/// Returns `len` bytes of `input` starting from `offset`, missing bytes are zeros.
 #[must_use]
-pub fn big_mod_exp(input: &[u8]) -> Vec<u8> {
-    if input.len() < 96 {
-        return vec![];
+pub fn get_data(input: &[u8], offset: usize, len: usize) -> Vec<u8> {
+    let mut data = vec![0_u8; len];
+    if let Some(available) = input.get(offset..) {
+        let available = &available[..available.len().min(len)];
+        data[..available.len()].copy_from_slice(available);
     }
 
-    let (base_len, rest) = input.split_at(32);
-    let (exp_len, rest) = rest.split_at(32);
-    let (mod_len, rest) = rest.split_at(32);
+    data
+}
+
+fn read_len(input: &[u8], offset: usize) -> U256 {
+    let word = get_data(input, offset, 32);
+    U256::from_be_bytes(word.try_into().unwrap())
+}
+
+/// Header is `base_len | exp_len | mod_len`, 32 bytes each.
+#[must_use]
+pub fn lengths(input: &[u8]) -> (U256, U256, U256) {
+    (read_len(input, 0), read_len(input, 32), read_len(input, 64))
+}
 
-    let Ok(base_len) = U256::from_be_bytes(base_len.try_into().unwrap()).try_into() else {
-        return vec![];
-    };
-    let Ok(exp_len) = U256::from_be_bytes(exp_len.try_into().unwrap()).try_into() else {
-        return vec![];
-    };
-    let Ok(mod_len) = U256::from_be_bytes(mod_len.try_into().unwrap()).try_into() else {
-        return vec![];
-    };
+pub fn big_mod_exp(input: &[u8]) -> Result<Vec<u8>> {
+    let (base_len, exp_len, mod_len) = lengths(input);
 
     if base_len == 0 && mod_len == 0 {
-        return vec![0; 32];
+        return Ok(vec![]);
+    }
+
+    let total_len = base_len.saturating_add(exp_len).saturating_add(mod_len);
+    if total_len > MAX_OPERANDS_LEN.as_u256() {
+        return Err(Error::PrecompileFailed("modexp length is too large"));
     }
 
-    let (base_val, rest) = rest.split_at(base_len);
-    let (exp_val, rest) = rest.split_at(exp_len);
-    let (mod_val, _) = rest.split_at(mod_len);
+    let base_len = base_len.as_usize();
+    let exp_len = exp_len.as_usize();
+    let mod_len = mod_len.as_usize();
+
+    let data = input.get(96..).unwrap_or_default();
+    let base_val = get_data(data, 0, base_len);
+    let exp_val = get_data(data, base_len, exp_len);
+    let mod_val = get_data(data, base_len.saturating_add(exp_len), mod_len);
+
+    Ok(solana_program::big_mod_exp::big_mod_exp(
+        &base_val, &exp_val, &mod_val,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    solana_program::big_mod_exp::big_mod_exp(base_val, exp_val, mod_val)
+    fn input(base_len: U256, exp_len: U256, mod_len: U256, data: &[u8]) -> Vec<u8> {
+        [
+            &base_len.to_be_bytes()[..],
+            &exp_len.to_be_bytes()[..],
+            &mod_len.to_be_bytes()[..],
+            data,
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn modexp() {
+        // 3 ^ 5 mod 7
+        let data = input(U256::ONE, U256::ONE, U256::ONE, &[3, 5, 7]);
+        assert_eq!(big_mod_exp(&data).unwrap(), vec![5]);
+
+        // Modulus is padded with zeros: 3 ^ 5 mod 0x0700
+        let data = input(U256::ONE, U256::ONE, U256::new(2), &[3, 5, 7]);
+        assert_eq!(big_mod_exp(&data).unwrap(), vec![0, 243]);
+    }
+
+    #[test]
+    fn huge_declared_length_fails() {
+        let data = input(U256::ONE, U256::ONE, U256::new(0xFFFF_FFFF), &[3, 5, 7]);
+        assert!(big_mod_exp(&data).is_err());
+
+        let data = input(U256::new(1 << 40), U256::ONE, U256::ONE, &[3, 5, 7]);
+        assert!(big_mod_exp(&data).is_err());
+
+        let data = input(U256::ONE, U256::MAX, U256::ONE, &[3, 5, 7]);
+        assert!(big_mod_exp(&data).is_err());
+
+        let too_large = (MAX_OPERANDS_LEN - 1).as_u256();
+        let data = input(U256::ONE, U256::ONE, too_large, &[3, 5, 7]);
+        assert!(big_mod_exp(&data).is_err());
+    }
+
+    #[test]
+    fn long_zero_padded_operands() {
+        // 3 ^ 5 mod 0x0700..00, the modulus is 2048 bytes with short calldata
+        let data = input(U256::ONE, U256::ONE, U256::new(2048), &[3, 5, 7]);
+        let result = big_mod_exp(&data).unwrap();
+        assert_eq!(result.len(), 2048);
+        assert_eq!(result[2047], 243);
+        assert!(result[..2047].iter().all(|b| *b == 0));
+
+        // Missing operands are zeros, zero modulus gives zero output
+        let data = input(U256::new(4096), U256::new(4096), U256::new(4096), &[]);
+        let result = big_mod_exp(&data).unwrap();
+        assert_eq!(result.len(), 4096);
+        assert!(result.iter().all(|b| *b == 0));
+
+        // Operands up to the allocation cap: 0 ^ 0 mod 0x0700..00
+        let len = MAX_OPERANDS_LEN / 3;
+        let mut data = input(len.as_u256(), len.as_u256(), len.as_u256(), &[]);
+        data.resize(data.len() + 2 * len, 0);
+        data.push(7);
+        let result = big_mod_exp(&data).unwrap();
+        assert_eq!(result.len(), len);
+        assert_eq!(result[len - 1], 1);
+    }
 }
@@ -1,6 +1,7 @@
-#[must_use]
+use crate::error::{Error, Result};
+
 #[allow(clippy::too_many_lines)]
-pub fn blake2_f(input: &[u8]) -> Vec<u8> {
+pub fn blake2_f(input: &[u8]) -> Result<Vec<u8>> {
     const BLAKE2_F_ARG_LEN: usize = 213;
     debug_print!("blake2F");
 
@@ -66,8 +67,9 @@ pub fn blake2_f(input: &[u8]) -> Vec<u8> {
     };
 
     if input.len() != BLAKE2_F_ARG_LEN {
-        // return Err(ExitError::Other("input length for Blake2 F precompile should be exactly 213 bytes".into()));
-        return Vec::new();
+        return Err(Error::PrecompileFailed(
+            "Blake2 F input length should be exactly 213 bytes",
+        ));
     }
 
     let mut rounds_arr: [u8; 4] = Default::default();
@@ -112,8 +114,9 @@ pub fn blake2_f(input: &[u8]) -> Vec<u8> {
     } else if input[0] == 0 {
         false
     } else {
-        // return Err(ExitError::Other("incorrect final block indicator flag".into()))
-        return Vec::new();
+        return Err(Error::PrecompileFailed(
+            "Blake2 F incorrect final block indicator flag",
+        ));
     };
 
     compress(&mut h, m, [t_0, t_1], f, rounds as usize);
@@ -123,5 +126,5 @@ pub fn blake2_f(input: &[u8]) -> Vec<u8> {
         output_buf[i * 8..(i + 1) * 8].copy_from_slice(&state_word.to_le_bytes());
     }
 
-    output_buf.to_vec()
+    Ok(output_buf.to_vec())
 }
@@ -0,0 +1,363 @@
+//! EIP-2537: Precompile for BLS12-381 curve operations
+//! <https://eips.ethereum.org/EIPS/eip-2537>
+//!
+//! Solana doesn't provide BLS12-381 syscalls, these operations are computed by the program itself.
+//! MSM, pairing and map-to-curve may not fit into the compute budget of a transaction,
+//! so the precompiles are only enabled for chains with the Prague spec.
+
+use ark_bls12_381::{
+    g1, g2, Bls12_381, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective,
+};
+use ark_ec::{
+    hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurve},
+    pairing::Pairing,
+    AffineRepr, CurveGroup, VariableBaseMSM,
+};
+use ark_ff::{BigInt, One, PrimeField, Zero};
+use arrayref::{array_ref, array_refs};
+
+use crate::error::{Error, Result};
+
+const FP_LEN: usize = 64;
+const FP2_LEN: usize = 2 * FP_LEN;
+const G1_LEN: usize = 2 * FP_LEN;
+const G2_LEN: usize = 2 * FP2_LEN;
+const SCALAR_LEN: usize = 32;
+
+const G1_MSM_PAIR_LEN: usize = G1_LEN + SCALAR_LEN;
+const G2_MSM_PAIR_LEN: usize = G2_LEN + SCALAR_LEN;
+const PAIRING_PAIR_LEN: usize = G1_LEN + G2_LEN;
+
+/// Field element is encoded as 64 bytes big-endian, the top 16 bytes must be zero.
+fn read_fp(input: &[u8; FP_LEN]) -> Result<Fq> {
+    let (padding, value) = array_refs![input, 16, 48];
+    if padding.iter().any(|b| *b != 0) {
+        return Err(Error::PrecompileFailed(
+            "BLS12-381 field element padding is not zero",
+        ));
+    }
+
+    let mut limbs = [0_u64; 6];
+    for (limb, chunk) in limbs.iter_mut().zip(value.rchunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+
+    Fq::from_bigint(BigInt(limbs)).ok_or(Error::PrecompileFailed(
+        "BLS12-381 field element is not canonical",
+    ))
+}
+
+fn read_fp2(input: &[u8; FP2_LEN]) -> Result<Fq2> {
+    let (c0, c1) = array_refs![input, FP_LEN, FP_LEN];
+    Ok(Fq2::new(read_fp(c0)?, read_fp(c1)?))
+}
+
+fn write_fp(value: &Fq, output: &mut Vec<u8>) {
+    output.extend_from_slice(&[0_u8; 16]);
+    for limb in value.into_bigint().0.iter().rev() {
+        output.extend_from_slice(&limb.to_be_bytes());
+    }
+}
+
+fn read_g1(input: &[u8; G1_LEN], subgroup_check: bool) -> Result<G1Affine> {
+    let (x, y) = array_refs![input, FP_LEN, FP_LEN];
+    let (x, y) = (read_fp(x)?, read_fp(y)?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::zero());
+    }
+
+    let point = G1Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(Error::PrecompileFailed(
+            "BLS12-381 G1 point is not on curve",
+        ));
+    }
+    if subgroup_check && !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::PrecompileFailed(
+            "BLS12-381 G1 point is not in subgroup",
+        ));
+    }
+
+    Ok(point)
+}
+
+fn read_g2(input: &[u8; G2_LEN], subgroup_check: bool) -> Result<G2Affine> {
+    let (x, y) = array_refs![input, FP2_LEN, FP2_LEN];
+    let (x, y) = (read_fp2(x)?, read_fp2(y)?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::zero());
+    }
+
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(Error::PrecompileFailed(
+            "BLS12-381 G2 point is not on curve",
+        ));
+    }
+    if subgroup_check && !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::PrecompileFailed(
+            "BLS12-381 G2 point is not in subgroup",
+        ));
+    }
+
+    Ok(point)
+}
+
+/// Point at infinity is encoded as all zeroes.
+fn write_g1(point: &G1Affine) -> Vec<u8> {
+    let mut output = Vec::with_capacity(G1_LEN);
+    match point.xy() {
+        Some((x, y)) => {
+            write_fp(x, &mut output);
+            write_fp(y, &mut output);
+        }
+        None => output.resize(G1_LEN, 0),
+    }
+
+    output
+}
+
+fn write_g2(point: &G2Affine) -> Vec<u8> {
+    let mut output = Vec::with_capacity(G2_LEN);
+    match point.xy() {
+        Some((x, y)) => {
+            write_fp(&x.c0, &mut output);
+            write_fp(&x.c1, &mut output);
+            write_fp(&y.c0, &mut output);
+            write_fp(&y.c1, &mut output);
+        }
+        None => output.resize(G2_LEN, 0),
+    }
+
+    output
+}
+
+/// Scalar is 32 bytes big-endian, it is not required to be less than the subgroup order.
+fn read_scalar(input: &[u8; SCALAR_LEN]) -> Fr {
+    Fr::from_be_bytes_mod_order(input)
+}
+
+fn check_pairs_len(input: &[u8], pair_len: usize) -> Result<()> {
+    if input.is_empty() || (input.len() % pair_len != 0) {
+        return Err(Error::PrecompileFailed("BLS12-381 invalid input length"));
+    }
+
+    Ok(())
+}
+
+fn check_len(input: &[u8], len: usize) -> Result<()> {
+    if input.len() != len {
+        return Err(Error::PrecompileFailed("BLS12-381 invalid input length"));
+    }
+
+    Ok(())
+}
+
+/// `BLS12_G1ADD`: no subgroup check for the inputs
+pub fn g1_add(input: &[u8]) -> Result<Vec<u8>> {
+    check_len(input, 2 * G1_LEN)?;
+
+    let (a, b) = array_refs![array_ref![input, 0, 2 * G1_LEN], G1_LEN, G1_LEN];
+    let a = read_g1(a, false)?;
+    let b = read_g1(b, false)?;
+
+    let sum: G1Affine = (a + b).into_affine();
+    Ok(write_g1(&sum))
+}
+
+/// `BLS12_G1MSM`
+pub fn g1_msm(input: &[u8]) -> Result<Vec<u8>> {
+    check_pairs_len(input, G1_MSM_PAIR_LEN)?;
+
+    let mut bases = Vec::with_capacity(input.len() / G1_MSM_PAIR_LEN);
+    let mut scalars = Vec::with_capacity(input.len() / G1_MSM_PAIR_LEN);
+    for pair in input.chunks_exact(G1_MSM_PAIR_LEN) {
+        let (point, scalar) = array_refs![array_ref![pair, 0, G1_MSM_PAIR_LEN], G1_LEN, SCALAR_LEN];
+        bases.push(read_g1(point, true)?);
+        scalars.push(read_scalar(scalar));
+    }
+
+    let result = G1Projective::msm_unchecked(&bases, &scalars).into_affine();
+    Ok(write_g1(&result))
+}
+
+/// `BLS12_G2ADD`: no subgroup check for the inputs
+pub fn g2_add(input: &[u8]) -> Result<Vec<u8>> {
+    check_len(input, 2 * G2_LEN)?;
+
+    let (a, b) = array_refs![array_ref![input, 0, 2 * G2_LEN], G2_LEN, G2_LEN];
+    let a = read_g2(a, false)?;
+    let b = read_g2(b, false)?;
+
+    let sum: G2Affine = (a + b).into_affine();
+    Ok(write_g2(&sum))
+}
+
+/// `BLS12_G2MSM`
+pub fn g2_msm(input: &[u8]) -> Result<Vec<u8>> {
+    check_pairs_len(input, G2_MSM_PAIR_LEN)?;
+
+    let mut bases = Vec::with_capacity(input.len() / G2_MSM_PAIR_LEN);
+    let mut scalars = Vec::with_capacity(input.len() / G2_MSM_PAIR_LEN);
+    for pair in input.chunks_exact(G2_MSM_PAIR_LEN) {
+        let (point, scalar) = array_refs![array_ref![pair, 0, G2_MSM_PAIR_LEN], G2_LEN, SCALAR_LEN];
+        bases.push(read_g2(point, true)?);
+        scalars.push(read_scalar(scalar));
+    }
+
+    let result = G2Projective::msm_unchecked(&bases, &scalars).into_affine();
+    Ok(write_g2(&result))
+}
+
+/// `BLS12_PAIRING_CHECK`: returns 32-byte word, 1 if the product of pairings is one, 0 otherwise
+pub fn pairing_check(input: &[u8]) -> Result<Vec<u8>> {
+    check_pairs_len(input, PAIRING_PAIR_LEN)?;
+
+    let mut g1_points = Vec::with_capacity(input.len() / PAIRING_PAIR_LEN);
+    let mut g2_points = Vec::with_capacity(input.len() / PAIRING_PAIR_LEN);
+    for pair in input.chunks_exact(PAIRING_PAIR_LEN) {
+        let (a, b) = array_refs![array_ref![pair, 0, PAIRING_PAIR_LEN], G1_LEN, G2_LEN];
+        g1_points.push(read_g1(a, true)?);
+        g2_points.push(read_g2(b, true)?);
+    }
+
+    let result = Bls12_381::multi_pairing(g1_points, g2_points);
+
+    let mut output = vec![0_u8; 32];
+    output[31] = u8::from(result.0.is_one());
+    Ok(output)
+}
+
+/// `BLS12_MAP_FP_TO_G1`: simplified SWU map with cofactor clearing
+pub fn map_fp_to_g1(input: &[u8]) -> Result<Vec<u8>> {
+    check_len(input, FP_LEN)?;
+
+    let element = read_fp(array_ref![input, 0, FP_LEN])?;
+    let point = WBMap::<g1::Config>::new()
+        .and_then(|map| map.map_to_curve(element))
+        .map_err(|_| Error::PrecompileFailed("BLS12-381 map to G1 failed"))?;
+
+    Ok(write_g1(&point.clear_cofactor()))
+}
+
+/// `BLS12_MAP_FP2_TO_G2`: simplified SWU map with cofactor clearing
+pub fn map_fp2_to_g2(input: &[u8]) -> Result<Vec<u8>> {
+    check_len(input, FP2_LEN)?;
+
+    let element = read_fp2(array_ref![input, 0, FP2_LEN])?;
+    let point = WBMap::<g2::Config>::new()
+        .and_then(|map| map.map_to_curve(element))
+        .map_err(|_| Error::PrecompileFailed("BLS12-381 map to G2 failed"))?;
+
+    Ok(write_g2(&point.clear_cofactor()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const G1_GENERATOR: &str = "\
+        0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\
+        0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1";
+
+    fn g1_generator() -> Vec<u8> {
+        hex::decode(G1_GENERATOR).unwrap()
+    }
+
+    fn g2_generator() -> Vec<u8> {
+        write_g2(&G2Affine::generator())
+    }
+
+    fn scalar(value: u8) -> Vec<u8> {
+        let mut scalar = vec![0_u8; SCALAR_LEN];
+        scalar[31] = value;
+        scalar
+    }
+
+    fn negate_g1(point: &[u8]) -> Vec<u8> {
+        let point = read_g1(array_ref![point, 0, G1_LEN], true).unwrap();
+        write_g1(&-point)
+    }
+
+    #[test]
+    fn g1_encoding_roundtrip() {
+        assert_eq!(write_g1(&G1Affine::generator()), g1_generator());
+    }
+
+    #[test]
+    fn g1_add_and_msm_agree() {
+        let doubled = g1_add(&[g1_generator(), g1_generator()].concat()).unwrap();
+        let multiplied = g1_msm(&[g1_generator(), scalar(2)].concat()).unwrap();
+        assert_eq!(doubled, multiplied);
+
+        let zero = g1_add(&[g1_generator(), negate_g1(&g1_generator())].concat()).unwrap();
+        assert_eq!(zero, vec![0; G1_LEN]);
+
+        let same = g1_add(&[g1_generator(), vec![0; G1_LEN]].concat()).unwrap();
+        assert_eq!(same, g1_generator());
+    }
+
+    #[test]
+    fn g2_add_and_msm_agree() {
+        let doubled = g2_add(&[g2_generator(), g2_generator()].concat()).unwrap();
+        let multiplied = g2_msm(&[g2_generator(), scalar(2)].concat()).unwrap();
+        assert_eq!(doubled, multiplied);
+
+        let sum = g2_msm(&[g2_generator(), scalar(1), g2_generator(), scalar(3)].concat()).unwrap();
+        let expected = g2_msm(&[g2_generator(), scalar(4)].concat()).unwrap();
+        assert_eq!(sum, expected);
+    }
+
+    #[test]
+    fn pairing_check_works() {
+        let one = pairing_check(
+            &[
+                g1_generator(),
+                g2_generator(),
+                negate_g1(&g1_generator()),
+                g2_generator(),
+            ]
+            .concat(),
+        )
+        .unwrap();
+        assert_eq!(one[31], 1);
+
+        let zero = pairing_check(&[g1_generator(), g2_generator()].concat()).unwrap();
+        assert_eq!(zero, vec![0; 32]);
+    }
+
+    #[test]
+    fn map_to_curve_returns_subgroup_points() {
+        let mut element = vec![0_u8; FP_LEN];
+        element[63] = 7;
+
+        let point = map_fp_to_g1(&element).unwrap();
+        assert!(read_g1(array_ref![point, 0, G1_LEN], true).is_ok());
+
+        let point = map_fp2_to_g2(&[element.clone(), element].concat()).unwrap();
+        assert!(read_g2(array_ref![point, 0, G2_LEN], true).is_ok());
+    }
+
+    #[test]
+    fn invalid_inputs_fail() {
+        // wrong length
+        assert!(g1_add(&g1_generator()).is_err());
+        assert!(g1_msm(&[]).is_err());
+        assert!(pairing_check(&[]).is_err());
+
+        // non-zero padding
+        let mut point = g1_generator();
+        point[0] = 1;
+        assert!(g1_add(&[point, g1_generator()].concat()).is_err());
+
+        // not on curve
+        let mut point = g1_generator();
+        point[127] ^= 1;
+        assert!(g1_add(&[point, g1_generator()].concat()).is_err());
+
+        // field element equal to the modulus
+        let mut modulus = vec![0_u8; 16];
+        modulus.extend(hex::decode("1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab").unwrap());
+        assert!(map_fp_to_g1(&modulus).is_err());
+    }
+}
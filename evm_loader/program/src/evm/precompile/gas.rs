@@ -0,0 +1,290 @@
+//! Ethereum gas cost of precompiled contracts.
+//! Only tracked by the emulator, see `evm::gas`.
+
+use ethnum::U256;
+
+use super::big_mod_exp::{get_data, lengths};
+use crate::evm::gas::words;
+
+const GAS_ECRECOVER: u64 = 3000;
+const GAS_SHA256: u64 = 60;
+const GAS_SHA256_WORD: u64 = 12;
+const GAS_RIPEMD160: u64 = 600;
+const GAS_RIPEMD160_WORD: u64 = 120;
+const GAS_DATACOPY: u64 = 15;
+const GAS_DATACOPY_WORD: u64 = 3;
+
+/// EIP-2565
+const GAS_BIGMODEXP_MIN: u64 = 200;
+
+/// EIP-1108
+const GAS_BN256_ADD: u64 = 150;
+const GAS_BN256_SCALAR_MUL: u64 = 6000;
+const GAS_BN256_PAIRING: u64 = 45000;
+const GAS_BN256_PAIRING_POINT: u64 = 34000;
+const BN256_PAIRING_POINT_LEN: u64 = 192;
+
+/// EIP-152
+const GAS_BLAKE2F_ROUND: u64 = 1;
+const BLAKE2F_INPUT_LEN: usize = 213;
+
+/// EIP-4844
+const GAS_KZG_POINT_EVALUATION: u64 = 50000;
+
+/// RIP-7212
+const GAS_P256VERIFY: u64 = 3450;
+
+fn saturating_u64(value: U256) -> u64 {
+    u64::try_from(value).unwrap_or(u64::MAX)
+}
+
+fn saturating_usize(value: U256) -> usize {
+    usize::try_from(value).unwrap_or(usize::MAX)
+}
+
+pub fn ecrecover(_input: &[u8]) -> u64 {
+    GAS_ECRECOVER
+}
+
+pub fn sha256(input: &[u8]) -> u64 {
+    GAS_SHA256 + GAS_SHA256_WORD * words(input.len() as u64)
+}
+
+pub fn ripemd160(input: &[u8]) -> u64 {
+    GAS_RIPEMD160 + GAS_RIPEMD160_WORD * words(input.len() as u64)
+}
+
+pub fn datacopy(input: &[u8]) -> u64 {
+    GAS_DATACOPY + GAS_DATACOPY_WORD * words(input.len() as u64)
+}
+
+/// EIP-2565: `max(200, words(max(base_len, mod_len))^2 * iterations / 3)`
+pub fn big_mod_exp(input: &[u8]) -> u64 {
+    let (base_len, exp_len, mod_len) = lengths(input);
+
+    let words = base_len.max(mod_len).saturating_add(U256::new(7)) / 8;
+    let complexity = words.saturating_mul(words);
+
+    // First 32 bytes of the exponent
+    let head_len = saturating_usize(exp_len.min(U256::new(32)));
+    let head_offset = saturating_usize(base_len).saturating_add(96);
+    let head = get_data(input, head_offset, head_len);
+    let head = head
+        .iter()
+        .fold(U256::ZERO, |acc, b| (acc << 8) | U256::from(*b));
+    let head_bits = u64::from(256 - head.leading_zeros()).saturating_sub(1);
+
+    let iterations = if exp_len <= 32 {
+        U256::from(head_bits)
+    } else {
+        (exp_len - 32)
+            .saturating_mul(U256::new(8))
+            .saturating_add(U256::from(head_bits))
+    };
+    let iterations = iterations.max(U256::ONE);
+
+    let gas = saturating_u64(complexity.saturating_mul(iterations) / 3);
+    gas.max(GAS_BIGMODEXP_MIN)
+}
+
+pub fn bn256_add(_input: &[u8]) -> u64 {
+    GAS_BN256_ADD
+}
+
+pub fn bn256_scalar_mul(_input: &[u8]) -> u64 {
+    GAS_BN256_SCALAR_MUL
+}
+
+pub fn bn256_pairing(input: &[u8]) -> u64 {
+    let points = input.len() as u64 / BN256_PAIRING_POINT_LEN;
+    GAS_BN256_PAIRING + GAS_BN256_PAIRING_POINT * points
+}
+
+pub fn blake2_f(input: &[u8]) -> u64 {
+    if input.len() != BLAKE2F_INPUT_LEN {
+        return 0;
+    }
+
+    let rounds = u32::from_be_bytes(*arrayref::array_ref![input, 0, 4]);
+    u64::from(rounds) * GAS_BLAKE2F_ROUND
+}
+
+pub fn kzg_point_evaluation(_input: &[u8]) -> u64 {
+    GAS_KZG_POINT_EVALUATION
+}
+
+pub fn p256_verify(_input: &[u8]) -> u64 {
+    GAS_P256VERIFY
+}
+
+/// EIP-2537
+mod bls12 {
+    const GAS_BLS12_G1ADD: u64 = 375;
+    const GAS_BLS12_G1MUL: u64 = 12000;
+    const GAS_BLS12_G2ADD: u64 = 600;
+    const GAS_BLS12_G2MUL: u64 = 22500;
+    const GAS_BLS12_PAIRING: u64 = 37700;
+    const GAS_BLS12_PAIRING_POINT: u64 = 32600;
+    const GAS_BLS12_MAP_FP_TO_G1: u64 = 5500;
+    const GAS_BLS12_MAP_FP2_TO_G2: u64 = 23800;
+    const BLS12_G1MSM_PAIR_LEN: u64 = 160;
+    const BLS12_G2MSM_PAIR_LEN: u64 = 288;
+    const BLS12_PAIRING_PAIR_LEN: u64 = 384;
+
+    /// EIP-2537 MSM discounts in permille, indexed by the number of pairs minus one.
+    /// The last value applies to any larger number of pairs.
+    const BLS12_G1MSM_DISCOUNT: [u16; 128] = [
+        1000, 949, 848, 797, 764, 750, 738, 728, 719, 712, 705, 698, 692, 687, 682, 677, 673, 669,
+        665, 661, 658, 654, 651, 648, 645, 642, 640, 637, 635, 632, 630, 627, 625, 623, 621, 619,
+        617, 615, 613, 611, 609, 608, 606, 604, 603, 601, 599, 598, 596, 595, 593, 592, 591, 589,
+        588, 586, 585, 584, 582, 581, 580, 579, 577, 576, 575, 574, 573, 572, 570, 569, 568, 567,
+        566, 565, 564, 563, 562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 551, 550, 549,
+        548, 547, 547, 546, 545, 544, 543, 542, 541, 540, 540, 539, 538, 537, 536, 536, 535, 534,
+        533, 532, 532, 531, 530, 529, 528, 528, 527, 526, 525, 525, 524, 523, 522, 522, 521, 520,
+        520, 519,
+    ];
+    const BLS12_G2MSM_DISCOUNT: [u16; 128] = [
+        1000, 1000, 923, 884, 855, 832, 812, 796, 782, 770, 759, 749, 740, 732, 724, 717, 711, 704,
+        699, 693, 688, 683, 679, 674, 670, 666, 663, 659, 655, 652, 649, 646, 643, 640, 637, 634,
+        632, 629, 627, 624, 622, 620, 618, 615, 613, 611, 609, 607, 606, 604, 602, 600, 598, 597,
+        595, 593, 592, 590, 589, 587, 586, 584, 583, 582, 580, 579, 578, 576, 575, 574, 573, 571,
+        570, 569, 568, 567, 566, 565, 563, 562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552,
+        552, 551, 550, 549, 548, 547, 546, 545, 545, 544, 543, 542, 541, 541, 540, 539, 538, 537,
+        537, 536, 535, 535, 534, 533, 532, 532, 531, 530, 530, 529, 528, 528, 527, 526, 526, 525,
+        524, 524,
+    ];
+
+    fn bls12_msm(input: &[u8], pair_len: u64, multiplication: u64, discount: &[u16; 128]) -> u64 {
+        let pairs = input.len() as u64 / pair_len;
+        if pairs == 0 {
+            return 0;
+        }
+
+        let index = usize::try_from(pairs)
+            .unwrap_or(usize::MAX)
+            .min(discount.len())
+            - 1;
+        pairs
+            .saturating_mul(multiplication)
+            .saturating_mul(u64::from(discount[index]))
+            / 1000
+    }
+
+    pub fn bls12_g1add(_input: &[u8]) -> u64 {
+        GAS_BLS12_G1ADD
+    }
+
+    pub fn bls12_g1msm(input: &[u8]) -> u64 {
+        bls12_msm(
+            input,
+            BLS12_G1MSM_PAIR_LEN,
+            GAS_BLS12_G1MUL,
+            &BLS12_G1MSM_DISCOUNT,
+        )
+    }
+
+    pub fn bls12_g2add(_input: &[u8]) -> u64 {
+        GAS_BLS12_G2ADD
+    }
+
+    pub fn bls12_g2msm(input: &[u8]) -> u64 {
+        bls12_msm(
+            input,
+            BLS12_G2MSM_PAIR_LEN,
+            GAS_BLS12_G2MUL,
+            &BLS12_G2MSM_DISCOUNT,
+        )
+    }
+
+    pub fn bls12_pairing_check(input: &[u8]) -> u64 {
+        let pairs = input.len() as u64 / BLS12_PAIRING_PAIR_LEN;
+        GAS_BLS12_PAIRING + GAS_BLS12_PAIRING_POINT * pairs
+    }
+
+    pub fn bls12_map_fp_to_g1(_input: &[u8]) -> u64 {
+        GAS_BLS12_MAP_FP_TO_G1
+    }
+
+    pub fn bls12_map_fp2_to_g2(_input: &[u8]) -> u64 {
+        GAS_BLS12_MAP_FP2_TO_G2
+    }
+}
+
+pub use bls12::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::precompile::{
+        precompile_gas, SYSTEM_ACCOUNT_BN256_PAIRING, SYSTEM_ACCOUNT_ECRECOVER,
+        SYSTEM_ACCOUNT_SHA_256,
+    };
+    use crate::evm::Spec;
+
+    fn modexp_input(base_len: u8, exp: &[u8], mod_len: u8) -> Vec<u8> {
+        let mut input = vec![0_u8; 96];
+        input[31] = base_len;
+        input[63] = u8::try_from(exp.len()).unwrap();
+        input[95] = mod_len;
+        input.extend(vec![0xFF; usize::from(base_len)]);
+        input.extend(exp);
+        input.extend(vec![0xFF; usize::from(mod_len)]);
+        input
+    }
+
+    #[test]
+    fn big_mod_exp_gas_eip2565() {
+        // Minimum price
+        assert_eq!(big_mod_exp(&modexp_input(1, &[3], 1)), 200);
+        // 64-byte operands, 32-byte exponent with top bit set: 8^2 * 255 / 3
+        assert_eq!(
+            big_mod_exp(&modexp_input(64, &[0xFF; 32], 64)),
+            8 * 8 * 255 / 3
+        );
+        // Long exponent: 8 * (40 - 32) + 255 iterations
+        assert_eq!(
+            big_mod_exp(&modexp_input(128, &[0xFF; 40], 128)),
+            16 * 16 * (8 * 8 + 255) / 3
+        );
+        // Empty input
+        assert_eq!(big_mod_exp(&[]), 200);
+    }
+
+    #[test]
+    fn fixed_and_linear_costs() {
+        assert_eq!(
+            precompile_gas(Spec::Cancun, &SYSTEM_ACCOUNT_ECRECOVER, &[]),
+            3000
+        );
+        assert_eq!(
+            precompile_gas(Spec::Cancun, &SYSTEM_ACCOUNT_SHA_256, &[0; 33]),
+            60 + 12 * 2
+        );
+        assert_eq!(
+            precompile_gas(Spec::Cancun, &SYSTEM_ACCOUNT_BN256_PAIRING, &[0; 384]),
+            45000 + 2 * 34000
+        );
+    }
+
+    #[test]
+    fn bls12_msm_costs() {
+        use crate::evm::precompile::{SYSTEM_ACCOUNT_BLS12_G1MSM, SYSTEM_ACCOUNT_BLS12_G2MSM};
+
+        assert_eq!(
+            precompile_gas(Spec::Prague, &SYSTEM_ACCOUNT_BLS12_G1MSM, &[0; 160]),
+            12000
+        );
+        assert_eq!(
+            precompile_gas(Spec::Prague, &SYSTEM_ACCOUNT_BLS12_G2MSM, &[0; 288 * 2]),
+            2 * 22500
+        );
+        assert_eq!(
+            precompile_gas(
+                Spec::Prague,
+                &SYSTEM_ACCOUNT_BLS12_G1MSM,
+                &vec![0; 160 * 200]
+            ),
+            200 * 12000 * 519 / 1000
+        );
+    }
+}
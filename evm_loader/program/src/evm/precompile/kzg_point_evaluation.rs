@@ -0,0 +1,158 @@
+//! EIP-4844: Point evaluation precompile
+//! <https://eips.ethereum.org/EIPS/eip-4844#point-evaluation-precompile>
+//!
+//! Verifies a KZG proof that the polynomial committed to by `commitment` evaluates to `y` at `z`.
+//! Any invalid input or failed verification is a precompile failure:
+//! the call returns `false` and no output.
+//!
+//! The pairing is computed by the program itself and may not fit into the compute budget
+//! of a transaction, so this implementation is only enabled for chains with the Prague spec.
+//! On Cancun the precompile fails for any input, in the program and in the emulator.
+
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{BigInt, One, PrimeField};
+use ark_serialize::CanonicalDeserialize;
+use arrayref::{array_ref, array_refs};
+use ethnum::U256;
+
+use crate::error::{Error, Result};
+
+const INPUT_LEN: usize = 192;
+
+const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+const FIELD_ELEMENTS_PER_BLOB: U256 = U256::new(4096);
+
+/// BLS12-381 scalar field modulus
+const BLS_MODULUS: [u8; 32] = [
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+/// `[τ]G2` from the Ethereum KZG ceremony trusted setup (`g2_monomial[1]`, compressed).
+/// Only this point of the setup is required to verify a single opening proof.
+const TRUSTED_SETUP_TAU_G2: [u8; 96] = [
+    0xb5, 0xbf, 0xd7, 0xdd, 0x8c, 0xde, 0xb1, 0x28, 0x84, 0x3b, 0xc2, 0x87, 0x23, 0x0a, 0xf3, 0x89,
+    0x26, 0x18, 0x70, 0x75, 0xcb, 0xfb, 0xef, 0xa8, 0x10, 0x09, 0xa2, 0xce, 0x61, 0x5a, 0xc5, 0x3d,
+    0x29, 0x14, 0xe5, 0x87, 0x0c, 0xb4, 0x52, 0xd2, 0xaf, 0xaa, 0xab, 0x24, 0xf3, 0x49, 0x9f, 0x72,
+    0x18, 0x5c, 0xbf, 0xee, 0x53, 0x49, 0x27, 0x14, 0x73, 0x44, 0x29, 0xb7, 0xb3, 0x86, 0x08, 0xe2,
+    0x39, 0x26, 0xc9, 0x11, 0xcc, 0xec, 0xea, 0xc9, 0xa3, 0x68, 0x51, 0x47, 0x7b, 0xa4, 0xc6, 0x0b,
+    0x08, 0x70, 0x41, 0xde, 0x62, 0x10, 0x00, 0xed, 0xc9, 0x8e, 0xda, 0xda, 0x20, 0xc1, 0xde, 0xf2,
+];
+
+fn kzg_to_versioned_hash(commitment: &[u8]) -> [u8; 32] {
+    let mut hash = solana_program::hash::hash(commitment).to_bytes();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+
+    hash
+}
+
+/// Field element is 32 bytes big-endian, it must be less than `BLS_MODULUS`.
+fn read_field_element(input: &[u8; 32]) -> Result<Fr> {
+    let mut limbs = [0_u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(input.rchunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+
+    Fr::from_bigint(BigInt(limbs)).ok_or(Error::PrecompileFailed(
+        "KZG field element is not canonical",
+    ))
+}
+
+/// Checks `e(commitment - [y]G1, G2) == e(proof, [τ]G2 - [z]G2)`
+fn verify_kzg_proof(commitment: &G1Affine, z: Fr, y: Fr, proof: &G1Affine) -> bool {
+    let tau_g2 = G2Affine::deserialize_compressed(&TRUSTED_SETUP_TAU_G2[..])
+        .expect("valid trusted setup point");
+
+    let commitment_minus_y = (commitment.into_group() - G1Affine::generator() * y).into_affine();
+    let tau_minus_z = (tau_g2.into_group() - G2Affine::generator() * z).into_affine();
+
+    let result = Bls12_381::multi_pairing(
+        [commitment_minus_y, -*proof],
+        [G2Affine::generator(), tau_minus_z],
+    );
+
+    result.0.is_one()
+}
+
+/// Input is `versioned_hash | z | y | commitment | proof`.
+/// Returns `FIELD_ELEMENTS_PER_BLOB | BLS_MODULUS` as two 32-byte words on success.
+pub fn kzg_point_evaluation(input: &[u8]) -> Result<Vec<u8>> {
+    debug_print!("kzg_point_evaluation");
+
+    if input.len() != INPUT_LEN {
+        return Err(Error::PrecompileFailed("KZG invalid input length"));
+    }
+
+    let input = array_ref![input, 0, INPUT_LEN];
+    let (versioned_hash, z, y, commitment, proof) = array_refs![input, 32, 32, 32, 48, 48];
+
+    if kzg_to_versioned_hash(commitment) != *versioned_hash {
+        return Err(Error::PrecompileFailed("KZG versioned hash mismatch"));
+    }
+
+    let z = read_field_element(z)?;
+    let y = read_field_element(y)?;
+
+    let commitment = G1Affine::deserialize_compressed(&commitment[..])
+        .map_err(|_| Error::PrecompileFailed("KZG invalid commitment"))?;
+    let proof = G1Affine::deserialize_compressed(&proof[..])
+        .map_err(|_| Error::PrecompileFailed("KZG invalid proof"))?;
+
+    if !verify_kzg_proof(&commitment, z, y, &proof) {
+        return Err(Error::PrecompileFailed("KZG proof verification failed"));
+    }
+
+    let mut output = Vec::with_capacity(64);
+    output.extend_from_slice(&FIELD_ELEMENTS_PER_BLOB.to_be_bytes());
+    output.extend_from_slice(&BLS_MODULUS);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // go-ethereum core/vm/testdata/precompiles/pointEvaluation.json
+    const VALID_INPUT: &str = "\
+        01e798154708fe7789429634053cbf9f99b619f9f084048927333fce637f549b\
+        564c0a11a0f704f4fc3e8acfe0f8245f0ad1347b378fbf96e206da11a5d36306\
+        24d25032e67a7e6a4910df5834b8fe70e6bcfeeac0352434196bdf4b2485d5a1\
+        8f59a8d2a1a625a17f3fea0fe5eb8c896db3764f3185481bc22f91b4aaffcca25f26936857bc3a7c2539ea8ec3a952b7\
+        873033e038326e87ed3e1276fd140253fa08e9fc25fb2d9a98527fc22a2c9612fbeafdad446cbc7bcdbdcd780af2c16a";
+
+    #[test]
+    fn valid_proof() {
+        let input = hex::decode(VALID_INPUT).unwrap();
+        let output = kzg_point_evaluation(&input).unwrap();
+
+        assert_eq!(
+            hex::encode(output),
+            "0000000000000000000000000000000000000000000000000000000000001000\
+             73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+        );
+    }
+
+    #[test]
+    fn invalid_proof() {
+        let input = hex::decode(VALID_INPUT).unwrap();
+        assert!(kzg_point_evaluation(&input[..191]).is_err());
+
+        // wrong evaluation
+        let mut wrong = input.clone();
+        wrong[95] ^= 1;
+        assert!(kzg_point_evaluation(&wrong).is_err());
+
+        // versioned hash doesn't match the commitment
+        let mut wrong = input.clone();
+        wrong[31] ^= 1;
+        assert!(kzg_point_evaluation(&wrong).is_err());
+
+        // z is not a canonical field element
+        let mut wrong = input;
+        wrong[32..64].copy_from_slice(&BLS_MODULUS);
+        assert!(kzg_point_evaluation(&wrong).is_err());
+    }
+}
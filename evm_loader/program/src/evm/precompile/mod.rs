@@ -1,11 +1,17 @@
-use crate::evm::{database::Database, Machine};
+use crate::error::Result;
+use crate::evm::{database::Database, Machine, Spec};
 use crate::types::Address;
 
 mod big_mod_exp;
 mod blake2_f;
+mod bls12_381;
 mod bn256;
 mod datacopy;
 mod ecrecover;
+#[cfg(not(target_os = "solana"))]
+mod gas;
+mod kzg_point_evaluation;
+mod p256_verify;
 mod ripemd160;
 mod sha256;
 
@@ -41,35 +47,260 @@ const SYSTEM_ACCOUNT_BN256_PAIRING: Address = Address([
 const SYSTEM_ACCOUNT_BLAKE2F: Address = Address([
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x09,
 ]);
+const SYSTEM_ACCOUNT_KZG_POINT_EVALUATION: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x0a,
+]);
+const SYSTEM_ACCOUNT_BLS12_G1ADD: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x0b,
+]);
+const SYSTEM_ACCOUNT_BLS12_G1MSM: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x0c,
+]);
+const SYSTEM_ACCOUNT_BLS12_G2ADD: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x0d,
+]);
+const SYSTEM_ACCOUNT_BLS12_G2MSM: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x0e,
+]);
+const SYSTEM_ACCOUNT_BLS12_PAIRING_CHECK: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x0f,
+]);
+const SYSTEM_ACCOUNT_BLS12_MAP_FP_TO_G1: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10,
+]);
+const SYSTEM_ACCOUNT_BLS12_MAP_FP2_TO_G2: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x11,
+]);
+const SYSTEM_ACCOUNT_P256VERIFY: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0x00,
+]);
+
+/// Precompiled contract handler
+pub type Handler = fn(&[u8]) -> Result<Vec<u8>>;
+
+/// Entry of a precompile table
+pub trait Precompiled {
+    fn address(&self) -> &Address;
+}
+
+/// Finds a precompile in a table
+#[must_use]
+pub fn find<'a, T: Precompiled>(table: &'a [T], address: &Address) -> Option<&'a T> {
+    table.iter().find(|p| p.address() == address)
+}
+
+/// Ethereum precompiled contract.
+/// These are pure functions of the input and are always safe to call from a static context.
+pub struct Precompile {
+    pub address: Address,
+    /// Hardfork which introduced the precompile, addresses of later precompiles are empty accounts.
+    /// Program and emulator use the same table, so they always agree on the precompile set.
+    pub spec: Spec,
+    pub handler: Handler,
+    /// Ethereum gas cost of the call
+    #[cfg(not(target_os = "solana"))]
+    pub gas: fn(&[u8]) -> u64,
+}
+
+impl Precompiled for Precompile {
+    fn address(&self) -> &Address {
+        &self.address
+    }
+}
+
+macro_rules! precompile {
+    ($address:expr, $handler:expr, $gas:ident) => {
+        precompile!($address, $handler, $gas, Istanbul)
+    };
+    ($address:expr, $handler:expr, $gas:ident, $spec:ident) => {
+        Precompile {
+            address: $address,
+            spec: Spec::$spec,
+            handler: $handler,
+            #[cfg(not(target_os = "solana"))]
+            gas: gas::$gas,
+        }
+    };
+}
+
+pub const PRECOMPILES: &[Precompile] = &[
+    precompile!(
+        SYSTEM_ACCOUNT_ECRECOVER,
+        |data| Ok(ecrecover::ecrecover(data)),
+        ecrecover
+    ),
+    precompile!(
+        SYSTEM_ACCOUNT_SHA_256,
+        |data| Ok(sha256::sha256(data)),
+        sha256
+    ),
+    precompile!(
+        SYSTEM_ACCOUNT_RIPEMD160,
+        |data| Ok(ripemd160::ripemd160(data)),
+        ripemd160
+    ),
+    precompile!(
+        SYSTEM_ACCOUNT_DATACOPY,
+        |data| Ok(datacopy::datacopy(data)),
+        datacopy
+    ),
+    precompile!(
+        SYSTEM_ACCOUNT_BIGMODEXP,
+        big_mod_exp::big_mod_exp,
+        big_mod_exp
+    ),
+    precompile!(SYSTEM_ACCOUNT_BN256_ADD, bn256::bn256_add, bn256_add),
+    precompile!(
+        SYSTEM_ACCOUNT_BN256_SCALAR_MUL,
+        bn256::bn256_scalar_mul,
+        bn256_scalar_mul
+    ),
+    precompile!(
+        SYSTEM_ACCOUNT_BN256_PAIRING,
+        bn256::bn256_pairing,
+        bn256_pairing
+    ),
+    precompile!(SYSTEM_ACCOUNT_BLAKE2F, blake2_f::blake2_f, blake2_f),
+    // Replaces the Cancun entry below, the first enabled entry of an address is used
+    precompile!(
+        SYSTEM_ACCOUNT_KZG_POINT_EVALUATION,
+        kzg_point_evaluation::kzg_point_evaluation,
+        kzg_point_evaluation,
+        Prague
+    ),
+    // Cancun contracts expect a precompile at this address, fail instead of acting as an empty account
+    precompile!(
+        SYSTEM_ACCOUNT_KZG_POINT_EVALUATION,
+        |_| Err(crate::error::Error::PrecompileFailed(
+            "KZG point evaluation requires the Prague spec"
+        )),
+        kzg_point_evaluation,
+        Cancun
+    ),
+    precompile!(
+        SYSTEM_ACCOUNT_BLS12_G1ADD,
+        bls12_381::g1_add,
+        bls12_g1add,
+        Prague
+    ),
+    precompile!(
+        SYSTEM_ACCOUNT_BLS12_G1MSM,
+        bls12_381::g1_msm,
+        bls12_g1msm,
+        Prague
+    ),
+    precompile!(
+        SYSTEM_ACCOUNT_BLS12_G2ADD,
+        bls12_381::g2_add,
+        bls12_g2add,
+        Prague
+    ),
+    precompile!(
+        SYSTEM_ACCOUNT_BLS12_G2MSM,
+        bls12_381::g2_msm,
+        bls12_g2msm,
+        Prague
+    ),
+    precompile!(
+        SYSTEM_ACCOUNT_BLS12_PAIRING_CHECK,
+        bls12_381::pairing_check,
+        bls12_pairing_check,
+        Prague
+    ),
+    precompile!(
+        SYSTEM_ACCOUNT_BLS12_MAP_FP_TO_G1,
+        bls12_381::map_fp_to_g1,
+        bls12_map_fp_to_g1,
+        Prague
+    ),
+    precompile!(
+        SYSTEM_ACCOUNT_BLS12_MAP_FP2_TO_G2,
+        bls12_381::map_fp2_to_g2,
+        bls12_map_fp2_to_g2,
+        Prague
+    ),
+    precompile!(
+        SYSTEM_ACCOUNT_P256VERIFY,
+        |data| Ok(p256_verify::p256_verify(data)),
+        p256_verify,
+        Osaka
+    ),
+];
+
+/// Finds a precompile enabled by the chain spec
+#[must_use]
+pub fn find_precompile(spec: Spec, address: &Address) -> Option<&'static Precompile> {
+    PRECOMPILES
+        .iter()
+        .find(|p| (&p.address == address) && spec.is_enabled(p.spec))
+}
+
+/// Precompiles enabled by the chain spec, one per address
+pub fn precompiles(spec: Spec) -> impl Iterator<Item = &'static Precompile> {
+    PRECOMPILES.iter().filter(move |p| {
+        matches!(find_precompile(spec, &p.address), Some(enabled) if std::ptr::eq(enabled, *p))
+    })
+}
 
 /// Is precompile address
 #[must_use]
-pub fn is_precompile_address(address: &Address) -> bool {
-    *address == SYSTEM_ACCOUNT_ECRECOVER
-        || *address == SYSTEM_ACCOUNT_SHA_256
-        || *address == SYSTEM_ACCOUNT_RIPEMD160
-        || *address == SYSTEM_ACCOUNT_DATACOPY
-        || *address == SYSTEM_ACCOUNT_BIGMODEXP
-        || *address == SYSTEM_ACCOUNT_BN256_ADD
-        || *address == SYSTEM_ACCOUNT_BN256_SCALAR_MUL
-        || *address == SYSTEM_ACCOUNT_BN256_PAIRING
-        || *address == SYSTEM_ACCOUNT_BLAKE2F
+pub fn is_precompile_address(spec: Spec, address: &Address) -> bool {
+    find_precompile(spec, address).is_some()
+}
+
+/// Ethereum gas cost of a precompile call, zero for unknown addresses
+#[cfg(not(target_os = "solana"))]
+#[must_use]
+pub fn precompile_gas(spec: Spec, address: &Address, input: &[u8]) -> u64 {
+    find_precompile(spec, address).map_or(0, |p| (p.gas)(input))
 }
 
 impl<B: Database> Machine<B> {
     #[must_use]
-    pub fn precompile(address: &Address, data: &[u8]) -> Option<Vec<u8>> {
-        match *address {
-            SYSTEM_ACCOUNT_ECRECOVER => Some(ecrecover::ecrecover(data)),
-            SYSTEM_ACCOUNT_SHA_256 => Some(sha256::sha256(data)),
-            SYSTEM_ACCOUNT_RIPEMD160 => Some(ripemd160::ripemd160(data)),
-            SYSTEM_ACCOUNT_DATACOPY => Some(datacopy::datacopy(data)),
-            SYSTEM_ACCOUNT_BIGMODEXP => Some(big_mod_exp::big_mod_exp(data)),
-            SYSTEM_ACCOUNT_BN256_ADD => Some(bn256::bn256_add(data)),
-            SYSTEM_ACCOUNT_BN256_SCALAR_MUL => Some(bn256::bn256_scalar_mul(data)),
-            SYSTEM_ACCOUNT_BN256_PAIRING => Some(bn256::bn256_pairing(data)),
-            SYSTEM_ACCOUNT_BLAKE2F => Some(blake2_f::blake2_f(data)),
-            _ => None,
-        }
+    pub fn precompile(spec: Spec, address: &Address, data: &[u8]) -> Option<Result<Vec<u8>>> {
+        find_precompile(spec, address).map(|p| (p.handler)(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Last two bytes of the enabled precompile addresses
+    fn addresses(spec: Spec) -> Vec<u16> {
+        precompiles(spec)
+            .map(|p| u16::from_be_bytes([p.address.0[18], p.address.0[19]]))
+            .collect()
+    }
+
+    /// Program and emulator share the table, the precompile set only depends on the chain spec
+    #[test]
+    fn precompiles_are_enabled_by_spec() {
+        assert_eq!(addresses(Spec::Shanghai), (0x01..=0x09).collect::<Vec<_>>());
+        assert_eq!(addresses(Spec::Cancun), (0x01..=0x0a).collect::<Vec<_>>());
+        assert_eq!(addresses(Spec::Prague), (0x01..=0x11).collect::<Vec<_>>());
+
+        let mut osaka: Vec<u16> = (0x01..=0x11).collect();
+        osaka.push(0x100);
+        assert_eq!(addresses(Spec::Osaka), osaka);
+
+        let bls12_g1add = Address([
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x0b,
+        ]);
+        assert!(!is_precompile_address(Spec::Cancun, &bls12_g1add));
+        assert!(is_precompile_address(Spec::Prague, &bls12_g1add));
+    }
+
+    #[test]
+    fn kzg_point_evaluation_is_computed_from_prague() {
+        assert!(!is_precompile_address(
+            Spec::Shanghai,
+            &SYSTEM_ACCOUNT_KZG_POINT_EVALUATION
+        ));
+
+        let cancun = find_precompile(Spec::Cancun, &SYSTEM_ACCOUNT_KZG_POINT_EVALUATION).unwrap();
+        let prague = find_precompile(Spec::Prague, &SYSTEM_ACCOUNT_KZG_POINT_EVALUATION).unwrap();
+        assert_eq!((cancun.spec, prague.spec), (Spec::Cancun, Spec::Prague));
+        assert!((cancun.handler)(&[0; 192]).is_err());
     }
 }
@@ -0,0 +1,126 @@
+//! RIP-7212: Precompile for secp256r1 curve support
+//! <https://github.com/ethereum/RIPs/blob/master/RIPS/rip-7212.md>
+//!
+//! Verification is computed by the program itself, two scalar multiplications on P-256
+//! may not fit into the compute budget of a transaction,
+//! so the precompile is only enabled for chains with the Osaka spec.
+//! On earlier specs the address is an empty account, in the program and in the emulator.
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInt, BigInteger, Field, PrimeField, Zero};
+use ark_secp256r1::{Affine, Fq, Fr};
+use arrayref::{array_ref, array_refs};
+
+const INPUT_LEN: usize = 160;
+
+fn to_bigint(bytes: &[u8; 32]) -> BigInt<4> {
+    let mut limbs = [0_u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.rchunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+
+    BigInt(limbs)
+}
+
+/// ECDSA verification with the message hash provided by the caller.
+fn verify(input: &[u8; INPUT_LEN]) -> Option<()> {
+    let (hash, r, s, key_x, key_y) = array_refs![input, 32, 32, 32, 32, 32];
+
+    // r and s must be in [1, n - 1]
+    let r = Fr::from_bigint(to_bigint(r)).filter(|r| !r.is_zero())?;
+    let s = Fr::from_bigint(to_bigint(s)).filter(|s| !s.is_zero())?;
+
+    let public_key = Affine::new_unchecked(
+        Fq::from_bigint(to_bigint(key_x))?,
+        Fq::from_bigint(to_bigint(key_y))?,
+    );
+    // secp256r1 has cofactor 1, any point on the curve is in the subgroup
+    if !public_key.is_on_curve() {
+        return None;
+    }
+
+    let message = Fr::from_be_bytes_mod_order(hash);
+    let s_inv = s.inverse()?;
+
+    let point = (Affine::generator() * (message * s_inv) + public_key * (r * s_inv)).into_affine();
+    let (point_x, _) = point.xy()?;
+
+    let point_x = Fr::from_le_bytes_mod_order(&point_x.into_bigint().to_bytes_le());
+    (point_x == r).then_some(())
+}
+
+/// Input is `hash | r | s | x | y`, 32 bytes each.
+/// Returns 32-byte word 1 for a valid signature and empty output otherwise.
+#[must_use]
+pub fn p256_verify(input: &[u8]) -> Vec<u8> {
+    debug_print!("p256_verify");
+
+    if input.len() != INPUT_LEN {
+        return vec![];
+    }
+
+    if verify(array_ref![input, 0, INPUT_LEN]).is_none() {
+        return vec![];
+    }
+
+    let mut output = vec![0_u8; 32];
+    output[31] = 1;
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_bytes<F: PrimeField>(value: F) -> Vec<u8> {
+        value.into_bigint().to_bytes_be()
+    }
+
+    /// Signs with the textbook ECDSA formula: r = (kG).x, s = (z + r * d) / k
+    fn signed_input() -> Vec<u8> {
+        let private_key = Fr::from(0x4242_u64);
+        let nonce = Fr::from(0x1337_u64);
+        let hash = solana_program::hash::hash(b"neon").to_bytes();
+
+        let nonce_point = (Affine::generator() * nonce).into_affine();
+        let (nonce_x, _) = nonce_point.xy().unwrap();
+        let r = Fr::from_le_bytes_mod_order(&nonce_x.into_bigint().to_bytes_le());
+        let message = Fr::from_be_bytes_mod_order(&hash);
+        let s = (message + r * private_key) * nonce.inverse().unwrap();
+
+        let public_key = (Affine::generator() * private_key).into_affine();
+        let (key_x, key_y) = public_key.xy().unwrap();
+
+        [
+            hash.to_vec(),
+            to_bytes(r),
+            to_bytes(s),
+            to_bytes(*key_x),
+            to_bytes(*key_y),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn valid_signature() {
+        let output = p256_verify(&signed_input());
+        assert_eq!(output.len(), 32);
+        assert_eq!(output[31], 1);
+    }
+
+    #[test]
+    fn invalid_signature() {
+        let mut input = signed_input();
+        input[0] ^= 1;
+        assert!(p256_verify(&input).is_empty());
+
+        let mut input = signed_input();
+        input[159] ^= 1; // not on curve
+        assert!(p256_verify(&input).is_empty());
+
+        let input = signed_input();
+        assert!(p256_verify(&input[..159]).is_empty());
+        assert!(p256_verify(&[input, vec![0]].concat()).is_empty());
+        assert!(p256_verify(&[0; INPUT_LEN]).is_empty());
+    }
+}
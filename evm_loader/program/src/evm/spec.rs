@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+/// Ethereum hardfork which defines the EVM behaviour.
+/// Selected per chain in the chain config.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum Spec {
+    /// CHAINID, SELFBALANCE
+    Istanbul,
+    /// EIP-2929 gas cost increases for state access opcodes
+    Berlin,
+    /// BASEFEE
+    London,
+    /// PUSH0, EIP-3860 initcode metering
+    Shanghai,
+    /// TLOAD, TSTORE, MCOPY, BLOBHASH, BLOBBASEFEE, EIP-6780 SELFDESTRUCT
+    #[default]
+    Cancun,
+    /// EIP-2537 BLS12-381 precompiles, other Prague changes are not implemented
+    Prague,
+    /// RIP-7212 P256VERIFY precompile, other Osaka changes are not implemented
+    Osaka,
+}
+
+impl Spec {
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Istanbul => "Istanbul",
+            Self::Berlin => "Berlin",
+            Self::London => "London",
+            Self::Shanghai => "Shanghai",
+            Self::Cancun => "Cancun",
+            Self::Prague => "Prague",
+            Self::Osaka => "Osaka",
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_enabled(self, spec: Self) -> bool {
+        self >= spec
+    }
+}
+
+impl TryFrom<u8> for Spec {
+    type Error = crate::error::Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Istanbul),
+            1 => Ok(Self::Berlin),
+            2 => Ok(Self::London),
+            3 => Ok(Self::Shanghai),
+            4 => Ok(Self::Cancun),
+            5 => Ok(Self::Prague),
+            6 => Ok(Self::Osaka),
+            _ => Err(crate::error::Error::Custom(format!(
+                "Unknown EVM spec {value}"
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for Spec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn specs_are_ordered() {
+        assert!(Spec::Cancun.is_enabled(Spec::Shanghai));
+        assert!(Spec::Shanghai.is_enabled(Spec::Shanghai));
+        assert!(!Spec::London.is_enabled(Spec::Shanghai));
+    }
+
+    #[test]
+    fn spec_from_u8() {
+        for spec in [
+            Spec::Istanbul,
+            Spec::Berlin,
+            Spec::London,
+            Spec::Shanghai,
+            Spec::Cancun,
+            Spec::Prague,
+            Spec::Osaka,
+        ] {
+            assert_eq!(Spec::try_from(spec as u8).unwrap(), spec);
+        }
+        assert!(Spec::try_from(7).is_err());
+    }
+}
@@ -51,6 +51,19 @@ impl Stack {
         slice.to_vec()
     }
 
+    /// Returns the Nth item from the top of the stack without removing it
+    #[cfg(not(target_os = "solana"))]
+    pub fn peek(&self, index: usize) -> Option<&[u8; 32]> {
+        let offset = (index + 1) * ELEMENT_SIZE;
+        let len = (self.top as usize) - (self.begin as usize);
+        if offset > len {
+            return None;
+        }
+
+        let value = unsafe { &*(self.top.sub(offset) as *const [u8; 32]) };
+        Some(value)
+    }
+
     #[inline(always)]
     unsafe fn read(&self) -> &[u8; 32] {
         &*(self.top as *const [u8; 32])
@@ -0,0 +1,192 @@
+//! Bytecode execution tests with the in-memory account storage.
+
+use ethnum::U256;
+
+use crate::account_storage::test_storage::{TestAccountStorage, CHAIN_ID};
+use crate::executor::ExecutorState;
+use crate::types::{Address, LegacyTx, Transaction, TransactionPayload};
+
+use super::{ExitStatus, Machine};
+
+const ORIGIN: Address = Address([0x11; 20]);
+const CONTRACT: Address = Address([0x22; 20]);
+const CALLEE: Address = Address([0x33; 20]);
+
+/// `PUSH1 0; MSTORE; PUSH1 32; PUSH1 0; RETURN` - returns the stack top as a word
+const RETURN_WORD: [u8; 8] = [0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+
+fn transaction(target: Address, gas_limit: U256) -> Transaction {
+    Transaction {
+        transaction: TransactionPayload::Legacy(LegacyTx {
+            nonce: 0,
+            gas_price: U256::ZERO,
+            gas_limit,
+            target: Some(target),
+            value: U256::ZERO,
+            call_data: Vec::new(),
+            v: U256::ZERO,
+            r: U256::ZERO,
+            s: U256::ZERO,
+            chain_id: Some(U256::from(CHAIN_ID)),
+            recovery_id: 0,
+        }),
+        byte_len: 0,
+        hash: [0; 32],
+        signed_hash: [0; 32],
+    }
+}
+
+/// Code which calls `target` with `opcode` and returns the call result
+fn call_code(opcode: u8, target: Address, gas: &[u8]) -> Vec<u8> {
+    let mut code = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00]; // ret and args
+    if opcode == 0xf1 {
+        code.extend([0x60, 0x00]); // value
+    }
+    code.push(0x73); // PUSH20
+    code.extend(target.as_bytes());
+    code.extend(gas);
+    code.push(opcode);
+    code.extend(RETURN_WORD);
+    code
+}
+
+async fn execute_with_gas_used(storage: &TestAccountStorage, gas_limit: U256) -> (ExitStatus, u64) {
+    let mut state = ExecutorState::new(storage);
+
+    let trx = transaction(CONTRACT, gas_limit);
+    let mut evm = Machine::new(trx, ORIGIN, &mut state, None).await.unwrap();
+    let (status, _) = evm.execute(1000, &mut state).await.unwrap();
+
+    (status, evm.gas_used())
+}
+
+async fn execute(storage: &TestAccountStorage, gas_limit: U256) -> ExitStatus {
+    let (status, _) = execute_with_gas_used(storage, gas_limit).await;
+    status
+}
+
+fn word(value: u64) -> Vec<u8> {
+    U256::from(value).to_be_bytes().to_vec()
+}
+
+#[tokio::test]
+async fn tstore_then_tload() {
+    let mut storage = TestAccountStorage::default();
+    // PUSH1 42; PUSH1 7; TSTORE; PUSH1 7; TLOAD
+    let mut code = vec![0x60, 42, 0x60, 7, 0x5d, 0x60, 7, 0x5c];
+    code.extend(RETURN_WORD);
+    storage.set_code(CONTRACT, code);
+
+    let status = execute(&storage, U256::from(100_000_u64)).await;
+    assert_eq!(status, ExitStatus::Return(word(42)));
+}
+
+#[tokio::test]
+async fn tstore_in_static_call_fails() {
+    let mut storage = TestAccountStorage::default();
+    // PUSH1 1; PUSH1 0; TSTORE; STOP
+    storage.set_code(CALLEE, vec![0x60, 0x01, 0x60, 0x00, 0x5d, 0x00]);
+
+    storage.set_code(CONTRACT, call_code(0xfa, CALLEE, &[0x5a]));
+    let status = execute(&storage, U256::from(100_000_u64)).await;
+    assert_eq!(status, ExitStatus::Return(word(0)));
+
+    storage.set_code(CONTRACT, call_code(0xf1, CALLEE, &[0x5a]));
+    let status = execute(&storage, U256::from(100_000_u64)).await;
+    assert_eq!(status, ExitStatus::Return(word(1)));
+}
+
+#[tokio::test]
+async fn gas_decreases_across_opcodes() {
+    let mut storage = TestAccountStorage::default();
+    // GAS
+    let mut code = vec![0x5a];
+    code.extend(RETURN_WORD);
+    storage.set_code(CONTRACT, code);
+
+    let (status, gas_used) = execute_with_gas_used(&storage, U256::from(100_000_u64)).await;
+    assert_eq!(status, ExitStatus::Return(word(100_000 - 21_000 - 2)));
+    assert!(gas_used > 21_000);
+
+    // GAS; GAS; SWAP1; SUB - the second GAS is charged before it reports
+    let mut code = vec![0x5a, 0x5a, 0x90, 0x03];
+    code.extend(RETURN_WORD);
+    storage.set_code(CONTRACT, code);
+
+    let status = execute(&storage, U256::from(100_000_u64)).await;
+    assert_eq!(status, ExitStatus::Return(word(2)));
+}
+
+/// Code which calls `CALLEE` with `gas` and `value` and returns its return data
+fn call_with_gas_code(gas: u32, value: u8) -> Vec<u8> {
+    let mut code = vec![
+        0x60, 0x20, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, value, 0x73,
+    ];
+    code.extend(CALLEE.as_bytes());
+    code.push(0x63); // PUSH4
+    code.extend(gas.to_be_bytes());
+    // CALL; POP; PUSH1 32; PUSH1 0; RETURN
+    code.extend([0xf1, 0x50, 0x60, 0x20, 0x60, 0x00, 0xf3]);
+    code
+}
+
+#[tokio::test]
+async fn call_forwards_the_same_gas_limit() {
+    let mut storage = TestAccountStorage::default();
+    storage.set_balance(CONTRACT, U256::from(10_u8));
+
+    let mut callee = vec![0x5a]; // GAS
+    callee.extend(RETURN_WORD);
+    storage.set_code(CALLEE, callee);
+
+    let gas_limit = U256::from(100_000_u64);
+
+    // callee reports its gas limit minus the GAS opcode cost
+    storage.set_code(CONTRACT, call_with_gas_code(50_000, 0));
+    let status = execute(&storage, gas_limit).await;
+    assert_eq!(status, ExitStatus::Return(word(50_000 - 2)));
+
+    // all but one 64th of the frame gas limit
+    storage.set_code(CONTRACT, call_with_gas_code(u32::MAX, 0));
+    let status = execute(&storage, gas_limit).await;
+    assert_eq!(status, ExitStatus::Return(word(100_000 - 100_000 / 64 - 2)));
+
+    // call stipend is added to the callee gas
+    storage.set_code(CONTRACT, call_with_gas_code(50_000, 1));
+    let status = execute(&storage, gas_limit).await;
+    assert_eq!(status, ExitStatus::Return(word(50_000 + 2_300 - 2)));
+}
+
+#[tokio::test]
+async fn jump_destinations_are_cached_by_code_address() {
+    let mut storage = TestAccountStorage::default();
+    // PUSH1 4; JUMP; INVALID; JUMPDEST
+    let jump = [0x60, 0x04, 0x56, 0xfe, 0x5b];
+
+    let mut callee = jump.to_vec();
+    callee.push(0x5a); // GAS
+    callee.extend(RETURN_WORD);
+    storage.set_code(CALLEE, callee);
+
+    let mut code = jump.to_vec();
+    for _ in 0..2 {
+        code.extend([0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00]);
+        code.push(0x73); // PUSH20
+        code.extend(CALLEE.as_bytes());
+        code.extend([0x5a, 0xf1]); // GAS; CALL
+    }
+    code.extend(RETURN_WORD);
+    storage.set_code(CONTRACT, code);
+
+    let mut state = ExecutorState::new(&storage);
+    let trx = transaction(CONTRACT, U256::from(100_000_u64));
+    let mut evm = Machine::new(trx, ORIGIN, &mut state, None).await.unwrap();
+    let (status, _) = evm.execute(1000, &mut state).await.unwrap();
+    assert_eq!(status, ExitStatus::Return(word(1)));
+
+    // Both calls into CALLEE share one bitmap, frames don't hold it anymore
+    let cache = &evm.jump_destinations_cache;
+    assert_eq!(cache.len(), 2);
+    assert_eq!(std::rc::Rc::strong_count(&cache[&CALLEE]), 1);
+    assert_eq!(std::rc::Rc::strong_count(&cache[&CONTRACT]), 2);
+}
@@ -23,15 +23,17 @@ pub enum Event {
     },
     EndVM {
         status: ExitStatus,
+        gas_used: u64,
     },
     BeginStep {
         opcode: u8,
         pc: usize,
+        gas: u64,
+        gas_cost: u64,
         stack: Vec<[u8; 32]>,
         memory: Vec<u8>,
     },
     EndStep {
-        gas_used: u64,
         return_data: Option<Vec<u8>>,
     },
     StorageAccess {
@@ -0,0 +1,131 @@
+//! Solidity ABI decoding of precompile extension arguments.
+//! Method enums are generated by `evm_loader_macro::precompile_abi`.
+
+use ethnum::U256;
+
+use crate::{
+    error::{Error, Result},
+    types::Address,
+};
+
+pub trait AbiDecode: Sized {
+    /// Decodes the argument which head starts at `position`
+    fn decode(arguments: &[u8], position: usize) -> Result<Self>;
+}
+
+fn read_word(arguments: &[u8], position: usize) -> Result<&[u8; 32]> {
+    let end = position.checked_add(32).ok_or(Error::OutOfBounds)?;
+    let word = arguments.get(position..end).ok_or(Error::OutOfBounds)?;
+
+    Ok(arrayref::array_ref![word, 0, 32])
+}
+
+fn read_bytes(arguments: &[u8], position: usize) -> Result<&[u8]> {
+    let offset = usize::decode(arguments, position)?;
+    let length = usize::decode(arguments, offset)?;
+
+    let begin = offset.saturating_add(32);
+    let end = begin.checked_add(length).ok_or(Error::OutOfBounds)?;
+
+    arguments.get(begin..end).ok_or(Error::OutOfBounds)
+}
+
+impl AbiDecode for [u8; 32] {
+    fn decode(arguments: &[u8], position: usize) -> Result<Self> {
+        read_word(arguments, position).copied()
+    }
+}
+
+impl AbiDecode for U256 {
+    fn decode(arguments: &[u8], position: usize) -> Result<Self> {
+        read_word(arguments, position).map(|word| U256::from_be_bytes(*word))
+    }
+}
+
+macro_rules! impl_abi_decode_uint {
+    ($($t:ty),*) => {
+        $(
+            impl AbiDecode for $t {
+                fn decode(arguments: &[u8], position: usize) -> Result<Self> {
+                    U256::decode(arguments, position)?
+                        .try_into()
+                        .map_err(Error::from)
+                }
+            }
+        )*
+    };
+}
+
+impl_abi_decode_uint!(u8, u16, u32, u64, u128, usize);
+
+impl AbiDecode for bool {
+    fn decode(arguments: &[u8], position: usize) -> Result<Self> {
+        match u8::decode(arguments, position)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::Custom("ABI: invalid bool".to_string())),
+        }
+    }
+}
+
+impl AbiDecode for Address {
+    fn decode(arguments: &[u8], position: usize) -> Result<Self> {
+        let word = read_word(arguments, position)?;
+        if word[..12].iter().any(|b| *b != 0) {
+            return Err(Error::Custom("ABI: invalid address".to_string()));
+        }
+
+        Ok(Address::from(*arrayref::array_ref![word, 12, 20]))
+    }
+}
+
+impl AbiDecode for Vec<u8> {
+    fn decode(arguments: &[u8], position: usize) -> Result<Self> {
+        read_bytes(arguments, position).map(<[u8]>::to_vec)
+    }
+}
+
+impl AbiDecode for String {
+    fn decode(arguments: &[u8], position: usize) -> Result<Self> {
+        let bytes = read_bytes(arguments, position)?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| Error::Custom("Invalid utf8 string".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(value: u64) -> [u8; 32] {
+        U256::from(value).to_be_bytes()
+    }
+
+    #[test]
+    fn decode_static() {
+        let arguments = [word(0xFF), word(0x100)].concat();
+
+        assert_eq!(u8::decode(&arguments, 0).unwrap(), 0xFF);
+        assert!(u8::decode(&arguments, 32).is_err());
+        assert_eq!(u64::decode(&arguments, 32).unwrap(), 0x100);
+        assert!(matches!(
+            u64::decode(&arguments, 64),
+            Err(Error::OutOfBounds)
+        ));
+    }
+
+    #[test]
+    fn decode_string() {
+        let mut arguments = [word(0x20), word(5)].concat();
+        arguments.extend_from_slice(b"hello");
+        arguments.resize(96, 0);
+
+        assert_eq!(String::decode(&arguments, 0).unwrap(), "hello");
+
+        arguments[63] = 100;
+        assert!(matches!(
+            String::decode(&arguments, 0),
+            Err(Error::OutOfBounds)
+        ));
+    }
+}
@@ -0,0 +1,298 @@
+use evm_loader_macro::precompile_abi;
+use maybe_async::maybe_async;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_instruction,
+};
+
+use crate::{
+    account::ACCOUNT_SEED_VERSION,
+    account_storage::AccountStorage,
+    error::{Error, Result},
+    executor::ExecutorState,
+    types::Address,
+};
+
+precompile_abi! {
+    #[interface = "CallSolana"]
+    pub enum CallSolanaCall {
+        /// Return the Solana account which signs instructions submitted by `execute` on behalf of the caller.
+        GetAuthority = "function getAuthority() external view returns(bytes32)",
+        /// Return the Solana account which signs instructions submitted by `executeWithSeed` on behalf of the caller.
+        /// This is PDA[ACCOUNT_SEED_VERSION, b"ContractData", msg.sender, salt], the same account as `SPLToken.findAccount`.
+        GetAuthorityWithSeed = "function getAuthorityWithSeed(bytes32 salt) external view returns(bytes32)",
+        /// Submit Solana instruction signed by `getAuthority()`.
+        /// `accounts` is a sequence of 34-byte account metas: pubkey, is_signer (0 or 1), is_writable (0 or 1).
+        /// The authority is the only allowed signer. Accounts owned by Neon EVM and the operator can't be writable.
+        Execute = "function execute(bytes32 program, bytes memory accounts, bytes memory data) external",
+        /// Submit Solana instruction signed by `getAuthorityWithSeed(salt)`.
+        /// The operator transfers `lamports` to the authority before the instruction, they are charged as a transaction fee.
+        ExecuteWithSeed = "function executeWithSeed(bytes32 salt, uint64 lamports, bytes32 program, bytes memory accounts, bytes memory data) external",
+    }
+}
+
+const ACCOUNT_META_LEN: usize = 34;
+
+impl<B: AccountStorage> ExecutorState<'_, B> {
+    #[maybe_async]
+    pub async fn call_solana(
+        &mut self,
+        address: &Address,
+        input: &[u8],
+        context: &crate::evm::Context,
+        is_static: bool,
+    ) -> Result<Vec<u8>> {
+        if context.value != 0 {
+            return Err(Error::Custom("CallSolana: value != 0".to_string()));
+        }
+
+        if &context.contract != address {
+            return Err(Error::Custom(
+                "CallSolana: callcode or delegatecall is not allowed".to_string(),
+            ));
+        }
+
+        let call = CallSolanaCall::decode(address, input)?;
+        if is_static && !call.is_view() {
+            return Err(Error::StaticModeViolation(*address));
+        }
+
+        match call {
+            CallSolanaCall::GetAuthority {} => {
+                let (authority, _) = self.backend.contract_pubkey(context.caller);
+                Ok(authority.to_bytes().to_vec())
+            }
+            CallSolanaCall::GetAuthorityWithSeed { salt } => {
+                let (authority, _) = self.contract_data_account(context.caller, &salt);
+                Ok(authority.to_bytes().to_vec())
+            }
+            CallSolanaCall::Execute {
+                program,
+                accounts,
+                data,
+            } => {
+                let (authority, bump_seed) = self.backend.contract_pubkey(context.caller);
+                let seeds = vec![
+                    vec![ACCOUNT_SEED_VERSION],
+                    context.caller.as_bytes().to_vec(),
+                    vec![bump_seed],
+                ];
+
+                let instruction = self
+                    .external_instruction(Pubkey::from(program), &accounts, data, &authority)
+                    .await?;
+                self.queue_external_instruction(instruction, seeds, 0);
+
+                Ok(vec![])
+            }
+            CallSolanaCall::ExecuteWithSeed {
+                salt,
+                lamports,
+                program,
+                accounts,
+                data,
+            } => {
+                let (authority, seeds) = self.contract_data_seeds(context.caller, &salt);
+
+                let instruction = self
+                    .external_instruction(Pubkey::from(program), &accounts, data, &authority)
+                    .await?;
+
+                if lamports > 0 {
+                    let transfer = system_instruction::transfer(
+                        &self.backend.operator(),
+                        &authority,
+                        lamports,
+                    );
+                    self.queue_external_instruction(transfer, vec![], lamports);
+                }
+                self.queue_external_instruction(instruction, seeds, 0);
+
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// Builds the instruction and checks that `authority` is its only signer
+    /// and that it doesn't modify Neon EVM accounts
+    #[maybe_async]
+    async fn external_instruction(
+        &self,
+        program_id: Pubkey,
+        accounts: &[u8],
+        data: Vec<u8>,
+        authority: &Pubkey,
+    ) -> Result<Instruction> {
+        if &program_id == self.backend.program_id() {
+            return Err(Error::Custom(
+                "CallSolana: Neon EVM program can't be called".to_string(),
+            ));
+        }
+
+        let accounts = parse_account_metas(accounts)?;
+        for meta in &accounts {
+            if meta.is_signer && &meta.pubkey != authority {
+                return Err(Error::Custom(format!(
+                    "CallSolana: {} can't be a signer",
+                    meta.pubkey
+                )));
+            }
+
+            if !meta.is_writable {
+                continue;
+            }
+
+            let owner = self
+                .backend
+                .map_solana_account(&meta.pubkey, |info| *info.owner)
+                .await;
+            if meta.pubkey == self.backend.operator() || &owner == self.backend.program_id() {
+                return Err(Error::Custom(format!(
+                    "CallSolana: {} can't be writable",
+                    meta.pubkey
+                )));
+            }
+        }
+
+        Ok(Instruction {
+            program_id,
+            accounts,
+            data,
+        })
+    }
+}
+
+fn parse_account_metas(accounts: &[u8]) -> Result<Vec<AccountMeta>> {
+    if accounts.len() % ACCOUNT_META_LEN != 0 {
+        return Err(Error::Custom(
+            "CallSolana: invalid account metas length".to_string(),
+        ));
+    }
+
+    let flag = |value: u8| match value {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(Error::Custom(
+            "CallSolana: invalid account meta flag".to_string(),
+        )),
+    };
+
+    accounts
+        .chunks_exact(ACCOUNT_META_LEN)
+        .map(|meta| {
+            let (pubkey, is_signer, is_writable) =
+                arrayref::array_refs![arrayref::array_ref![meta, 0, ACCOUNT_META_LEN], 32, 1, 1];
+
+            Ok(AccountMeta {
+                pubkey: Pubkey::from(*pubkey),
+                is_signer: flag(is_signer[0])?,
+                is_writable: flag(is_writable[0])?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{account_storage::test_storage::TestAccountStorage, executor::OwnedAccountInfo};
+
+    fn encode_account_metas(metas: &[AccountMeta]) -> Vec<u8> {
+        metas
+            .iter()
+            .flat_map(|meta| {
+                [
+                    meta.pubkey.as_ref(),
+                    &[u8::from(meta.is_signer), u8::from(meta.is_writable)],
+                ]
+                .concat()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn external_instruction_rules() {
+        let mut storage = TestAccountStorage {
+            operator: Pubkey::new_unique(),
+            ..TestAccountStorage::default()
+        };
+        storage.add_wallet(storage.operator, 1_000_000_000);
+
+        let neon_account = Pubkey::new_unique();
+        storage.add_solana_account(OwnedAccountInfo {
+            key: neon_account,
+            is_signer: false,
+            is_writable: false,
+            lamports: 1_000_000_000,
+            data: vec![0; 32],
+            owner: crate::ID,
+            executable: false,
+            rent_epoch: 0,
+        });
+
+        let state = ExecutorState::new(&storage);
+        let authority = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        let check = |metas: Vec<AccountMeta>| {
+            let accounts = encode_account_metas(&metas);
+            let state = &state;
+            async move {
+                state
+                    .external_instruction(program, &accounts, vec![1, 2, 3], &authority)
+                    .await
+            }
+        };
+
+        let metas = vec![
+            AccountMeta::new(authority, true),
+            AccountMeta::new(other, false),
+            AccountMeta::new_readonly(storage.operator, false),
+            AccountMeta::new_readonly(neon_account, false),
+        ];
+        let instruction = check(metas.clone()).await.unwrap();
+        assert_eq!(instruction.program_id, program);
+        assert_eq!(instruction.accounts, metas);
+        assert_eq!(instruction.data, vec![1, 2, 3]);
+
+        // Only the authority can sign
+        assert!(check(vec![AccountMeta::new_readonly(other, true)])
+            .await
+            .is_err());
+
+        // The operator and Neon EVM accounts can't be modified
+        assert!(check(vec![AccountMeta::new(storage.operator, false)])
+            .await
+            .is_err());
+        assert!(check(vec![AccountMeta::new(neon_account, false)])
+            .await
+            .is_err());
+
+        // Neon EVM can't be called
+        let result = state
+            .external_instruction(crate::ID, &[], vec![], &authority)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn account_metas() {
+        let key = Pubkey::new_unique();
+        let mut accounts = [key.as_ref(), &[1, 0], key.as_ref(), &[0, 1]].concat();
+
+        let metas = parse_account_metas(&accounts).unwrap();
+        assert_eq!(
+            metas,
+            vec![
+                AccountMeta::new_readonly(key, true),
+                AccountMeta::new(key, false)
+            ]
+        );
+
+        accounts[33] = 2;
+        assert!(parse_account_metas(&accounts).is_err());
+        assert!(parse_account_metas(&accounts[..33]).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 #![allow(clippy::unnecessary_wraps)]
-use std::convert::{Into, TryInto};
 
 use ethnum::U256;
+use evm_loader_macro::precompile_abi;
 use maybe_async::maybe_async;
 use mpl_token_metadata::state::{
     Creator, Metadata, TokenMetadataAccount, TokenStandard, CREATE_FEE, MAX_MASTER_EDITION_LEN,
@@ -17,56 +17,27 @@ use crate::{
     types::Address,
 };
 
-// "[0xc5, 0x73, 0x50, 0xc6]": "createMetadata(bytes32,string,string,string)"
-// "[0x4a, 0xe8, 0xb6, 0x6b]": "createMasterEdition(bytes32,uint64)"
-// "[0xf7, 0xb6, 0x37, 0xbb]": "isInitialized(bytes32)"
-// "[0x23, 0x5b, 0x2b, 0x94]": "isNFT(bytes32)"
-// "[0x9e, 0xd1, 0x9d, 0xdb]": "uri(bytes32)"
-// "[0x69, 0x1f, 0x34, 0x31]": "name(bytes32)"
-// "[0x6b, 0xaa, 0x03, 0x30]": "symbol(bytes32)"
-
-#[inline]
-fn read_u64(input: &[u8]) -> Result<u64> {
-    if input.len() < 32 {
-        return Err(Error::OutOfBounds);
+precompile_abi! {
+    #[interface = "Metaplex"]
+    pub enum MetaplexCall {
+        CreateMetadata = "function createMetadata(bytes32 _mint, string memory _name, string memory _symbol, string memory _uri) external returns(bytes32)",
+        #[grouped]
+        CreateMasterEdition = "function createMasterEdition(bytes32 mint, uint64 maxSupply) external returns(bytes32)",
+        IsInitialized = "function isInitialized(bytes32 mint) external view returns(bool)",
+        #[grouped]
+        IsNft = "function isNFT(bytes32 mint) external view returns(bool)",
+        #[grouped]
+        Uri = "function uri(bytes32 mint) external view returns(string memory)",
+        #[grouped]
+        Name = "function name(bytes32 mint) external view returns(string memory)",
+        #[grouped]
+        Symbol = "function symbol(bytes32 mint) external view returns(string memory)",
     }
-    U256::from_be_bytes(*arrayref::array_ref![input, 0, 32])
-        .try_into()
-        .map_err(Into::into)
 }
 
-#[inline]
-fn read_pubkey(input: &[u8]) -> Result<Pubkey> {
-    if input.len() < 32 {
-        return Err(Error::OutOfBounds);
-    }
-    Ok(Pubkey::new_from_array(*arrayref::array_ref![input, 0, 32]))
-}
-
-#[inline]
-fn read_string(input: &[u8], offset_position: usize, max_length: usize) -> Result<String> {
-    if input.len() < offset_position + 32 {
-        return Err(Error::OutOfBounds);
-    }
-    let offset: usize =
-        U256::from_be_bytes(*arrayref::array_ref![input, offset_position, 32]).try_into()?;
-    if input.len() < offset.saturating_add(32) {
-        return Err(Error::OutOfBounds);
-    }
-    let length = U256::from_be_bytes(*arrayref::array_ref![input, offset, 32]).try_into()?;
-    if length > max_length {
-        return Err(Error::OutOfBounds);
-    }
-
-    let begin = offset.saturating_add(32);
-    let end = begin.saturating_add(length);
-
-    if input.len() < end {
-        return Err(Error::OutOfBounds);
-    }
-    let data = input[begin..end].to_vec();
-    String::from_utf8(data).map_err(|_| Error::Custom("Invalid utf8 string".to_string()))
-}
+const MAX_NAME_LENGTH: usize = 256;
+const MAX_SYMBOL_LENGTH: usize = 256;
+const MAX_URI_LENGTH: usize = 1024;
 
 impl<B: AccountStorage> ExecutorState<'_, B> {
     #[maybe_async]
@@ -87,60 +58,37 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             ));
         }
 
-        let (selector, input) = input.split_at(4);
-        let selector: [u8; 4] = selector.try_into()?;
-
-        match selector {
-            [0xc5, 0x73, 0x50, 0xc6] => {
-                // "createMetadata(bytes32,string,string,string)"
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let mint = read_pubkey(input)?;
-                let name = read_string(input, 32, 256)?;
-                let symbol = read_string(input, 64, 256)?;
-                let uri = read_string(input, 96, 1024)?;
+        let call = MetaplexCall::decode(address, input)?;
+        if is_static && !call.is_view() {
+            return Err(Error::StaticModeViolation(*address));
+        }
 
-                self.create_metadata(context, mint, name, symbol, uri)
-            }
-            [0x4a, 0xe8, 0xb6, 0x6b] => {
-                // "createMasterEdition(bytes32,uint64)"
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
+        match call {
+            MetaplexCall::CreateMetadata {
+                mint,
+                name,
+                symbol,
+                uri,
+            } => {
+                if name.len() > MAX_NAME_LENGTH
+                    || symbol.len() > MAX_SYMBOL_LENGTH
+                    || uri.len() > MAX_URI_LENGTH
+                {
+                    return Err(Error::OutOfBounds);
                 }
 
-                let mint = read_pubkey(input)?;
-                let max_supply = read_u64(&input[32..])?;
-
-                self.create_master_edition(context, mint, Some(max_supply))
-            }
-            [0xf7, 0xb6, 0x37, 0xbb] => {
-                // "isInitialized(bytes32)"
-                let mint = read_pubkey(input)?;
-                self.is_initialized(context, mint).await
-            }
-            [0x23, 0x5b, 0x2b, 0x94] => {
-                // "isNFT(bytes32)"
-                let mint = read_pubkey(input)?;
-                self.is_nft(context, mint).await
-            }
-            [0x9e, 0xd1, 0x9d, 0xdb] => {
-                // "uri(bytes32)"
-                let mint = read_pubkey(input)?;
-                self.uri(context, mint).await
+                self.create_metadata(context, Pubkey::from(mint), name, symbol, uri)
             }
-            [0x69, 0x1f, 0x34, 0x31] => {
-                // "name(bytes32)"
-                let mint = read_pubkey(input)?;
-                self.token_name(context, mint).await
+            MetaplexCall::CreateMasterEdition { mint, max_supply } => {
+                self.create_master_edition(context, Pubkey::from(mint), Some(max_supply))
             }
-            [0x6b, 0xaa, 0x03, 0x30] => {
-                // "symbol(bytes32)"
-                let mint = read_pubkey(input)?;
-                self.symbol(context, mint).await
+            MetaplexCall::IsInitialized { mint } => {
+                self.is_initialized(context, Pubkey::from(mint)).await
             }
-            _ => Err(Error::UnknownPrecompileMethodSelector(*address, selector)),
+            MetaplexCall::IsNft { mint } => self.is_nft(context, Pubkey::from(mint)).await,
+            MetaplexCall::Uri { mint } => self.uri(context, Pubkey::from(mint)).await,
+            MetaplexCall::Name { mint } => self.token_name(context, Pubkey::from(mint)).await,
+            MetaplexCall::Symbol { mint } => self.symbol(context, Pubkey::from(mint)).await,
         }
     }
 
@@ -1,38 +1,104 @@
-use crate::{account_storage::AccountStorage, error::Result, evm::Context, types::Address};
+use crate::{
+    account_storage::AccountStorage,
+    error::Result,
+    evm::{
+        precompile::{find, Precompiled},
+        Context,
+    },
+    types::Address,
+};
 use maybe_async::maybe_async;
 
 use super::ExecutorState;
 
+mod abi;
+mod call_solana;
 mod metaplex;
 mod neon_token;
 mod query_account;
 mod spl_token;
+mod system_program;
 
-impl<B: AccountStorage> ExecutorState<'_, B> {
-    #[deprecated]
-    const _SYSTEM_ACCOUNT_ERC20_WRAPPER: Address = Address([
-        0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01,
-    ]);
-    const SYSTEM_ACCOUNT_QUERY: Address = Address([
-        0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02,
-    ]);
-    const SYSTEM_ACCOUNT_NEON_TOKEN: Address = Address([
-        0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x03,
-    ]);
-    const SYSTEM_ACCOUNT_SPL_TOKEN: Address = Address([
-        0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x04,
-    ]);
-    const SYSTEM_ACCOUNT_METAPLEX: Address = Address([
-        0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x05,
-    ]);
+#[deprecated]
+const _SYSTEM_ACCOUNT_ERC20_WRAPPER: Address = Address([
+    0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01,
+]);
+const SYSTEM_ACCOUNT_QUERY: Address = Address([
+    0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02,
+]);
+const SYSTEM_ACCOUNT_NEON_TOKEN: Address = Address([
+    0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x03,
+]);
+const SYSTEM_ACCOUNT_SPL_TOKEN: Address = Address([
+    0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x04,
+]);
+const SYSTEM_ACCOUNT_METAPLEX: Address = Address([
+    0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x05,
+]);
+const SYSTEM_ACCOUNT_CALL_SOLANA: Address = Address([
+    0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x06,
+]);
+const SYSTEM_ACCOUNT_SYSTEM_PROGRAM: Address = Address([
+    0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x07,
+]);
 
-    #[must_use]
+#[derive(Clone, Copy)]
+enum ExtensionHandler {
+    QueryAccount,
+    NeonToken,
+    SplToken,
+    Metaplex,
+    CallSolana,
+    SystemProgram,
+}
+
+/// Neon EVM precompile extension.
+/// Extensions can be entered from a static context,
+/// state-changing methods are rejected there by the handlers (see `is_view` of the ABI enums).
+#[derive(Clone, Copy)]
+pub struct PrecompileExtension {
+    address: Address,
+    handler: ExtensionHandler,
+}
+
+impl Precompiled for PrecompileExtension {
+    fn address(&self) -> &Address {
+        &self.address
+    }
+}
+
+pub const PRECOMPILE_EXTENSIONS: &[PrecompileExtension] = &[
+    PrecompileExtension {
+        address: SYSTEM_ACCOUNT_QUERY,
+        handler: ExtensionHandler::QueryAccount,
+    },
+    PrecompileExtension {
+        address: SYSTEM_ACCOUNT_NEON_TOKEN,
+        handler: ExtensionHandler::NeonToken,
+    },
+    PrecompileExtension {
+        address: SYSTEM_ACCOUNT_SPL_TOKEN,
+        handler: ExtensionHandler::SplToken,
+    },
+    PrecompileExtension {
+        address: SYSTEM_ACCOUNT_METAPLEX,
+        handler: ExtensionHandler::Metaplex,
+    },
+    PrecompileExtension {
+        address: SYSTEM_ACCOUNT_CALL_SOLANA,
+        handler: ExtensionHandler::CallSolana,
+    },
+    PrecompileExtension {
+        address: SYSTEM_ACCOUNT_SYSTEM_PROGRAM,
+        handler: ExtensionHandler::SystemProgram,
+    },
+];
+
+impl<B: AccountStorage> ExecutorState<'_, B> {
     #[allow(clippy::unused_self)]
+    #[must_use]
     pub fn is_precompile_extension(&self, address: &Address) -> bool {
-        *address == Self::SYSTEM_ACCOUNT_QUERY
-            || *address == Self::SYSTEM_ACCOUNT_NEON_TOKEN
-            || *address == Self::SYSTEM_ACCOUNT_SPL_TOKEN
-            || *address == Self::SYSTEM_ACCOUNT_METAPLEX
+        find(PRECOMPILE_EXTENSIONS, address).is_some()
     }
 
     #[maybe_async]
@@ -43,20 +109,175 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
         input: &[u8],
         is_static: bool,
     ) -> Option<Result<Vec<u8>>> {
-        match *address {
-            Self::SYSTEM_ACCOUNT_QUERY => {
-                Some(self.query_account(address, input, context, is_static).await)
+        let extension = find(PRECOMPILE_EXTENSIONS, address)?;
+
+        let result = match extension.handler {
+            ExtensionHandler::QueryAccount => {
+                self.query_account(address, input, context, is_static).await
             }
-            Self::SYSTEM_ACCOUNT_NEON_TOKEN => {
-                Some(self.neon_token(address, input, context, is_static).await)
+            ExtensionHandler::NeonToken => {
+                self.neon_token(address, input, context, is_static).await
             }
-            Self::SYSTEM_ACCOUNT_SPL_TOKEN => {
-                Some(self.spl_token(address, input, context, is_static).await)
+            ExtensionHandler::SplToken => self.spl_token(address, input, context, is_static).await,
+            ExtensionHandler::Metaplex => self.metaplex(address, input, context, is_static).await,
+            ExtensionHandler::CallSolana => {
+                self.call_solana(address, input, context, is_static).await
             }
-            Self::SYSTEM_ACCOUNT_METAPLEX => {
-                Some(self.metaplex(address, input, context, is_static).await)
+            ExtensionHandler::SystemProgram => {
+                self.system_program(address, input, context, is_static)
+                    .await
             }
-            _ => None,
+        };
+
+        Some(result)
+    }
+}
+
+/// ABI encoded call with static arguments
+#[cfg(test)]
+fn abi_call(signature: &str, arguments: &[[u8; 32]]) -> Vec<u8> {
+    let selector = solana_program::keccak::hash(signature.as_bytes()).to_bytes();
+
+    let mut input = selector[..4].to_vec();
+    for argument in arguments {
+        input.extend_from_slice(argument);
+    }
+
+    input
+}
+
+#[cfg(test)]
+fn abi_word(value: u64) -> [u8; 32] {
+    ethnum::U256::from(value).to_be_bytes()
+}
+
+#[cfg(test)]
+const TEST_CALLER: Address = Address([0x42; 20]);
+
+/// Context of a call from `TEST_CALLER` to the precompile
+#[cfg(test)]
+fn test_context(precompile: Address) -> Context {
+    Context {
+        caller: TEST_CALLER,
+        contract: precompile,
+        contract_chain_id: crate::account_storage::test_storage::CHAIN_ID,
+        value: ethnum::U256::ZERO,
+        code_address: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::evm::precompile::{is_precompile_address, precompiles};
+    use crate::evm::Spec;
+
+    #[test]
+    fn addresses_are_unique() {
+        let mut addresses: Vec<Address> = precompiles(Spec::Osaka)
+            .map(|p| p.address)
+            .chain(PRECOMPILE_EXTENSIONS.iter().map(|p| p.address))
+            .collect();
+        let count = addresses.len();
+
+        addresses.sort_unstable();
+        addresses.dedup();
+        assert_eq!(addresses.len(), count);
+
+        assert!(PRECOMPILE_EXTENSIONS
+            .iter()
+            .all(|p| !is_precompile_address(Spec::Osaka, &p.address)));
+    }
+
+    #[test]
+    fn abi_selectors() {
+        let address = Address::default();
+        let call = |selector: [u8; 4]| [selector.as_slice(), &[0; 128]].concat();
+
+        assert!(matches!(
+            spl_token::SplTokenCall::decode(&address, &call([0xb1, 0x1e, 0xcc, 0x50])),
+            Ok(spl_token::SplTokenCall::InitializeMint { .. })
+        ));
+        assert!(matches!(
+            spl_token::SplTokenCall::decode(&address, &call([0x7c, 0x0e, 0xb8, 0x10])),
+            Ok(spl_token::SplTokenCall::TransferWithSeed { .. })
+        ));
+        assert!(matches!(
+            metaplex::MetaplexCall::decode(&address, &call([0x4a, 0xe8, 0xb6, 0x6b])),
+            Ok(metaplex::MetaplexCall::CreateMasterEdition { .. })
+        ));
+        assert!(matches!(
+            neon_token::NeonTokenCall::decode(&address, &call([0x8e, 0x19, 0x89, 0x9e])),
+            Ok(neon_token::NeonTokenCall::Withdraw { .. })
+        ));
+        assert!(matches!(
+            spl_token::SplTokenCall::decode(&address, &[0x8e, 0x19]),
+            Err(Error::UnknownPrecompileMethodSelector(
+                _,
+                [0x8e, 0x19, 0, 0]
+            ))
+        ));
+
+        for (selector, deprecated) in [
+            ([0xb6, 0x4a, 0x09, 0x7e], false),
+            ([0x7d, 0xd6, 0xc1, 0xa0], false),
+            ([0x02, 0x57, 0x1b, 0xe3], false),
+            ([0x43, 0xca, 0x51, 0x61], true),
+            ([0x2b, 0x3c, 0x83, 0x22], true),
+            ([0xc4, 0xd3, 0x69, 0xb5], true),
+        ] {
+            let call = query_account::QueryAccountCall::decode(&address, &call(selector)).unwrap();
+            assert!(call.is_view());
+            assert_eq!(format!("{call:?}").starts_with("Deprecated"), deprecated);
+        }
+
+        let find_account =
+            spl_token::SplTokenCall::decode(&address, &call([0xeb, 0x7d, 0xa7, 0x8c]));
+        assert!(find_account.unwrap().is_view());
+    }
+
+    /// Solidity interfaces are generated from the ABI definitions.
+    /// Run with `UPDATE_SOLIDITY_INTERFACES=1` to rewrite them.
+    #[test]
+    fn solidity_interfaces_are_up_to_date() {
+        let interfaces = [
+            ("SPLToken.sol", spl_token::SplTokenCall::SOLIDITY_INTERFACE),
+            ("Metaplex.sol", metaplex::MetaplexCall::SOLIDITY_INTERFACE),
+            (
+                "INeonWithdraw.sol",
+                neon_token::NeonTokenCall::SOLIDITY_INTERFACE,
+            ),
+            (
+                "IQueryAccount.sol",
+                query_account::QueryAccountCall::SOLIDITY_INTERFACE,
+            ),
+            (
+                "CallSolana.sol",
+                call_solana::CallSolanaCall::SOLIDITY_INTERFACE,
+            ),
+            (
+                "SystemProgram.sol",
+                system_program::SystemProgramCall::SOLIDITY_INTERFACE,
+            ),
+        ];
+
+        let directory = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../solidity");
+        let update = std::env::var_os("UPDATE_SOLIDITY_INTERFACES").is_some();
+
+        for (file, interface) in interfaces {
+            let path = directory.join(file);
+            if update {
+                std::fs::write(&path, interface).unwrap();
+                continue;
+            }
+
+            let current = std::fs::read_to_string(&path).unwrap();
+            assert_eq!(
+                current.trim_end(),
+                interface.trim_end(),
+                "{file} is out of date"
+            );
         }
     }
 }
@@ -1,7 +1,5 @@
-use std::convert::TryInto;
-
-use arrayref::array_ref;
 use ethnum::U256;
+use evm_loader_macro::precompile_abi;
 use maybe_async::maybe_async;
 use solana_program::{
     account_info::IntoAccountInfo, program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar::Sysvar,
@@ -16,11 +14,12 @@ use crate::{
     types::Address,
 };
 
-// Neon token method ids:
-//--------------------------------------------------
-// withdraw(bytes32)           => 8e19899e
-//--------------------------------------------------
-const NEON_TOKEN_METHOD_WITHDRAW_ID: &[u8; 4] = &[0x8e, 0x19, 0x89, 0x9e];
+precompile_abi! {
+    #[interface = "INeonWithdraw"]
+    pub enum NeonTokenCall {
+        Withdraw = "function withdraw(bytes32 destination) external payable returns(bool)",
+    }
+}
 
 impl<B: AccountStorage> ExecutorState<'_, B> {
     #[maybe_async]
@@ -39,31 +38,27 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             ));
         }
 
-        let (method_id, rest) = input.split_at(4);
-        let method_id: &[u8; 4] = method_id.try_into().unwrap_or(&[0_u8; 4]);
-
-        if method_id == NEON_TOKEN_METHOD_WITHDRAW_ID {
-            if is_static {
-                return Err(Error::StaticModeViolation(*address));
-            }
+        let call = NeonTokenCall::decode(address, input)?;
+        if is_static && !call.is_view() {
+            return Err(Error::StaticModeViolation(*address));
+        }
 
-            let source = context.contract;
-            let chain_id = context.contract_chain_id;
-            let value = context.value;
-            // owner of the associated token account
-            let destination = array_ref![rest, 0, 32];
-            let destination = Pubkey::new_from_array(*destination);
+        match call {
+            NeonTokenCall::Withdraw { destination } => {
+                let source = context.contract;
+                let chain_id = context.contract_chain_id;
+                let value = context.value;
+                // owner of the associated token account
+                let destination = Pubkey::from(destination);
 
-            self.withdraw(source, chain_id, destination, value).await?;
+                self.withdraw(source, chain_id, destination, value).await?;
 
-            let mut output = vec![0_u8; 32];
-            output[31] = 1; // return true
+                let mut output = vec![0_u8; 32];
+                output[31] = 1; // return true
 
-            return Ok(output);
-        };
-
-        debug_print!("neon_token UNKNOWN");
-        Err(Error::UnknownPrecompileMethodSelector(*address, *method_id))
+                Ok(output)
+            }
+        }
     }
 
     #[maybe_async]
@@ -78,7 +73,7 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             return Err(Error::Custom("Neon Withdraw: value == 0".to_string()));
         }
 
-        let mint_address = self.backend.chain_id_to_token(chain_id);
+        let mint_address = self.backend.chain_id_to_token(chain_id)?;
 
         let mut mint_account = self.external_account(mint_address).await?;
         let mint_data = {
@@ -145,3 +140,48 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::Chain;
+    use crate::account_storage::test_storage::{
+        install_syscall_stubs, TestAccountStorage, CHAIN_ID,
+    };
+    use crate::evm::Spec;
+    use crate::executor::precompile_extension::{
+        abi_call, test_context, SYSTEM_ACCOUNT_NEON_TOKEN,
+    };
+
+    #[tokio::test]
+    async fn withdraw_from_disabled_chain() {
+        install_syscall_stubs();
+
+        let other_chain_id = CHAIN_ID + 1;
+        let mut storage = TestAccountStorage::default();
+        storage.set_chains(&[
+            Chain::new(CHAIN_ID, "neon", Pubkey::new_unique(), Spec::Cancun).unwrap(),
+            Chain::new(other_chain_id, "sol", Pubkey::new_unique(), Spec::Cancun).unwrap(),
+        ]);
+
+        let mut context = test_context(SYSTEM_ACCOUNT_NEON_TOKEN);
+        context.contract_chain_id = other_chain_id;
+        context.value = U256::new(1_000_000_000);
+
+        let withdraw = abi_call("withdraw(bytes32)", &[Pubkey::new_unique().to_bytes()]);
+
+        // Enabled chain resolves the token mint and fails on the missing mint account
+        let result = ExecutorState::new(&storage)
+            .neon_token(&SYSTEM_ACCOUNT_NEON_TOKEN, &withdraw, &context, false)
+            .await;
+        assert!(result.is_err());
+        assert!(!matches!(result, Err(Error::InvalidChainId(_))));
+
+        storage.disable_chain(other_chain_id).unwrap();
+
+        let result = ExecutorState::new(&storage)
+            .neon_token(&SYSTEM_ACCOUNT_NEON_TOKEN, &withdraw, &context, false)
+            .await;
+        assert!(matches!(result, Err(Error::InvalidChainId(id)) if id == other_chain_id));
+    }
+}
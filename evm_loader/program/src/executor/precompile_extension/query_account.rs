@@ -1,7 +1,5 @@
-use std::convert::TryInto;
-
-use arrayref::{array_ref, array_refs};
 use ethnum::U256;
+use evm_loader_macro::precompile_abi;
 use maybe_async::maybe_async;
 use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
 
@@ -12,24 +10,53 @@ use crate::{
     types::Address,
 };
 
-// QueryAccount method DEPRECATED ids:
-//-------------------------------------------
-// cache(uint256,uint64,uint64) => 0x2b3c8322
-// owner(uint256)               => 0xa123c33e
-// length(uint256)              => 0xaa8b99d2
-// lamports(uint256)            => 0x748f2d8a
-// executable(uint256)          => 0xc219a785
-// rent_epoch(uint256)          => 0xc4d369b5
-// data(uint256,uint64,uint64)  => 0x43ca5161
-//-------------------------------------------
-// QueryAccount method current ids:
-// "02571be3": "owner(bytes32)",
-// "6273448f": "lamports(bytes32)",
-// "e6bef488": "executable(bytes32)",
-// "8bb9e6f4": "rent_epoch(bytes32)"
-// "b64a097e": "info(bytes32)",
-// "a9dbaf25": "length(bytes32)",
-// "7dd6c1a0": "data(bytes32,uint64,uint64)",
+precompile_abi! {
+    #[interface = "IQueryAccount"]
+    #[declarations = r#"
+    struct AccountInfo {
+        bytes32 pubkey;
+        uint64 lamports;
+        bytes32 owner;
+        bool executable;
+        uint64 rent_epoch;
+    }
+"#]
+    #[derive(Debug)]
+    pub enum QueryAccountCall {
+        Owner = "function owner(bytes32 solana_address) external view returns(bytes32)",
+        #[grouped]
+        Lamports = "function lamports(bytes32 solana_address) external view returns(uint256)",
+        #[grouped]
+        Executable = "function executable(bytes32 solana_address) external view returns(bool)",
+        #[grouped]
+        RentEpoch = "function rent_epoch(bytes32 solana_address) external view returns(uint256)",
+        #[grouped]
+        Info = "function info(bytes32 solana_address) external view returns(AccountInfo memory)",
+        #[grouped]
+        Length = "function length(bytes32 solana_address) external view returns(uint256)",
+        /// Returns the chunk of the account data as is, without ABI encoding
+        Data = "function data(bytes32 solana_address, uint64 offset, uint64 len) external view",
+        /// Deprecated methods, the account is passed as uint256
+        /// cache() does nothing
+        DeprecatedCache = "function cache(uint256 solana_address, uint64 offset, uint64 len) external view",
+        #[grouped]
+        DeprecatedOwner = "function owner(uint256 solana_address) external view returns(bytes32)",
+        #[grouped]
+        DeprecatedLamports = "function lamports(uint256 solana_address) external view returns(uint256)",
+        #[grouped]
+        DeprecatedExecutable = "function executable(uint256 solana_address) external view returns(bool)",
+        #[grouped]
+        DeprecatedRentEpoch = "function rent_epoch(uint256 solana_address) external view returns(uint256)",
+        #[grouped]
+        DeprecatedLength = "function length(uint256 solana_address) external view returns(uint256)",
+        #[grouped]
+        DeprecatedData = "function data(uint256 solana_address, uint64 offset, uint64 len) external view",
+    }
+}
+
+fn deprecated_address(address: U256) -> Pubkey {
+    Pubkey::new_from_array(address.to_be_bytes())
+}
 
 impl<B: AccountStorage> ExecutorState<'_, B> {
     #[maybe_async]
@@ -46,59 +73,82 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             return Err(Error::Custom("Query Account: value != 0".to_string()));
         }
 
-        let (method_id, rest) = input.split_at(4);
-        let method_id: [u8; 4] = method_id.try_into()?;
-
-        let (account_address, rest) = rest.split_at(32);
-        let account_address = Pubkey::try_from(account_address)?;
-
-        match method_id {
-            [0x2b, 0x3c, 0x83, 0x22] => {
-                // cache(uint256,uint64,uint64)
-                // deprecated
-                Ok(Vec::new())
-            }
-            [0xa1, 0x23, 0xc3, 0x3e] | [0x02, 0x57, 0x1b, 0xe3] => {
+        match QueryAccountCall::decode(address, input)? {
+            QueryAccountCall::DeprecatedCache { .. } => Ok(Vec::new()),
+            QueryAccountCall::Owner { solana_address } => {
+                let account_address = Pubkey::from(solana_address);
                 debug_print!("query_account.owner({})", &account_address);
                 self.account_owner(&account_address).await
             }
-            [0xaa, 0x8b, 0x99, 0xd2] | [0xa9, 0xdb, 0xaf, 0x25] => {
+            QueryAccountCall::DeprecatedOwner { solana_address } => {
+                self.account_owner(&deprecated_address(solana_address))
+                    .await
+            }
+            QueryAccountCall::Length { solana_address } => {
+                let account_address = Pubkey::from(solana_address);
                 debug_print!("query_account.length({})", &account_address);
                 self.account_data_length(&account_address).await
             }
-            [0x74, 0x8f, 0x2d, 0x8a] | [0x62, 0x73, 0x44, 0x8f] => {
+            QueryAccountCall::DeprecatedLength { solana_address } => {
+                self.account_data_length(&deprecated_address(solana_address))
+                    .await
+            }
+            QueryAccountCall::Lamports { solana_address } => {
+                let account_address = Pubkey::from(solana_address);
                 debug_print!("query_account.lamports({})", &account_address);
                 self.account_lamports(&account_address).await
             }
-            [0xc2, 0x19, 0xa7, 0x85] | [0xe6, 0xbe, 0xf4, 0x88] => {
+            QueryAccountCall::DeprecatedLamports { solana_address } => {
+                self.account_lamports(&deprecated_address(solana_address))
+                    .await
+            }
+            QueryAccountCall::Executable { solana_address } => {
+                let account_address = Pubkey::from(solana_address);
                 debug_print!("query_account.executable({})", &account_address);
                 self.account_is_executable(&account_address).await
             }
-            [0xc4, 0xd3, 0x69, 0xb5] | [0x8b, 0xb9, 0xe6, 0xf4] => {
+            QueryAccountCall::DeprecatedExecutable { solana_address } => {
+                self.account_is_executable(&deprecated_address(solana_address))
+                    .await
+            }
+            QueryAccountCall::RentEpoch { solana_address } => {
+                let account_address = Pubkey::from(solana_address);
                 debug_print!("query_account.rent_epoch({})", &account_address);
                 self.account_rent_epoch(&account_address).await
             }
-            [0x43, 0xca, 0x51, 0x61] | [0x7d, 0xd6, 0xc1, 0xa0] => {
-                let arguments = array_ref![rest, 0, 64];
-                let (offset, length) = array_refs!(arguments, 32, 32);
-                let offset = U256::from_be_bytes(*offset).try_into()?;
-                let length = U256::from_be_bytes(*length).try_into()?;
+            QueryAccountCall::DeprecatedRentEpoch { solana_address } => {
+                self.account_rent_epoch(&deprecated_address(solana_address))
+                    .await
+            }
+            QueryAccountCall::Data {
+                solana_address,
+                offset,
+                len,
+            } => {
+                let account_address = Pubkey::from(solana_address);
                 debug_print!(
                     "query_account.data({}, {}, {})",
                     account_address,
                     offset,
-                    length
+                    len
                 );
-                self.account_data(&account_address, offset, length).await
+                self.account_data(&account_address, offset.try_into()?, len.try_into()?)
+                    .await
             }
-            [0xb6, 0x4a, 0x09, 0x7e] => {
+            QueryAccountCall::DeprecatedData {
+                solana_address,
+                offset,
+                len,
+            } => {
+                let account_address = deprecated_address(solana_address);
+                self.account_data(&account_address, offset.try_into()?, len.try_into()?)
+                    .await
+            }
+            QueryAccountCall::Info { solana_address } => {
+                let account_address = Pubkey::from(solana_address);
                 debug_print!("query_account.info({})", &account_address);
                 self.account_info(&account_address).await
             }
-            _ => {
-                debug_print!("query_account UNKNOWN {:?}", method_id);
-                Err(Error::UnknownPrecompileMethodSelector(*address, method_id))
-            }
         }
     }
 
@@ -187,7 +237,7 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             .map_solana_account(address, |info| {
                 info.data
                     .borrow()
-                    .get(offset..offset + length)
+                    .get(offset..offset.saturating_add(length))
                     .map(<[u8]>::to_vec)
             })
             .await
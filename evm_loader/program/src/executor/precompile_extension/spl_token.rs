@@ -1,10 +1,30 @@
-use std::convert::{Into, TryInto};
+use std::convert::Into;
 
-use ethnum::U256;
+use ethnum::{I256, U256};
+use evm_loader_macro::precompile_abi;
 use maybe_async::maybe_async;
 use solana_program::{
-    program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent,
-    system_instruction, system_program, sysvar::Sysvar,
+    clock::Clock, instruction::Instruction, program_error::ProgramError, program_pack::Pack,
+    pubkey::Pubkey, rent::Rent, system_program, sysvar::Sysvar,
+};
+use spl_associated_token_account::{
+    error::AssociatedTokenAccountError, get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token_2022::{
+    extension::{
+        cpi_guard::CpiGuard,
+        immutable_owner::ImmutableOwner,
+        interest_bearing_mint::InterestBearingConfig,
+        memo_transfer::MemoTransfer,
+        metadata_pointer::MetadataPointer,
+        non_transferable::NonTransferable,
+        permanent_delegate::PermanentDelegate,
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+        transfer_hook::{self, TransferHook},
+        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    state::{Account, Mint},
 };
 
 use crate::{
@@ -15,52 +35,103 @@ use crate::{
     types::Address,
 };
 
-// [0xa9, 0xc1, 0x58, 0x06] : "approve(bytes32,bytes32,uint64)",
-// [0xc0, 0x67, 0xee, 0xbb] : "burn(bytes32,bytes32,uint64)",
-// [0x57, 0x82, 0xa0, 0x43] : "closeAccount(bytes32)",
-// [0x6d, 0xa9, 0xde, 0x75] : "isSystemAccount(bytes32)",
-// [0xeb, 0x7d, 0xa7, 0x8c] : "findAccount(bytes32)",
-// [0x44, 0xef, 0x32, 0x44] : "freeze(bytes32)",
-// [0xd1, 0xde, 0x50, 0x11] : "getAccount(bytes32)",
-// [0xa2, 0xce, 0x9c, 0x1f] : "getMint(bytes32)",
-// [0xda, 0xa1, 0x2c, 0x5c] : "initializeAccount(bytes32,bytes32)",
-// [0xfc, 0x86, 0xb7, 0x17] : "initializeAccount(bytes32,bytes32,bytes32)",
-// [0xb1, 0x1e, 0xcc, 0x50] : "initializeMint(bytes32,uint8)",
-// [0xc3, 0xf3, 0xf2, 0xf2] : "initializeMint(bytes32,uint8,bytes32,bytes32)",
-// [0xc9, 0xd0, 0xe2, 0xfd] : "mintTo(bytes32,bytes32,uint64)",
-// [0xb7, 0x5c, 0x7d, 0xc6] : "revoke(bytes32)",
-// [0x3d, 0x71, 0x8c, 0x9a] : "thaw(bytes32,bytes32)",
-// [0x78, 0x42, 0x3b, 0xcf] : "transfer(bytes32,bytes32,uint64)"
-// [0x7c, 0x0e, 0xb8, 0x10] : "transferWithSeed(bytes32,bytes32,bytes32,uint64)"
-
-#[inline]
-fn read_u8(input: &[u8]) -> Result<u8> {
-    U256::from_be_bytes(*arrayref::array_ref![input, 0, 32])
-        .try_into()
-        .map_err(Into::into)
-}
+precompile_abi! {
+    #[interface = "SPLToken"]
+    #[declarations = r#"
+    enum AccountState {
+        Uninitialized,
+        Initialized,
+        Frozen
+    }
 
-#[inline]
-fn read_u64(input: &[u8]) -> Result<u64> {
-    U256::from_be_bytes(*arrayref::array_ref![input, 0, 32])
-        .try_into()
-        .map_err(Into::into)
-}
+    struct Account {
+        bytes32 mint;
+        bytes32 owner;
+        uint64 amount;
+        bytes32 delegate;
+        uint64 delegated_amount;
+        bytes32 close_authority;
+        AccountState state;
+    }
 
-#[inline]
-fn read_pubkey(input: &[u8]) -> Result<Pubkey> {
-    if input.len() < 32 {
-        return Err(Error::OutOfBounds);
+    struct Mint {
+        uint64 supply;
+        uint8 decimals;
+        bool isInitialized;
+        bytes32 freezeAuthority;
+        bytes32 mintAuthority;
     }
-    Ok(Pubkey::new_from_array(*arrayref::array_ref![input, 0, 32]))
-}
 
-#[inline]
-fn read_salt(input: &[u8]) -> Result<&[u8; 32]> {
-    if input.len() < 32 {
-        return Err(Error::OutOfBounds);
+    struct MintExtensions {
+        bytes32 program;
+        uint16 transferFeeBasisPoints;
+        uint64 maximumTransferFee;
+        uint64 withheldFeeAmount;
+        int16 interestRate;
+        bytes32 metadataAddress;
+        bytes32 permanentDelegate;
+        bytes32 transferHookProgram;
+        bool nonTransferable;
+    }
+
+    struct AccountExtensions {
+        bytes32 program;
+        uint64 withheldFeeAmount;
+        bool requireMemo;
+        bool cpiGuard;
+        bool immutableOwner;
+    }
+"#]
+    pub enum SplTokenCall {
+        FindAccount = "function findAccount(bytes32 salt) external pure returns(bytes32)",
+        IsSystemAccount = "function isSystemAccount(bytes32 account) external view returns(bool)",
+        /// Return spl_token account data. This function checks the account is owned by correct spl_token.
+        /// Return default not initialized spl_token account data if corresponded Solana account doesn't exist.
+        GetAccount = "function getAccount(bytes32 account) external view returns(Account memory)",
+        /// Return spl_token mint data. This function checks the mint is owned by correct spl_token.
+        /// Return default not initialized spl_token mint data if corresponded Solana account doesn't exist.
+        GetMint = "function getMint(bytes32 account) external view returns(Mint memory)",
+        /// Return Token-2022 extensions of the mint. Fields of missing extensions are zero.
+        /// `program` is the token program which owns the mint.
+        GetMintExtensions = "function getMintExtensions(bytes32 mint) external view returns(MintExtensions memory)",
+        /// Return Token-2022 extensions of the token account. Fields of missing extensions are zero.
+        /// `program` is the token program which owns the account.
+        GetAccountExtensions = "function getAccountExtensions(bytes32 account) external view returns(AccountExtensions memory)",
+        InitializeMint = "function initializeMint(bytes32 salt, uint8 decimals) external returns(bytes32)",
+        #[grouped]
+        InitializeMintWithAuthorities = "function initializeMint(bytes32 salt, uint8 decimals, bytes32 mint_authority, bytes32 freeze_authority) external returns(bytes32)",
+        /// Create a mint owned by the Token-2022 program.
+        /// Token accounts for the mint are created with `initializeAccount`, all other methods work for both programs.
+        InitializeMint2022 = "function initializeMint2022(bytes32 salt, uint8 decimals) external returns(bytes32)",
+        #[grouped]
+        InitializeMint2022WithAuthorities = "function initializeMint2022(bytes32 salt, uint8 decimals, bytes32 mint_authority, bytes32 freeze_authority) external returns(bytes32)",
+        InitializeAccount = "function initializeAccount(bytes32 salt, bytes32 mint) external returns(bytes32)",
+        #[grouped]
+        InitializeAccountWithOwner = "function initializeAccount(bytes32 salt, bytes32 mint, bytes32 owner) external returns(bytes32)",
+        CloseAccount = "function closeAccount(bytes32 account) external",
+        MintTo = "function mintTo(bytes32 mint, bytes32 account, uint64 amount) external",
+        #[grouped]
+        Burn = "function burn(bytes32 mint, bytes32 account, uint64 amount) external",
+        Approve = "function approve(bytes32 source, bytes32 target, uint64 amount) external",
+        #[grouped]
+        Revoke = "function revoke(bytes32 source) external",
+        Transfer = "function transfer(bytes32 source, bytes32 target, uint64 amount) external",
+        /// transfer funds from spl-token accounts owned by Solana user.
+        /// This method uses PDA[ACCOUNT_SEED_VERSION, b"AUTH", msg.sender, seed] to authorize transfer
+        TransferWithSeed = "function transferWithSeed(bytes32 seed, bytes32 source, bytes32 target, uint64 amount) external",
+        Freeze = "function freeze(bytes32 mint, bytes32 account) external",
+        #[grouped]
+        Thaw = "function thaw(bytes32 mint, bytes32 account) external",
+        /// Return associated token account of the Solana wallet `owner` for the `mint`.
+        /// The account is derived for the token program which owns the mint.
+        FindAssociatedTokenAccount = "function findAssociatedTokenAccount(bytes32 owner, bytes32 mint) external view returns(bytes32)",
+        /// Create associated token account of the Solana wallet `owner` for the `mint`, if it doesn't exist.
+        /// Rent is paid by the operator and included in the transaction fee.
+        CreateAssociatedTokenAccount = "function createAssociatedTokenAccount(bytes32 owner, bytes32 mint) external returns(bytes32)",
+        /// Transfer funds to the associated token account of the Solana wallet `owner`.
+        /// The associated token account is created for the mint of the `source` account if it doesn't exist.
+        TransferToAssociatedTokenAccount = "function transferToAssociatedTokenAccount(bytes32 source, bytes32 owner, uint64 amount) external returns(bytes32)",
     }
-    Ok(arrayref::array_ref![input, 0, 32])
 }
 
 impl<B: AccountStorage> ExecutorState<'_, B> {
@@ -83,216 +154,226 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             ));
         }
 
-        let (selector, input) = input.split_at(4);
-        let selector: [u8; 4] = selector.try_into()?;
-
-        match selector {
-            [0xb1, 0x1e, 0xcc, 0x50] => {
-                // initializeMint(bytes32 seed, uint8 decimals)
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let seed = read_salt(input)?;
-                let decimals = read_u8(&input[32..])?;
+        let call = SplTokenCall::decode(address, input)?;
+        if is_static && !call.is_view() {
+            return Err(Error::StaticModeViolation(*address));
+        }
 
-                self.initialize_mint(context, seed, decimals, None, None)
+        match call {
+            SplTokenCall::InitializeMint { salt, decimals } => {
+                self.initialize_mint(context, &spl_token::ID, &salt, decimals, None, None)
                     .await
             }
-            [0xc3, 0xf3, 0xf2, 0xf2] => {
-                // initializeMint(bytes32 seed, uint8 decimals, bytes32 mint_authority, bytes32 freeze_authority)
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let seed = read_salt(input)?;
-                let decimals = read_u8(&input[32..])?;
-                let mint_authority = read_pubkey(&input[64..])?;
-                let freeze_authority = read_pubkey(&input[96..])?;
+            SplTokenCall::InitializeMintWithAuthorities {
+                salt,
+                decimals,
+                mint_authority,
+                freeze_authority,
+            } => {
                 self.initialize_mint(
                     context,
-                    seed,
+                    &spl_token::ID,
+                    &salt,
                     decimals,
-                    Some(mint_authority),
-                    Some(freeze_authority),
+                    Some(Pubkey::from(mint_authority)),
+                    Some(Pubkey::from(freeze_authority)),
                 )
                 .await
             }
-            [0xda, 0xa1, 0x2c, 0x5c] => {
-                // initializeAccount(bytes32 seed, bytes32 mint)
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let seed = read_salt(input)?;
-                let mint = read_pubkey(&input[32..])?;
-
-                self.initialize_account(context, seed, mint, None).await
+            SplTokenCall::InitializeMint2022 { salt, decimals } => {
+                self.initialize_mint(context, &spl_token_2022::ID, &salt, decimals, None, None)
+                    .await
             }
-            [0xfc, 0x86, 0xb7, 0x17] => {
-                // initializeAccount(bytes32 seed, bytes32 mint, bytes32 owner)
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let seed = read_salt(input)?;
-                let mint = read_pubkey(&input[32..])?;
-                let owner = read_pubkey(&input[64..])?;
-                self.initialize_account(context, seed, mint, Some(owner))
+            SplTokenCall::InitializeMint2022WithAuthorities {
+                salt,
+                decimals,
+                mint_authority,
+                freeze_authority,
+            } => {
+                self.initialize_mint(
+                    context,
+                    &spl_token_2022::ID,
+                    &salt,
+                    decimals,
+                    Some(Pubkey::from(mint_authority)),
+                    Some(Pubkey::from(freeze_authority)),
+                )
+                .await
+            }
+            SplTokenCall::InitializeAccount { salt, mint } => {
+                self.initialize_account(context, &salt, Pubkey::from(mint), None)
                     .await
             }
-            [0x57, 0x82, 0xa0, 0x43] => {
-                // closeAccount(bytes32 account)
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let account = read_pubkey(input)?;
-                self.close_account(context, account)
+            SplTokenCall::InitializeAccountWithOwner { salt, mint, owner } => {
+                self.initialize_account(
+                    context,
+                    &salt,
+                    Pubkey::from(mint),
+                    Some(Pubkey::from(owner)),
+                )
+                .await
             }
-            [0xa9, 0xc1, 0x58, 0x06] => {
-                // approve(bytes32 source, bytes32 target, uint64 amount)
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let source = read_pubkey(input)?;
-                let target = read_pubkey(&input[32..])?;
-                let amount = read_u64(&input[64..])?;
-                self.approve(context, source, target, amount)
+            SplTokenCall::CloseAccount { account } => {
+                self.close_account(context, Pubkey::from(account)).await
             }
-            [0xb7, 0x5c, 0x7d, 0xc6] => {
-                // revoke(bytes32 source)
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let source = read_pubkey(input)?;
-                self.revoke(context, source)
+            SplTokenCall::Approve {
+                source,
+                target,
+                amount,
+            } => {
+                self.approve(context, Pubkey::from(source), Pubkey::from(target), amount)
+                    .await
             }
-            [0x78, 0x42, 0x3b, 0xcf] => {
-                // transfer(bytes32 source, bytes32 target, uint64 amount)
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let source = read_pubkey(input)?;
-                let target = read_pubkey(&input[32..])?;
-                let amount = read_u64(&input[64..])?;
-                self.transfer(context, source, target, amount)
+            SplTokenCall::Revoke { source } => self.revoke(context, Pubkey::from(source)).await,
+            SplTokenCall::Transfer {
+                source,
+                target,
+                amount,
+            } => {
+                self.transfer(context, Pubkey::from(source), Pubkey::from(target), amount)
+                    .await
             }
-            [0x7c, 0x0e, 0xb8, 0x10] => {
-                // transferWithSeed(bytes32,bytes32,bytes32,uint64)
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let seed = read_salt(input)?;
-                let source = read_pubkey(&input[32..])?;
-                let target = read_pubkey(&input[64..])?;
-                let amount = read_u64(&input[96..])?;
-
-                self.transfer_with_seed(context, seed, source, target, amount)
+            SplTokenCall::TransferWithSeed {
+                seed,
+                source,
+                target,
+                amount,
+            } => {
+                self.transfer_with_seed(
+                    context,
+                    &seed,
+                    Pubkey::from(source),
+                    Pubkey::from(target),
+                    amount,
+                )
+                .await
             }
-            [0xc9, 0xd0, 0xe2, 0xfd] => {
-                // mintTo(bytes32 mint, bytes32 account, uint64 amount)
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let mint = read_pubkey(input)?;
-                let account = read_pubkey(&input[32..])?;
-                let amount = read_u64(&input[64..])?;
-                self.mint_to(context, mint, account, amount)
+            SplTokenCall::MintTo {
+                mint,
+                account,
+                amount,
+            } => {
+                self.mint_to(context, Pubkey::from(mint), Pubkey::from(account), amount)
+                    .await
             }
-            [0xc0, 0x67, 0xee, 0xbb] => {
-                // burn(bytes32 mint, bytes32 account, uint64 amount)
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let mint = read_pubkey(input)?;
-                let account = read_pubkey(&input[32..])?;
-                let amount = read_u64(&input[64..])?;
-                self.burn_spl_token(context, mint, account, amount)
+            SplTokenCall::Burn {
+                mint,
+                account,
+                amount,
+            } => {
+                self.burn_spl_token(context, Pubkey::from(mint), Pubkey::from(account), amount)
+                    .await
             }
-            [0x44, 0xef, 0x32, 0x44] => {
-                // freeze(bytes32 mint, bytes32 account)
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let mint = read_pubkey(input)?;
-                let account = read_pubkey(&input[32..])?;
-                self.freeze(context, mint, account)
+            SplTokenCall::Freeze { mint, account } => {
+                self.freeze(context, Pubkey::from(mint), Pubkey::from(account))
+                    .await
             }
-            [0x3d, 0x71, 0x8c, 0x9a] => {
-                // thaw(bytes32 mint, bytes32 account)
-                if is_static {
-                    return Err(Error::StaticModeViolation(*address));
-                }
-
-                let mint = read_pubkey(input)?;
-                let account = read_pubkey(&input[32..])?;
-                self.thaw(context, mint, account)
+            SplTokenCall::Thaw { mint, account } => {
+                self.thaw(context, Pubkey::from(mint), Pubkey::from(account))
+                    .await
+            }
+            SplTokenCall::FindAssociatedTokenAccount { owner, mint } => {
+                self.find_associated_token_account(Pubkey::from(owner), Pubkey::from(mint))
+                    .await
+            }
+            SplTokenCall::CreateAssociatedTokenAccount { owner, mint } => {
+                self.create_associated_token_account(Pubkey::from(owner), Pubkey::from(mint))
+                    .await
             }
-            [0xeb, 0x7d, 0xa7, 0x8c] => {
-                // findAccount(bytes32 seed)
-                let seed = read_salt(input)?;
-                self.find_account(context, seed)
+            SplTokenCall::TransferToAssociatedTokenAccount {
+                source,
+                owner,
+                amount,
+            } => {
+                self.transfer_to_associated_token_account(
+                    context,
+                    Pubkey::from(source),
+                    Pubkey::from(owner),
+                    amount,
+                )
+                .await
             }
-            [0x6d, 0xa9, 0xde, 0x75] => {
-                // isSystemAccount(bytes32 account)
-                let account = read_pubkey(input)?;
-                self.is_system_account(context, account).await
+            SplTokenCall::FindAccount { salt } => self.find_account(context, &salt),
+            SplTokenCall::IsSystemAccount { account } => {
+                self.is_system_account(context, Pubkey::from(account)).await
             }
-            [0xd1, 0xde, 0x50, 0x11] => {
-                // getAccount(bytes32 account)
-                let account = read_pubkey(input)?;
-                self.get_account(context, account).await
+            SplTokenCall::GetAccount { account } => {
+                self.get_account(context, Pubkey::from(account)).await
             }
-            [0xa2, 0xce, 0x9c, 0x1f] => {
-                // getMint(bytes32 account)
-                let account = read_pubkey(input)?;
-                self.get_mint(context, account).await
+            SplTokenCall::GetMint { account } => {
+                self.get_mint(context, Pubkey::from(account)).await
+            }
+            SplTokenCall::GetMintExtensions { mint } => {
+                self.get_mint_extensions(context, Pubkey::from(mint)).await
+            }
+            SplTokenCall::GetAccountExtensions { account } => {
+                self.get_account_extensions(context, Pubkey::from(account))
+                    .await
             }
-            _ => Err(Error::UnknownPrecompileMethodSelector(*address, selector)),
         }
     }
 
-    fn create_account(
-        &mut self,
-        account: &OwnedAccountInfo,
-        space: usize,
-        seeds: Vec<Vec<u8>>,
-    ) -> Result<()> {
-        let rent = Rent::get()?;
-        let minimum_balance = rent.minimum_balance(space);
-
-        let required_lamports = minimum_balance.saturating_sub(account.lamports);
+    /// Token program which owns `key`: SPL Token or Token-2022
+    #[maybe_async]
+    async fn token_program(&mut self, key: Pubkey) -> Result<Pubkey> {
+        let account = self.external_account(key).await?;
+        token_program_id(&account)
+    }
 
-        if required_lamports > 0 {
-            let transfer = system_instruction::transfer(
-                &self.backend.operator(),
-                &account.key,
-                required_lamports,
-            );
-            self.queue_external_instruction(transfer, vec![], required_lamports);
+    /// SPL Token `transfer` or, for Token-2022, `transfer_checked` which requires the mint
+    #[maybe_async]
+    async fn transfer_instruction(
+        &mut self,
+        source: Pubkey,
+        target: Pubkey,
+        authority: &Pubkey,
+        amount: u64,
+    ) -> Result<Instruction> {
+        let source_account = self.external_account(source).await?;
+        let program_id = token_program_id(&source_account)?;
+
+        if spl_token::check_id(&program_id) {
+            let transfer = spl_token::instruction::transfer(
+                &program_id,
+                &source,
+                &target,
+                authority,
+                &[],
+                amount,
+            )?;
+            return Ok(transfer);
         }
 
-        let allocate = system_instruction::allocate(&account.key, space.try_into().unwrap());
-        self.queue_external_instruction(allocate, seeds.clone(), 0);
+        let mint = StateWithExtensions::<Account>::unpack(&source_account.data)?
+            .base
+            .mint;
+        let mint_account = self.external_account(mint).await?;
+        let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data)?;
 
-        let assign = system_instruction::assign(&account.key, &spl_token::ID);
-        self.queue_external_instruction(assign, seeds, 0);
+        if transfer_hook::get_program_id(&mint_state).is_some() {
+            return Err(Error::Custom(
+                "SplToken: mints with transfer hook are not supported".to_string(),
+            ));
+        }
 
-        Ok(())
+        let transfer = spl_token_2022::instruction::transfer_checked(
+            &program_id,
+            &source,
+            &mint,
+            &target,
+            authority,
+            &[],
+            amount,
+            mint_state.base.decimals,
+        )?;
+        Ok(transfer)
     }
 
     #[maybe_async]
     async fn initialize_mint(
         &mut self,
         context: &crate::evm::Context,
+        program_id: &Pubkey,
         seed: &[u8],
         decimals: u8,
         mint_authority: Option<Pubkey>,
@@ -301,33 +382,17 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
         let signer = context.caller;
         let (signer_pubkey, _) = self.backend.contract_pubkey(signer);
 
-        let (mint_key, bump_seed) = Pubkey::find_program_address(
-            &[
-                &[ACCOUNT_SEED_VERSION],
-                b"ContractData",
-                signer.as_bytes(),
-                seed,
-            ],
-            self.backend.program_id(),
-        );
+        let (mint_key, seeds) = self.contract_data_seeds(signer, seed);
 
         let account = self.external_account(mint_key).await?;
         if !system_program::check_id(&account.owner) {
             return Err(Error::AccountInvalidOwner(mint_key, system_program::ID));
         }
 
-        let seeds: Vec<Vec<u8>> = vec![
-            vec![ACCOUNT_SEED_VERSION],
-            b"ContractData".to_vec(),
-            signer.as_bytes().to_vec(),
-            seed.to_vec(),
-            vec![bump_seed],
-        ];
+        self.create_account(&account, program_id, Mint::LEN, seeds)?;
 
-        self.create_account(&account, spl_token::state::Mint::LEN, seeds)?;
-
-        let initialize_mint = spl_token::instruction::initialize_mint(
-            &spl_token::ID,
+        let initialize_mint = spl_token_2022::instruction::initialize_mint2(
+            program_id,
             &mint_key,
             &mint_authority.unwrap_or(signer_pubkey),
             Some(&freeze_authority.unwrap_or(signer_pubkey)),
@@ -349,43 +414,36 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
         let signer = context.caller;
         let (signer_pubkey, _) = self.backend.contract_pubkey(signer);
 
-        let (account_key, bump_seed) = Pubkey::find_program_address(
-            &[
-                &[ACCOUNT_SEED_VERSION],
-                b"ContractData",
-                signer.as_bytes(),
-                seed,
-            ],
-            self.backend.program_id(),
-        );
+        let (account_key, seeds) = self.contract_data_seeds(signer, seed);
 
         let account = self.external_account(account_key).await?;
         if !system_program::check_id(&account.owner) {
             return Err(Error::AccountInvalidOwner(account_key, system_program::ID));
         }
 
-        let seeds: Vec<Vec<u8>> = vec![
-            vec![ACCOUNT_SEED_VERSION],
-            b"ContractData".to_vec(),
-            signer.as_bytes().to_vec(),
-            seed.to_vec(),
-            vec![bump_seed],
-        ];
+        let mint_account = self.external_account(mint).await?;
+        let program_id = token_program_id(&mint_account)?;
+        let space = token_account_len(&mint_account, &[])?;
 
-        self.create_account(&account, spl_token::state::Account::LEN, seeds)?;
+        self.create_account(&account, &program_id, space, seeds)?;
 
-        let initialize_mint = spl_token::instruction::initialize_account2(
-            &spl_token::ID,
+        let initialize_account = spl_token_2022::instruction::initialize_account3(
+            &program_id,
             &account_key,
             &mint,
             &owner.unwrap_or(signer_pubkey),
         )?;
-        self.queue_external_instruction(initialize_mint, vec![], 0);
+        self.queue_external_instruction(initialize_account, vec![], 0);
 
         Ok(account_key.to_bytes().to_vec())
     }
 
-    fn close_account(&mut self, context: &crate::evm::Context, account: Pubkey) -> Result<Vec<u8>> {
+    #[maybe_async]
+    async fn close_account(
+        &mut self,
+        context: &crate::evm::Context,
+        account: Pubkey,
+    ) -> Result<Vec<u8>> {
         let signer = context.caller;
         let (signer_pubkey, bump_seed) = self.backend.contract_pubkey(signer);
 
@@ -395,8 +453,9 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             vec![bump_seed],
         ];
 
-        let close_account = spl_token::instruction::close_account(
-            &spl_token::ID,
+        let program_id = self.token_program(account).await?;
+        let close_account = spl_token_2022::instruction::close_account(
+            &program_id,
             &account,
             &self.backend.operator(),
             &signer_pubkey,
@@ -407,7 +466,8 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
         Ok(vec![])
     }
 
-    fn approve(
+    #[maybe_async]
+    async fn approve(
         &mut self,
         context: &crate::evm::Context,
         source: Pubkey,
@@ -423,8 +483,9 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             vec![bump_seed],
         ];
 
-        let approve = spl_token::instruction::approve(
-            &spl_token::ID,
+        let program_id = self.token_program(source).await?;
+        let approve = spl_token_2022::instruction::approve(
+            &program_id,
             &source,
             &target,
             &signer_pubkey,
@@ -436,7 +497,8 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
         Ok(vec![])
     }
 
-    fn revoke(&mut self, context: &crate::evm::Context, account: Pubkey) -> Result<Vec<u8>> {
+    #[maybe_async]
+    async fn revoke(&mut self, context: &crate::evm::Context, account: Pubkey) -> Result<Vec<u8>> {
         let signer = context.caller;
         let (signer_pubkey, bump_seed) = self.backend.contract_pubkey(signer);
 
@@ -446,13 +508,16 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             vec![bump_seed],
         ];
 
-        let revoke = spl_token::instruction::revoke(&spl_token::ID, &account, &signer_pubkey, &[])?;
+        let program_id = self.token_program(account).await?;
+        let revoke =
+            spl_token_2022::instruction::revoke(&program_id, &account, &signer_pubkey, &[])?;
         self.queue_external_instruction(revoke, seeds, 0);
 
         Ok(vec![])
     }
 
-    fn transfer(
+    #[maybe_async]
+    async fn transfer(
         &mut self,
         context: &crate::evm::Context,
         source: Pubkey,
@@ -468,20 +533,16 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             vec![bump_seed],
         ];
 
-        let transfer = spl_token::instruction::transfer(
-            &spl_token::ID,
-            &source,
-            &target,
-            &signer_pubkey,
-            &[],
-            amount,
-        )?;
+        let transfer = self
+            .transfer_instruction(source, target, &signer_pubkey, amount)
+            .await?;
         self.queue_external_instruction(transfer, seeds, 0);
 
         Ok(vec![])
     }
 
-    fn transfer_with_seed(
+    #[maybe_async]
+    async fn transfer_with_seed(
         &mut self,
         context: &crate::evm::Context,
         seed: &[u8; 32],
@@ -506,20 +567,16 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             vec![signer_seed],
         ];
 
-        let transfer = spl_token::instruction::transfer(
-            &spl_token::ID,
-            &source,
-            &target,
-            &signer_pubkey,
-            &[],
-            amount,
-        )?;
+        let transfer = self
+            .transfer_instruction(source, target, &signer_pubkey, amount)
+            .await?;
         self.queue_external_instruction(transfer, seeds, 0);
 
         Ok(vec![])
     }
 
-    fn mint_to(
+    #[maybe_async]
+    async fn mint_to(
         &mut self,
         context: &crate::evm::Context,
         mint: Pubkey,
@@ -535,8 +592,9 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             vec![bump_seed],
         ];
 
-        let mint_to = spl_token::instruction::mint_to(
-            &spl_token::ID,
+        let program_id = self.token_program(mint).await?;
+        let mint_to = spl_token_2022::instruction::mint_to(
+            &program_id,
             &mint,
             &target,
             &signer_pubkey,
@@ -548,7 +606,8 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
         Ok(vec![])
     }
 
-    fn burn_spl_token(
+    #[maybe_async]
+    async fn burn_spl_token(
         &mut self,
         context: &crate::evm::Context,
         mint: Pubkey,
@@ -564,8 +623,9 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             vec![bump_seed],
         ];
 
-        let burn = spl_token::instruction::burn(
-            &spl_token::ID,
+        let program_id = self.token_program(mint).await?;
+        let burn = spl_token_2022::instruction::burn(
+            &program_id,
             &source,
             &mint,
             &signer_pubkey,
@@ -577,7 +637,8 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
         Ok(vec![])
     }
 
-    fn freeze(
+    #[maybe_async]
+    async fn freeze(
         &mut self,
         context: &crate::evm::Context,
         mint: Pubkey,
@@ -592,8 +653,9 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             vec![bump_seed],
         ];
 
-        let freeze = spl_token::instruction::freeze_account(
-            &spl_token::ID,
+        let program_id = self.token_program(mint).await?;
+        let freeze = spl_token_2022::instruction::freeze_account(
+            &program_id,
             &target,
             &mint,
             &signer_pubkey,
@@ -604,7 +666,8 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
         Ok(vec![])
     }
 
-    fn thaw(
+    #[maybe_async]
+    async fn thaw(
         &mut self,
         context: &crate::evm::Context,
         mint: Pubkey,
@@ -619,8 +682,9 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
             vec![bump_seed],
         ];
 
-        let thaw = spl_token::instruction::thaw_account(
-            &spl_token::ID,
+        let program_id = self.token_program(mint).await?;
+        let thaw = spl_token_2022::instruction::thaw_account(
+            &program_id,
             &target,
             &mint,
             &signer_pubkey,
@@ -631,19 +695,86 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
         Ok(vec![])
     }
 
-    #[allow(clippy::unnecessary_wraps)]
-    fn find_account(&mut self, context: &crate::evm::Context, seed: &[u8]) -> Result<Vec<u8>> {
-        let signer = context.caller;
+    #[maybe_async]
+    async fn find_associated_token_account(
+        &mut self,
+        owner: Pubkey,
+        mint: Pubkey,
+    ) -> Result<Vec<u8>> {
+        let program_id = self.token_program(mint).await?;
+        let account_key = get_associated_token_address_with_program_id(&owner, &mint, &program_id);
 
-        let (account_key, _) = Pubkey::find_program_address(
-            &[
-                &[ACCOUNT_SEED_VERSION],
-                b"ContractData",
-                signer.as_bytes(),
-                seed,
-            ],
-            self.backend.program_id(),
+        Ok(account_key.to_bytes().to_vec())
+    }
+
+    /// Queues idempotent creation of the associated token account, returns its address
+    #[maybe_async]
+    async fn associated_token_account(&mut self, owner: Pubkey, mint: Pubkey) -> Result<Pubkey> {
+        let mint_account = self.external_account(mint).await?;
+        let program_id = token_program_id(&mint_account)?;
+
+        let account_key = get_associated_token_address_with_program_id(&owner, &mint, &program_id);
+        let account = self.external_account(account_key).await?;
+        if account.owner == program_id {
+            // Classic token accounts can change the owner after creation
+            let token = StateWithExtensions::<Account>::unpack(&account.data)?.base;
+            if (token.owner != owner) || (token.mint != mint) {
+                return Err(ProgramError::from(AssociatedTokenAccountError::InvalidOwner).into());
+            }
+
+            return Ok(account_key);
+        }
+
+        let space = token_account_len(&mint_account, &[ExtensionType::ImmutableOwner])?;
+        let rent = Rent::get()?;
+        let fee = rent.minimum_balance(space).saturating_sub(account.lamports);
+
+        let create_associated = create_associated_token_account_idempotent(
+            &self.backend.operator(),
+            &owner,
+            &mint,
+            &program_id,
         );
+        self.queue_external_instruction(create_associated, vec![], fee);
+
+        Ok(account_key)
+    }
+
+    #[maybe_async]
+    async fn create_associated_token_account(
+        &mut self,
+        owner: Pubkey,
+        mint: Pubkey,
+    ) -> Result<Vec<u8>> {
+        let account_key = self.associated_token_account(owner, mint).await?;
+
+        Ok(account_key.to_bytes().to_vec())
+    }
+
+    #[maybe_async]
+    async fn transfer_to_associated_token_account(
+        &mut self,
+        context: &crate::evm::Context,
+        source: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+    ) -> Result<Vec<u8>> {
+        let source_account = self.external_account(source).await?;
+        token_program_id(&source_account)?;
+
+        let mint = StateWithExtensions::<Account>::unpack(&source_account.data)?
+            .base
+            .mint;
+
+        let target = self.associated_token_account(owner, mint).await?;
+        self.transfer(context, source, target, amount).await?;
+
+        Ok(target.to_bytes().to_vec())
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn find_account(&mut self, context: &crate::evm::Context, seed: &[u8]) -> Result<Vec<u8>> {
+        let (account_key, _) = self.contract_data_account(context.caller, seed);
 
         Ok(account_key.to_bytes().to_vec())
     }
@@ -672,10 +803,10 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
         account: Pubkey,
     ) -> Result<Vec<u8>> {
         let account = self.external_account(account).await?;
-        let token = if spl_token::check_id(&account.owner) {
-            spl_token::state::Account::unpack(&account.data)?
+        let token = if is_token_program(&account.owner) {
+            StateWithExtensions::<Account>::unpack(&account.data)?.base
         } else if system_program::check_id(&account.owner) {
-            spl_token::state::Account::default()
+            Account::default()
         } else {
             return Err(ProgramError::IllegalOwner.into());
         };
@@ -707,10 +838,10 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
         account: Pubkey,
     ) -> Result<Vec<u8>> {
         let account = self.external_account(account).await?;
-        let mint = if spl_token::check_id(&account.owner) {
-            spl_token::state::Mint::unpack(&account.data)?
+        let mint = if is_token_program(&account.owner) {
+            StateWithExtensions::<Mint>::unpack(&account.data)?.base
         } else if system_program::check_id(&account.owner) {
-            spl_token::state::Mint::default()
+            Mint::default()
         } else {
             return Err(ProgramError::IllegalOwner.into());
         };
@@ -735,4 +866,519 @@ impl<B: AccountStorage> ExecutorState<'_, B> {
 
         Ok(result.to_vec())
     }
+
+    #[maybe_async]
+    async fn get_mint_extensions(
+        &mut self,
+        _context: &crate::evm::Context,
+        mint: Pubkey,
+    ) -> Result<Vec<u8>> {
+        let mut result = [0_u8; 9 * 32];
+
+        let account = self.external_account(mint).await?;
+        if system_program::check_id(&account.owner) {
+            return Ok(result.to_vec());
+        }
+
+        let program_id = token_program_id(&account)?;
+        let mint = StateWithExtensions::<Mint>::unpack(&account.data)?;
+
+        let (
+            program,
+            basis_points,
+            maximum_fee,
+            withheld_amount,
+            interest_rate,
+            metadata_address,
+            permanent_delegate,
+            transfer_hook_program,
+            non_transferable,
+        ) = arrayref::mut_array_refs![&mut result, 32, 32, 32, 32, 32, 32, 32, 32, 32];
+
+        *program = program_id.to_bytes();
+
+        if let Ok(config) = mint.get_extension::<TransferFeeConfig>() {
+            let fee = config.get_epoch_fee(Clock::get()?.epoch);
+            *basis_points = U256::from(u16::from(fee.transfer_fee_basis_points)).to_be_bytes();
+            *maximum_fee = U256::from(u64::from(fee.maximum_fee)).to_be_bytes();
+            *withheld_amount = U256::from(u64::from(config.withheld_amount)).to_be_bytes();
+        }
+        if let Ok(config) = mint.get_extension::<InterestBearingConfig>() {
+            *interest_rate = I256::from(i16::from(config.current_rate)).to_be_bytes();
+        }
+        if let Ok(pointer) = mint.get_extension::<MetadataPointer>() {
+            *metadata_address = key_bytes(pointer.metadata_address);
+        }
+        if let Ok(delegate) = mint.get_extension::<PermanentDelegate>() {
+            *permanent_delegate = key_bytes(delegate.delegate);
+        }
+        if let Ok(hook) = mint.get_extension::<TransferHook>() {
+            *transfer_hook_program = key_bytes(hook.program_id);
+        }
+        non_transferable[31] = u8::from(mint.get_extension::<NonTransferable>().is_ok());
+
+        Ok(result.to_vec())
+    }
+
+    #[maybe_async]
+    async fn get_account_extensions(
+        &mut self,
+        _context: &crate::evm::Context,
+        account: Pubkey,
+    ) -> Result<Vec<u8>> {
+        let mut result = [0_u8; 5 * 32];
+
+        let account = self.external_account(account).await?;
+        if system_program::check_id(&account.owner) {
+            return Ok(result.to_vec());
+        }
+
+        let program_id = token_program_id(&account)?;
+        let token = StateWithExtensions::<Account>::unpack(&account.data)?;
+
+        let (program, withheld_amount, require_memo, cpi_guard, immutable_owner) =
+            arrayref::mut_array_refs![&mut result, 32, 32, 32, 32, 32];
+
+        *program = program_id.to_bytes();
+
+        if let Ok(fee) = token.get_extension::<TransferFeeAmount>() {
+            *withheld_amount = U256::from(u64::from(fee.withheld_amount)).to_be_bytes();
+        }
+        if let Ok(memo) = token.get_extension::<MemoTransfer>() {
+            require_memo[31] = u8::from(bool::from(memo.require_incoming_transfer_memos));
+        }
+        if let Ok(guard) = token.get_extension::<CpiGuard>() {
+            cpi_guard[31] = u8::from(bool::from(guard.lock_cpi));
+        }
+        immutable_owner[31] = u8::from(token.get_extension::<ImmutableOwner>().is_ok());
+
+        Ok(result.to_vec())
+    }
+}
+
+fn is_token_program(program_id: &Pubkey) -> bool {
+    spl_token::check_id(program_id) || spl_token_2022::check_id(program_id)
+}
+
+fn token_program_id(account: &OwnedAccountInfo) -> Result<Pubkey> {
+    if is_token_program(&account.owner) {
+        Ok(account.owner)
+    } else {
+        Err(Error::AccountInvalidOwner(account.key, spl_token::ID))
+    }
+}
+
+/// Size of the token account for the mint.
+/// Token-2022 accounts reserve space for the extensions required by the mint.
+fn token_account_len(mint: &OwnedAccountInfo, extensions: &[ExtensionType]) -> Result<usize> {
+    if spl_token::check_id(&mint.owner) {
+        return Ok(Account::LEN);
+    }
+
+    let mint_state = StateWithExtensions::<Mint>::unpack(&mint.data)?;
+    let mint_extensions = mint_state.get_extension_types()?;
+
+    let mut account_extensions =
+        ExtensionType::get_required_init_account_extensions(&mint_extensions);
+    account_extensions.extend_from_slice(extensions);
+
+    let len = ExtensionType::try_calculate_account_len::<Account>(&account_extensions)?;
+    Ok(len)
+}
+
+fn key_bytes(key: impl Into<Option<Pubkey>>) -> [u8; 32] {
+    key.into().map(Pubkey::to_bytes).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::program_option::COption;
+    use spl_token_2022::extension::StateWithExtensionsMut;
+
+    use super::*;
+    use crate::account_storage::test_storage::{install_syscall_stubs, TestAccountStorage};
+    use crate::executor::precompile_extension::{
+        abi_call, abi_word, test_context, SYSTEM_ACCOUNT_SPL_TOKEN, TEST_CALLER,
+    };
+
+    const DECIMALS: u8 = 6;
+    const FEE_BASIS_POINTS: u16 = 100;
+    const MAXIMUM_FEE: u64 = 50;
+
+    fn token_2022_account(key: Pubkey, data: Vec<u8>) -> OwnedAccountInfo {
+        OwnedAccountInfo {
+            key,
+            is_signer: false,
+            is_writable: false,
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: spl_token_2022::ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn token_account(key: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) -> OwnedAccountInfo {
+        let mut data = vec![0_u8; Account::LEN];
+        Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token_2022::state::AccountState::Initialized,
+            ..Account::default()
+        }
+        .pack_into_slice(&mut data);
+
+        token_2022_account(key, data)
+    }
+
+    fn mint_2022(
+        key: Pubkey,
+        authority: Pubkey,
+        extensions: &[ExtensionType],
+        init: impl FnOnce(&mut StateWithExtensionsMut<Mint>),
+    ) -> OwnedAccountInfo {
+        let len = ExtensionType::try_calculate_account_len::<Mint>(extensions).unwrap();
+        let mut data = vec![0_u8; len];
+
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        init(&mut state);
+        state.base = Mint {
+            mint_authority: COption::Some(authority),
+            supply: 0,
+            decimals: DECIMALS,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+
+        token_2022_account(key, data)
+    }
+
+    fn transfer_fee_mint(key: Pubkey, authority: Pubkey) -> OwnedAccountInfo {
+        mint_2022(
+            key,
+            authority,
+            &[ExtensionType::TransferFeeConfig],
+            |state| {
+                let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+                for fee in [
+                    &mut config.older_transfer_fee,
+                    &mut config.newer_transfer_fee,
+                ] {
+                    fee.maximum_fee = MAXIMUM_FEE.into();
+                    fee.transfer_fee_basis_points = FEE_BASIS_POINTS.into();
+                }
+            },
+        )
+    }
+
+    fn setup() -> (TestAccountStorage, Pubkey) {
+        install_syscall_stubs();
+
+        let mut storage = TestAccountStorage {
+            operator: Pubkey::new_unique(),
+            ..TestAccountStorage::default()
+        };
+        storage.add_wallet(storage.operator, 1_000_000_000_000);
+
+        let (authority, _) = storage.contract_pubkey(TEST_CALLER);
+        (storage, authority)
+    }
+
+    async fn call(
+        state: &mut ExecutorState<'_, TestAccountStorage>,
+        signature: &str,
+        arguments: &[[u8; 32]],
+    ) -> Result<Vec<u8>> {
+        let context = test_context(SYSTEM_ACCOUNT_SPL_TOKEN);
+        let input = abi_call(signature, arguments);
+
+        state
+            .spl_token(&SYSTEM_ACCOUNT_SPL_TOKEN, &input, &context, false)
+            .await
+    }
+
+    async fn emulated_account(
+        state: &ExecutorState<'_, TestAccountStorage>,
+        key: &[u8],
+    ) -> OwnedAccountInfo {
+        let key = Pubkey::try_from(key).unwrap();
+        state.external_account(key).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn transfer_with_transfer_fee() {
+        let (mut storage, authority) = setup();
+        let mint = Pubkey::new_unique();
+        storage.add_solana_account(transfer_fee_mint(mint, authority));
+
+        let mut state = ExecutorState::new(&storage);
+
+        let initialize_account = "initializeAccount(bytes32,bytes32)";
+        let source = call(&mut state, initialize_account, &[[1; 32], mint.to_bytes()])
+            .await
+            .unwrap();
+        let target = call(&mut state, initialize_account, &[[2; 32], mint.to_bytes()])
+            .await
+            .unwrap();
+
+        // Token accounts reserve space for the withheld fee
+        let source_account = emulated_account(&state, &source).await;
+        assert_eq!(source_account.owner, spl_token_2022::ID);
+        assert_eq!(
+            source_account.data.len(),
+            ExtensionType::try_calculate_account_len::<Account>(&[
+                ExtensionType::TransferFeeAmount
+            ])
+            .unwrap()
+        );
+
+        let mint_to = "mintTo(bytes32,bytes32,uint64)";
+        let source_word: [u8; 32] = source.clone().try_into().unwrap();
+        let target_word: [u8; 32] = target.clone().try_into().unwrap();
+        call(
+            &mut state,
+            mint_to,
+            &[mint.to_bytes(), source_word, abi_word(10_000)],
+        )
+        .await
+        .unwrap();
+
+        let transfer = "transfer(bytes32,bytes32,uint64)";
+        call(
+            &mut state,
+            transfer,
+            &[source_word, target_word, abi_word(1_000)],
+        )
+        .await
+        .unwrap();
+
+        // 1% fee is withheld in the target account
+        let fee = 1_000 * u64::from(FEE_BASIS_POINTS) / 10_000;
+        let target_account = emulated_account(&state, &target).await;
+        let target_state = StateWithExtensions::<Account>::unpack(&target_account.data).unwrap();
+        assert_eq!(target_state.base.amount, 1_000 - fee);
+
+        let extensions = call(&mut state, "getAccountExtensions(bytes32)", &[target_word])
+            .await
+            .unwrap();
+        assert_eq!(extensions[..32], spl_token_2022::ID.to_bytes());
+        assert_eq!(extensions[32..64], abi_word(fee));
+
+        let account = call(&mut state, "getAccount(bytes32)", &[source_word])
+            .await
+            .unwrap();
+        assert_eq!(account[64..96], abi_word(10_000 - 1_000));
+
+        // Token-2022 transfers go through `transfer_checked`
+        let instruction = state
+            .into_actions()
+            .into_iter()
+            .rev()
+            .find_map(|action| match action {
+                crate::executor::Action::ExternalInstruction {
+                    program_id, data, ..
+                } => Some((program_id, data)),
+                _ => None,
+            });
+        let (program_id, data) = instruction.unwrap();
+        assert_eq!(program_id, spl_token_2022::ID);
+        assert!(matches!(
+            spl_token_2022::instruction::TokenInstruction::unpack(&data).unwrap(),
+            spl_token_2022::instruction::TokenInstruction::TransferChecked {
+                amount: 1_000,
+                decimals: DECIMALS
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn mint_extensions_encoding() {
+        let (mut storage, authority) = setup();
+        let mint = Pubkey::new_unique();
+        storage.add_solana_account(transfer_fee_mint(mint, authority));
+
+        let mut state = ExecutorState::new(&storage);
+        let extensions = call(&mut state, "getMintExtensions(bytes32)", &[mint.to_bytes()])
+            .await
+            .unwrap();
+
+        assert_eq!(extensions.len(), 9 * 32);
+        assert_eq!(extensions[..32], spl_token_2022::ID.to_bytes());
+        assert_eq!(extensions[32..64], abi_word(FEE_BASIS_POINTS.into()));
+        assert_eq!(extensions[64..96], abi_word(MAXIMUM_FEE));
+        assert!(extensions[96..].iter().all(|b| *b == 0));
+
+        // System accounts have no extensions
+        let extensions = call(
+            &mut state,
+            "getMintExtensions(bytes32)",
+            &[Pubkey::new_unique().to_bytes()],
+        )
+        .await
+        .unwrap();
+        assert!(extensions.iter().all(|b| *b == 0));
+    }
+
+    #[tokio::test]
+    async fn transfer_hook_is_rejected() {
+        let (mut storage, authority) = setup();
+
+        let hook_program = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        storage.add_solana_account(mint_2022(
+            mint,
+            authority,
+            &[ExtensionType::TransferHook],
+            |state| {
+                let hook = state.init_extension::<TransferHook>(true).unwrap();
+                hook.program_id = Some(hook_program).try_into().unwrap();
+            },
+        ));
+
+        let source = Pubkey::new_unique();
+        storage.add_solana_account(token_account(source, mint, authority, 100));
+
+        let mut state = ExecutorState::new(&storage);
+        let result = call(
+            &mut state,
+            "transfer(bytes32,bytes32,uint64)",
+            &[
+                source.to_bytes(),
+                Pubkey::new_unique().to_bytes(),
+                abi_word(10),
+            ],
+        )
+        .await;
+        assert!(matches!(result, Err(Error::Custom(_))));
+
+        let extensions = call(&mut state, "getMintExtensions(bytes32)", &[mint.to_bytes()])
+            .await
+            .unwrap();
+        assert_eq!(extensions[7 * 32..8 * 32], hook_program.to_bytes());
+    }
+
+    #[tokio::test]
+    async fn create_associated_token_account_twice() {
+        let (mut storage, authority) = setup();
+        let mint = Pubkey::new_unique();
+        storage.add_solana_account(transfer_fee_mint(mint, authority));
+
+        let wallet = Pubkey::new_unique();
+        let expected =
+            get_associated_token_address_with_program_id(&wallet, &mint, &spl_token_2022::ID);
+
+        let mut state = ExecutorState::new(&storage);
+        let create = "createAssociatedTokenAccount(bytes32,bytes32)";
+        for _ in 0..2 {
+            let key = call(&mut state, create, &[wallet.to_bytes(), mint.to_bytes()])
+                .await
+                .unwrap();
+            assert_eq!(key, expected.to_bytes());
+        }
+
+        let key = call(
+            &mut state,
+            "findAssociatedTokenAccount(bytes32,bytes32)",
+            &[wallet.to_bytes(), mint.to_bytes()],
+        )
+        .await
+        .unwrap();
+        assert_eq!(key, expected.to_bytes());
+
+        // Associated token accounts of Token-2022 mints have immutable owner
+        let account = emulated_account(&state, &key).await;
+        assert_eq!(account.owner, spl_token_2022::ID);
+        assert_eq!(
+            account.data.len(),
+            ExtensionType::try_calculate_account_len::<Account>(&[
+                ExtensionType::TransferFeeAmount,
+                ExtensionType::ImmutableOwner
+            ])
+            .unwrap()
+        );
+
+        let token = StateWithExtensions::<Account>::unpack(&account.data).unwrap();
+        assert_eq!(token.base.owner, wallet);
+        assert_eq!(token.base.mint, mint);
+        assert!(token
+            .get_extension_types()
+            .unwrap()
+            .contains(&ExtensionType::ImmutableOwner));
+
+        // The second call doesn't queue another instruction
+        let instructions = state
+            .into_actions()
+            .into_iter()
+            .filter(|action| matches!(action, crate::executor::Action::ExternalInstruction { .. }))
+            .count();
+        assert_eq!(instructions, 1);
+    }
+
+    #[tokio::test]
+    async fn associated_token_account_with_another_owner_is_rejected() {
+        let (mut storage, authority) = setup();
+
+        let mint = Pubkey::new_unique();
+        let mut account = mint_2022(mint, authority, &[], |_| {});
+        account.owner = spl_token::ID;
+        storage.add_solana_account(account);
+
+        // Owner of the classic token account was changed after creation
+        let wallet = Pubkey::new_unique();
+        let key = get_associated_token_address_with_program_id(&wallet, &mint, &spl_token::ID);
+        let mut account = token_account(key, mint, Pubkey::new_unique(), 0);
+        account.owner = spl_token::ID;
+        storage.add_solana_account(account);
+
+        let source = Pubkey::new_unique();
+        let mut account = token_account(source, mint, authority, 100);
+        account.owner = spl_token::ID;
+        storage.add_solana_account(account);
+
+        let mut state = ExecutorState::new(&storage);
+        let result = call(
+            &mut state,
+            "createAssociatedTokenAccount(bytes32,bytes32)",
+            &[wallet.to_bytes(), mint.to_bytes()],
+        )
+        .await;
+        assert!(result.is_err());
+
+        let result = call(
+            &mut state,
+            "transferToAssociatedTokenAccount(bytes32,bytes32,uint64)",
+            &[source.to_bytes(), wallet.to_bytes(), abi_word(10)],
+        )
+        .await;
+        assert!(result.is_err());
+        assert!(state.into_actions().is_empty());
+    }
+
+    #[test]
+    fn token_account_len_includes_mint_extensions() {
+        let authority = Pubkey::new_unique();
+
+        let mut classic = transfer_fee_mint(Pubkey::new_unique(), authority);
+        classic.owner = spl_token::ID;
+        assert_eq!(token_account_len(&classic, &[]).unwrap(), Account::LEN);
+
+        let mint = mint_2022(Pubkey::new_unique(), authority, &[], |_| {});
+        assert_eq!(
+            token_account_len(&mint, &[]).unwrap(),
+            ExtensionType::try_calculate_account_len::<Account>(&[]).unwrap()
+        );
+
+        let mint = transfer_fee_mint(Pubkey::new_unique(), authority);
+        assert_eq!(
+            token_account_len(&mint, &[ExtensionType::ImmutableOwner]).unwrap(),
+            ExtensionType::try_calculate_account_len::<Account>(&[
+                ExtensionType::TransferFeeAmount,
+                ExtensionType::ImmutableOwner
+            ])
+            .unwrap()
+        );
+    }
 }
@@ -0,0 +1,286 @@
+use std::convert::TryInto;
+
+use evm_loader_macro::precompile_abi;
+use maybe_async::maybe_async;
+use solana_program::{
+    entrypoint::MAX_PERMITTED_DATA_INCREASE, pubkey::Pubkey, rent::Rent, system_instruction,
+    system_program, sysvar::Sysvar,
+};
+
+use crate::{
+    account::ACCOUNT_SEED_VERSION,
+    account_storage::AccountStorage,
+    error::{Error, Result},
+    executor::{ExecutorState, OwnedAccountInfo},
+    types::Address,
+};
+
+precompile_abi! {
+    #[interface = "SystemProgram"]
+    pub enum SystemProgramCall {
+        /// Return PDA[ACCOUNT_SEED_VERSION, b"ContractData", msg.sender, salt], the same account as `SPLToken.findAccount`.
+        /// All other methods operate on this account and sign with its seeds.
+        FindAccount = "function findAccount(bytes32 salt) external pure returns(bytes32)",
+        /// Create rent exempt account of `space` bytes owned by `owner`.
+        /// Rent is paid by the operator and included in the transaction fee.
+        CreateAccount = "function createAccount(bytes32 salt, uint64 space, bytes32 owner) external returns(bytes32)",
+        /// Transfer lamports from the account to `target`. The account must be owned by the system program and carry no data.
+        Transfer = "function transfer(bytes32 salt, bytes32 target, uint64 lamports) external",
+        /// Allocate `space` bytes for the account owned by the system program
+        Allocate = "function allocate(bytes32 salt, uint64 space) external",
+        /// Assign the account owned by the system program to `owner`
+        Assign = "function assign(bytes32 salt, bytes32 owner) external",
+    }
+}
+
+impl<B: AccountStorage> ExecutorState<'_, B> {
+    #[maybe_async]
+    pub async fn system_program(
+        &mut self,
+        address: &Address,
+        input: &[u8],
+        context: &crate::evm::Context,
+        is_static: bool,
+    ) -> Result<Vec<u8>> {
+        if context.value != 0 {
+            return Err(Error::Custom("SystemProgram: value != 0".to_string()));
+        }
+
+        if &context.contract != address {
+            return Err(Error::Custom(
+                "SystemProgram: callcode or delegatecall is not allowed".to_string(),
+            ));
+        }
+
+        let call = SystemProgramCall::decode(address, input)?;
+        if is_static && !call.is_view() {
+            return Err(Error::StaticModeViolation(*address));
+        }
+
+        match call {
+            SystemProgramCall::FindAccount { salt } => {
+                let (account_key, _) = self.contract_data_account(context.caller, &salt);
+                Ok(account_key.to_bytes().to_vec())
+            }
+            SystemProgramCall::CreateAccount { salt, space, owner } => {
+                self.create_system_account(context, &salt, space, Pubkey::from(owner))
+                    .await
+            }
+            SystemProgramCall::Transfer {
+                salt,
+                target,
+                lamports,
+            } => {
+                let (account_key, seeds) = self.contract_data_seeds(context.caller, &salt);
+
+                let transfer =
+                    system_instruction::transfer(&account_key, &Pubkey::from(target), lamports);
+                self.queue_external_instruction(transfer, seeds, 0);
+
+                Ok(vec![])
+            }
+            SystemProgramCall::Allocate { salt, space } => {
+                check_space(space)?;
+                let (account_key, seeds) = self.contract_data_seeds(context.caller, &salt);
+
+                let allocate = system_instruction::allocate(&account_key, space);
+                self.queue_external_instruction(allocate, seeds, 0);
+
+                Ok(vec![])
+            }
+            SystemProgramCall::Assign { salt, owner } => {
+                let owner = Pubkey::from(owner);
+                self.check_owner(&owner)?;
+                let (account_key, seeds) = self.contract_data_seeds(context.caller, &salt);
+
+                let assign = system_instruction::assign(&account_key, &owner);
+                self.queue_external_instruction(assign, seeds, 0);
+
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// `PDA[ACCOUNT_SEED_VERSION, b"ContractData", contract, salt]`
+    pub(super) fn contract_data_account(&self, contract: Address, salt: &[u8]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                &[ACCOUNT_SEED_VERSION],
+                b"ContractData",
+                contract.as_bytes(),
+                salt,
+            ],
+            self.backend.program_id(),
+        )
+    }
+
+    /// Contract data account with the seeds to sign for it
+    pub(super) fn contract_data_seeds(
+        &self,
+        contract: Address,
+        salt: &[u8],
+    ) -> (Pubkey, Vec<Vec<u8>>) {
+        let (account_key, bump_seed) = self.contract_data_account(contract, salt);
+        let seeds: Vec<Vec<u8>> = vec![
+            vec![ACCOUNT_SEED_VERSION],
+            b"ContractData".to_vec(),
+            contract.as_bytes().to_vec(),
+            salt.to_vec(),
+            vec![bump_seed],
+        ];
+
+        (account_key, seeds)
+    }
+
+    /// Funds the account from the operator up to the rent exempt balance, allocates and assigns it.
+    /// Works for accounts which already received lamports, unlike `system_instruction::create_account`.
+    pub(super) fn create_account(
+        &mut self,
+        account: &OwnedAccountInfo,
+        program_id: &Pubkey,
+        space: usize,
+        seeds: Vec<Vec<u8>>,
+    ) -> Result<()> {
+        let rent = Rent::get()?;
+        let minimum_balance = rent.minimum_balance(space);
+
+        let required_lamports = minimum_balance.saturating_sub(account.lamports);
+
+        if required_lamports > 0 {
+            let transfer = system_instruction::transfer(
+                &self.backend.operator(),
+                &account.key,
+                required_lamports,
+            );
+            self.queue_external_instruction(transfer, vec![], required_lamports);
+        }
+
+        let allocate = system_instruction::allocate(&account.key, space.try_into().unwrap());
+        self.queue_external_instruction(allocate, seeds.clone(), 0);
+
+        let assign = system_instruction::assign(&account.key, program_id);
+        self.queue_external_instruction(assign, seeds, 0);
+
+        Ok(())
+    }
+
+    #[maybe_async]
+    async fn create_system_account(
+        &mut self,
+        context: &crate::evm::Context,
+        salt: &[u8],
+        space: u64,
+        owner: Pubkey,
+    ) -> Result<Vec<u8>> {
+        check_space(space)?;
+        self.check_owner(&owner)?;
+
+        let (account_key, seeds) = self.contract_data_seeds(context.caller, salt);
+
+        let account = self.external_account(account_key).await?;
+        if !system_program::check_id(&account.owner) || !account.data.is_empty() {
+            return Err(Error::AccountInvalidOwner(account_key, system_program::ID));
+        }
+
+        self.create_account(&account, &owner, space.try_into()?, seeds)?;
+
+        Ok(account_key.to_bytes().to_vec())
+    }
+
+    /// Contracts can't create accounts owned by Neon EVM
+    fn check_owner(&self, owner: &Pubkey) -> Result<()> {
+        if owner == self.backend.program_id() {
+            return Err(Error::Custom(
+                "SystemProgram: Neon EVM can't be the account owner".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Account data can't grow more than `MAX_PERMITTED_DATA_INCREASE` in a single instruction
+fn check_space(space: u64) -> Result<()> {
+    if space > MAX_PERMITTED_DATA_INCREASE as u64 {
+        return Err(Error::Custom(format!(
+            "SystemProgram: space exceeds {MAX_PERMITTED_DATA_INCREASE} bytes"
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account_storage::test_storage::{install_syscall_stubs, TestAccountStorage};
+    use crate::executor::precompile_extension::{
+        abi_call, abi_word, test_context, SYSTEM_ACCOUNT_SYSTEM_PROGRAM, TEST_CALLER,
+    };
+
+    #[test]
+    fn space_limit() {
+        assert!(check_space(MAX_PERMITTED_DATA_INCREASE as u64).is_ok());
+        assert!(check_space(MAX_PERMITTED_DATA_INCREASE as u64 + 1).is_err());
+    }
+
+    #[test]
+    fn neon_evm_owner_is_rejected() {
+        let storage = TestAccountStorage::default();
+        let state = ExecutorState::new(&storage);
+
+        assert!(state.check_owner(&crate::ID).is_err());
+        assert!(state.check_owner(&system_program::ID).is_ok());
+    }
+
+    #[tokio::test]
+    async fn static_mode() {
+        install_syscall_stubs();
+
+        let storage = TestAccountStorage {
+            operator: Pubkey::new_unique(),
+            ..TestAccountStorage::default()
+        };
+        let mut state = ExecutorState::new(&storage);
+        let context = test_context(SYSTEM_ACCOUNT_SYSTEM_PROGRAM);
+
+        let salt = [1_u8; 32];
+        let find_account = abi_call("findAccount(bytes32)", &[salt]);
+        let account_key = state
+            .system_program(
+                &SYSTEM_ACCOUNT_SYSTEM_PROGRAM,
+                &find_account,
+                &context,
+                true,
+            )
+            .await
+            .unwrap();
+        let (expected, _) = state.contract_data_account(TEST_CALLER, &salt);
+        assert_eq!(account_key, expected.to_bytes());
+
+        let create_account = abi_call(
+            "createAccount(bytes32,uint64,bytes32)",
+            &[salt, abi_word(100), Pubkey::new_unique().to_bytes()],
+        );
+        let result = state
+            .system_program(
+                &SYSTEM_ACCOUNT_SYSTEM_PROGRAM,
+                &create_account,
+                &context,
+                true,
+            )
+            .await;
+        assert!(matches!(result, Err(Error::StaticModeViolation(_))));
+
+        let account_key = state
+            .system_program(
+                &SYSTEM_ACCOUNT_SYSTEM_PROGRAM,
+                &create_account,
+                &context,
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(account_key, expected.to_bytes());
+    }
+}
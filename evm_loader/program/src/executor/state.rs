@@ -1,28 +1,47 @@
 use std::cell::RefCell;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use ethnum::{AsU256, U256};
 use maybe_async::maybe_async;
+use serde::{Deserialize, Serialize};
 use solana_program::instruction::Instruction;
 use solana_program::pubkey::Pubkey;
 
 use crate::account_storage::AccountStorage;
 use crate::error::{Error, Result};
 use crate::evm::database::Database;
-use crate::evm::{Context, ExitStatus};
+use crate::evm::{Context, ExitStatus, Spec};
 use crate::types::Address;
 
 use super::action::Action;
 use super::cache::{cache_get_or_insert_account, Cache};
 use super::OwnedAccountInfo;
 
+/// EIP-1153 transient storage slot, index is big endian.
+type TransientStorageKey = (Address, [u8; 32]);
+
+/// Value of the transient storage slot before the first write in the current call frame.
+/// Restored when the frame is reverted.
+#[derive(Serialize, Deserialize)]
+struct TransientStorageJournalEntry {
+    key: TransientStorageKey,
+    previous: Option<[u8; 32]>,
+}
+
 /// Represents the state of executor abstracted away from a self.backend.
 /// UPDATE `serialize/deserialize` WHEN THIS STRUCTURE CHANGES
 pub struct ExecutorState<'a, B: AccountStorage> {
     pub backend: &'a B,
     cache: RefCell<Cache>,
     actions: Vec<Action>,
-    stack: Vec<usize>,
+    /// EIP-1153 transient storage.
+    /// Lives only in the executor state and is never applied to accounts.
+    transient_storage: BTreeMap<TransientStorageKey, [u8; 32]>,
+    transient_journal: Vec<TransientStorageJournalEntry>,
+    /// Slots journaled in each call frame, the last set belongs to the current frame.
+    /// Not serialized, rebuilt from the journal.
+    transient_journaled: Vec<BTreeSet<TransientStorageKey>>,
+    stack: Vec<(usize, usize)>,
     exit_status: Option<ExitStatus>,
 }
 
@@ -30,21 +49,51 @@ impl<'a, B: AccountStorage> ExecutorState<'a, B> {
     pub fn serialize_into(&self, buffer: &mut [u8]) -> Result<usize> {
         let mut cursor = std::io::Cursor::new(buffer);
 
-        let value = (&self.cache, &self.actions, &self.stack, &self.exit_status);
+        let value = (
+            &self.cache,
+            &self.actions,
+            &self.transient_storage,
+            &self.transient_journal,
+            &self.stack,
+            &self.exit_status,
+        );
         bincode::serialize_into(&mut cursor, &value)?;
 
         cursor.position().try_into().map_err(Error::from)
     }
 
     pub fn deserialize_from(buffer: &[u8], backend: &'a B) -> Result<Self> {
-        let (cache, actions, stack, exit_status) = bincode::deserialize(buffer)?;
-        Ok(Self {
+        let (cache, actions, transient_storage, transient_journal, stack, exit_status) =
+            bincode::deserialize(buffer)?;
+        let mut state = Self {
             backend,
             cache,
             actions,
+            transient_storage,
+            transient_journal,
+            transient_journaled: Vec::new(),
             stack,
             exit_status,
-        })
+        };
+        state.transient_journaled = Self::journaled_keys(&state.transient_journal, &state.stack);
+
+        Ok(state)
+    }
+
+    fn journaled_keys(
+        journal: &[TransientStorageJournalEntry],
+        stack: &[(usize, usize)],
+    ) -> Vec<BTreeSet<TransientStorageKey>> {
+        let frame_starts = std::iter::once(0).chain(stack.iter().map(|&(_, len)| len));
+        let frame_ends = stack
+            .iter()
+            .map(|&(_, len)| len)
+            .chain(std::iter::once(journal.len()));
+
+        frame_starts
+            .zip(frame_ends)
+            .map(|(start, end)| journal[start..end].iter().map(|e| e.key).collect())
+            .collect()
     }
 
     #[must_use]
@@ -59,11 +108,15 @@ impl<'a, B: AccountStorage> ExecutorState<'a, B> {
             backend,
             cache: RefCell::new(cache),
             actions: Vec::with_capacity(64),
+            transient_storage: BTreeMap::new(),
+            transient_journal: Vec::new(),
+            transient_journaled: vec![BTreeSet::new()],
             stack: Vec::with_capacity(16),
             exit_status: None,
         }
     }
 
+    /// Transient storage is dropped here, at the end of the transaction
     pub fn into_actions(self) -> Vec<Action> {
         assert!(self.stack.is_empty());
 
@@ -150,6 +203,13 @@ impl<'a, B: AccountStorage> ExecutorState<'a, B> {
                     program_id if spl_token::check_id(program_id) => {
                         crate::external_programs::spl_token::emulate(data, meta, &mut accounts)?;
                     }
+                    program_id if spl_token_2022::check_id(program_id) => {
+                        crate::external_programs::spl_token_2022::emulate(
+                            data,
+                            meta,
+                            &mut accounts,
+                        )?;
+                    }
                     program_id if spl_associated_token_account::check_id(program_id) => {
                         crate::external_programs::spl_associated_token::emulate(
                             data,
@@ -160,11 +220,15 @@ impl<'a, B: AccountStorage> ExecutorState<'a, B> {
                     program_id if mpl_token_metadata::check_id(program_id) => {
                         crate::external_programs::metaplex::emulate(data, meta, &mut accounts)?;
                     }
-                    _ => {
+                    // Instructions submitted through the CallSolana precompile can't be emulated.
+                    // They are skipped unless they modify the requested account,
+                    // the emulator reports them as not emulated.
+                    _ if meta.iter().any(|m| (m.pubkey == address) && m.is_writable) => {
                         return Err(Error::Custom(format!(
                             "Unknown external program: {program_id}"
                         )));
                     }
+                    _ => {}
                 }
             }
         }
@@ -297,16 +361,6 @@ impl<'a, B: AccountStorage> Database for ExecutorState<'a, B> {
     }
 
     fn set_code(&mut self, address: Address, chain_id: u64, code: Vec<u8>) -> Result<()> {
-        if code.starts_with(&[0xEF]) {
-            // https://eips.ethereum.org/EIPS/eip-3541
-            return Err(Error::EVMObjectFormatNotSupported(address));
-        }
-
-        if code.len() > 0x6000 {
-            // https://eips.ethereum.org/EIPS/eip-170
-            return Err(Error::ContractCodeSizeLimit(address, code.len()));
-        }
-
         let set_code = Action::EvmSetCode {
             address,
             chain_id,
@@ -324,6 +378,12 @@ impl<'a, B: AccountStorage> Database for ExecutorState<'a, B> {
         Ok(())
     }
 
+    fn is_deployed_in_transaction(&self, address: Address) -> bool {
+        self.actions
+            .iter()
+            .any(|action| matches!(action, Action::EvmSetCode { address: a, .. } if *a == address))
+    }
+
     async fn storage(&self, from_address: Address, from_index: U256) -> Result<[u8; 32]> {
         for action in self.actions.iter().rev() {
             if let Action::EvmSetStorage {
@@ -352,6 +412,43 @@ impl<'a, B: AccountStorage> Database for ExecutorState<'a, B> {
         Ok(())
     }
 
+    fn transient_storage(&self, from_address: Address, from_index: U256) -> Result<[u8; 32]> {
+        let key = (from_address, from_index.to_be_bytes());
+        let value = self.transient_storage.get(&key).copied();
+
+        Ok(value.unwrap_or_default())
+    }
+
+    fn set_transient_storage(
+        &mut self,
+        address: Address,
+        index: U256,
+        value: [u8; 32],
+    ) -> Result<()> {
+        let key = (address, index.to_be_bytes());
+
+        // Only the first write in the call frame is journaled,
+        // so repeated writes to the same slot don't grow the state
+        let journaled = self
+            .transient_journaled
+            .last_mut()
+            .expect("Fatal Error: Inconsistent EVM Call Stack");
+
+        if journaled.insert(key) {
+            let previous = self.transient_storage.get(&key).copied();
+            self.transient_journal
+                .push(TransientStorageJournalEntry { key, previous });
+        }
+
+        if value == [0; 32] {
+            self.transient_storage.remove(&key);
+        } else {
+            self.transient_storage.insert(key, value);
+        }
+
+        Ok(())
+    }
+
     async fn block_hash(&self, number: U256) -> Result<[u8; 32]> {
         // geth:
         //  - checks the overflow
@@ -395,16 +492,26 @@ impl<'a, B: AccountStorage> Database for ExecutorState<'a, B> {
     }
 
     fn snapshot(&mut self) {
-        self.stack.push(self.actions.len());
+        self.stack
+            .push((self.actions.len(), self.transient_journal.len()));
+        self.transient_journaled.push(BTreeSet::new());
     }
 
     fn revert_snapshot(&mut self) {
-        let actions_len = self
+        let (actions_len, journal_len) = self
             .stack
             .pop()
             .expect("Fatal Error: Inconsistent EVM Call Stack");
 
         self.actions.truncate(actions_len);
+        self.transient_journaled.pop();
+
+        for entry in self.transient_journal.drain(journal_len..).rev() {
+            match entry.previous {
+                Some(value) => self.transient_storage.insert(entry.key, value),
+                None => self.transient_storage.remove(&entry.key),
+            };
+        }
 
         if self.stack.is_empty() {
             // sanity check
@@ -414,9 +521,25 @@ impl<'a, B: AccountStorage> Database for ExecutorState<'a, B> {
     }
 
     fn commit_snapshot(&mut self) {
-        self.stack
+        let (_, journal_len) = self
+            .stack
             .pop()
             .expect("Fatal Error: Inconsistent EVM Call Stack");
+
+        // Merge the committed frame journal into the parent frame.
+        // The parent keeps only the oldest value of each slot.
+        self.transient_journaled.pop();
+        let parent_journaled = self
+            .transient_journaled
+            .last_mut()
+            .expect("Fatal Error: Inconsistent EVM Call Stack");
+
+        let committed = self.transient_journal.split_off(journal_len);
+        for entry in committed {
+            if parent_journaled.insert(entry.key) {
+                self.transient_journal.push(entry);
+            }
+        }
     }
 
     async fn precompile_extension(
@@ -438,6 +561,10 @@ impl<'a, B: AccountStorage> Database for ExecutorState<'a, B> {
         self.backend.is_valid_chain_id(chain_id)
     }
 
+    fn chain_spec(&self, chain_id: u64) -> Result<Spec> {
+        self.backend.chain_spec(chain_id)
+    }
+
     async fn contract_chain_id(&self, contract: Address) -> Result<u64> {
         for action in self.actions.iter().rev() {
             if let Action::EvmSetCode {
@@ -453,3 +580,106 @@ impl<'a, B: AccountStorage> Database for ExecutorState<'a, B> {
         self.backend.contract_chain_id(contract).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account_storage::test_storage::TestAccountStorage;
+
+    const ADDRESS: Address = Address([0x22; 20]);
+
+    fn word(value: u8) -> [u8; 32] {
+        let mut word = [0; 32];
+        word[31] = value;
+        word
+    }
+
+    fn tload(state: &ExecutorState<TestAccountStorage>, index: u64) -> [u8; 32] {
+        state.transient_storage(ADDRESS, U256::from(index)).unwrap()
+    }
+
+    fn tstore(state: &mut ExecutorState<TestAccountStorage>, index: u64, value: u8) {
+        state
+            .set_transient_storage(ADDRESS, U256::from(index), word(value))
+            .unwrap();
+    }
+
+    #[test]
+    fn transient_storage_is_reverted_with_snapshot() {
+        let storage = TestAccountStorage::default();
+        let mut state = ExecutorState::new(&storage);
+        state.increment_nonce(ADDRESS, 1).unwrap();
+
+        state.snapshot();
+        tstore(&mut state, 1, 10);
+
+        state.snapshot();
+        tstore(&mut state, 1, 20);
+        tstore(&mut state, 2, 30);
+        state.snapshot();
+        tstore(&mut state, 1, 40);
+        state.commit_snapshot();
+        assert_eq!(tload(&state, 1), word(40));
+        state.revert_snapshot();
+
+        assert_eq!(tload(&state, 1), word(10));
+        assert_eq!(tload(&state, 2), word(0));
+
+        state.revert_snapshot();
+        assert_eq!(tload(&state, 1), word(0));
+        assert!(state.transient_storage.is_empty());
+    }
+
+    #[test]
+    fn transient_storage_writes_to_the_same_slot_are_not_accumulated() {
+        let storage = TestAccountStorage::default();
+        let mut state = ExecutorState::new(&storage);
+
+        state.snapshot();
+        for value in 1..=100 {
+            tstore(&mut state, 1, value);
+        }
+        assert_eq!(tload(&state, 1), word(100));
+        assert_eq!(state.transient_journal.len(), 1);
+
+        state.snapshot();
+        for value in 1..=100 {
+            tstore(&mut state, 1, value);
+        }
+        state.commit_snapshot();
+        assert_eq!(state.transient_journal.len(), 1);
+
+        tstore(&mut state, 1, 0);
+        tstore(&mut state, 2, 0);
+        assert!(state.transient_storage.is_empty());
+        assert_eq!(state.transient_journal.len(), 2);
+    }
+
+    #[test]
+    fn transient_storage_is_serialized() {
+        let storage = TestAccountStorage::default();
+        let mut state = ExecutorState::new(&storage);
+        state.increment_nonce(ADDRESS, 1).unwrap();
+
+        state.snapshot();
+        tstore(&mut state, 5, 50);
+
+        let mut buffer = vec![0; 1024];
+        state.serialize_into(&mut buffer).unwrap();
+
+        let mut state = ExecutorState::deserialize_from(&buffer, &storage).unwrap();
+        assert_eq!(tload(&state, 5), word(50));
+
+        // Journaled slots are restored with the frames
+        tstore(&mut state, 5, 55);
+        assert_eq!(state.transient_journal.len(), 1);
+
+        state.snapshot();
+        tstore(&mut state, 5, 60);
+        state.revert_snapshot();
+        assert_eq!(tload(&state, 5), word(55));
+
+        state.revert_snapshot();
+        assert_eq!(tload(&state, 5), word(0));
+    }
+}
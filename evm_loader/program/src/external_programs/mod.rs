@@ -1,4 +1,17 @@
 pub mod metaplex;
 pub mod spl_associated_token;
 pub mod spl_token;
+pub mod spl_token_2022;
 pub mod system;
+
+use solana_program::pubkey::Pubkey;
+
+/// Instructions of other programs can't be replayed by the emulator
+#[must_use]
+pub fn is_emulated(program_id: &Pubkey) -> bool {
+    solana_program::system_program::check_id(program_id)
+        || ::spl_token::check_id(program_id)
+        || ::spl_token_2022::check_id(program_id)
+        || ::spl_associated_token_account::check_id(program_id)
+        || ::mpl_token_metadata::check_id(program_id)
+}
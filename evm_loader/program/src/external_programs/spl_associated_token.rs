@@ -6,7 +6,13 @@ use solana_program::{
     entrypoint::ProgramResult, instruction::AccountMeta, program_error::ProgramError,
     program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar::Sysvar,
 };
-use spl_associated_token_account::instruction::AssociatedTokenAccountInstruction;
+use spl_associated_token_account::{
+    error::AssociatedTokenAccountError, instruction::AssociatedTokenAccountInstruction,
+};
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    state::{Account, Mint},
+};
 
 pub fn emulate(
     instruction: &[u8],
@@ -20,9 +26,13 @@ pub fn emulate(
             .map_err(|_| ProgramError::InvalidInstructionData)?
     };
 
-    if instruction != AssociatedTokenAccountInstruction::Create {
-        return Err!(ProgramError::InvalidInstructionData; "Unknown spl_associated_token instruction");
-    }
+    let idempotent = match instruction {
+        AssociatedTokenAccountInstruction::Create => false,
+        AssociatedTokenAccountInstruction::CreateIdempotent => true,
+        AssociatedTokenAccountInstruction::RecoverNested => {
+            return Err!(ProgramError::InvalidInstructionData; "Unknown spl_associated_token instruction");
+        }
+    };
 
     let funder_key = &meta[0].pubkey;
     let associated_token_account_key = &meta[1].pubkey;
@@ -31,11 +41,37 @@ pub fn emulate(
     // let system_program_key = &meta[4].pubkey;
     let spl_token_program_key = &meta[5].pubkey;
 
+    if idempotent && accounts[associated_token_account_key].owner == *spl_token_program_key {
+        let associated_token_account = &accounts[associated_token_account_key];
+        if let Ok(token) = StateWithExtensions::<Account>::unpack(&associated_token_account.data) {
+            if token.base.owner != *wallet_account_key {
+                return Err(AssociatedTokenAccountError::InvalidOwner.into());
+            }
+            if token.base.mint != *spl_token_mint_key {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            return Ok(());
+        }
+    }
+
+    let account_len = if spl_token_2022::check_id(spl_token_program_key) {
+        let mint_data = &accounts[spl_token_mint_key].data;
+        let mint = StateWithExtensions::<Mint>::unpack(mint_data)?;
+
+        let mut extensions =
+            ExtensionType::get_required_init_account_extensions(&mint.get_extension_types()?);
+        extensions.push(ExtensionType::ImmutableOwner);
+        ExtensionType::try_calculate_account_len::<Account>(&extensions)?
+    } else {
+        spl_token::state::Account::LEN
+    };
+
     let required_lamports = {
         let associated_token_account = &accounts[associated_token_account_key];
 
         let rent = Rent::get()?;
-        rent.minimum_balance(spl_token::state::Account::LEN)
+        rent.minimum_balance(account_len)
             .max(1)
             .saturating_sub(associated_token_account.lamports)
     };
@@ -56,10 +92,32 @@ pub fn emulate(
         }
 
         associated_token_account.lamports += required_lamports;
-        associated_token_account.owner = spl_token::ID;
-        associated_token_account
-            .data
-            .resize(spl_token::state::Account::LEN, 0);
+        associated_token_account.owner = *spl_token_program_key;
+        associated_token_account.data.resize(account_len, 0);
+    }
+
+    if spl_token_2022::check_id(spl_token_program_key) {
+        let initialize_immutable_owner = spl_token_2022::instruction::initialize_immutable_owner(
+            spl_token_program_key,
+            associated_token_account_key,
+        )?;
+        super::spl_token_2022::emulate(
+            &initialize_immutable_owner.data,
+            &initialize_immutable_owner.accounts,
+            accounts,
+        )?;
+
+        let initialize_account = spl_token_2022::instruction::initialize_account3(
+            spl_token_program_key,
+            associated_token_account_key,
+            spl_token_mint_key,
+            wallet_account_key,
+        )?;
+        return super::spl_token_2022::emulate(
+            &initialize_account.data,
+            &initialize_account.accounts,
+            accounts,
+        );
     }
 
     let initialize_account = spl_token::instruction::initialize_account3(
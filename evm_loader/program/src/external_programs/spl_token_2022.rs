@@ -0,0 +1,36 @@
+use std::collections::BTreeMap;
+
+use crate::executor::OwnedAccountInfo;
+use solana_program::{
+    account_info::{AccountInfo, IntoAccountInfo},
+    entrypoint::ProgramResult,
+    instruction::AccountMeta,
+    pubkey::Pubkey,
+};
+
+pub fn emulate(
+    instruction: &[u8],
+    meta: &[AccountMeta],
+    accounts: &mut BTreeMap<Pubkey, OwnedAccountInfo>,
+) -> ProgramResult {
+    let accounts_info = accounts
+        .iter_mut()
+        .map(|(key, a)| (*key, a.into_account_info()))
+        .collect::<BTreeMap<Pubkey, AccountInfo>>();
+
+    let instruction_accounts: Vec<AccountInfo> = meta
+        .iter()
+        .map(|a| {
+            let mut info = accounts_info[&a.pubkey].clone();
+            info.is_writable = a.is_writable;
+            info.is_signer = a.is_signer;
+            info
+        })
+        .collect();
+
+    spl_token_2022::processor::Processor::process(
+        &spl_token_2022::ID,
+        &instruction_accounts,
+        instruction,
+    )
+}
@@ -1,13 +1,13 @@
 use arrayref::array_ref;
 use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
 
-use crate::account::{program, AccountsDB, BalanceAccount, Operator};
-use crate::config::{CHAIN_ID_LIST, DEFAULT_CHAIN_ID};
+use crate::account::{find_chain, program, AccountsDB, BalanceAccount, ChainRegistry, Operator};
+use crate::config::DEFAULT_CHAIN_ID;
 use crate::error::{Error, Result};
 use crate::types::Address;
 
 pub fn process<'a>(
-    _program_id: &'a Pubkey,
+    program_id: &'a Pubkey,
     accounts: &'a [AccountInfo<'a>],
     instruction: &[u8],
 ) -> Result<()> {
@@ -24,9 +24,8 @@ pub fn process<'a>(
     let chain_id = array_ref![instruction, 20, 8];
     let chain_id = u64::from_le_bytes(*chain_id);
 
-    CHAIN_ID_LIST
-        .binary_search_by_key(&chain_id, |c| c.0)
-        .map_err(|_| Error::InvalidChainId(chain_id))?;
+    let chain_registry = ChainRegistry::from_accounts_db(program_id, &accounts_db)?;
+    find_chain(chain_registry.as_ref(), chain_id)?.ok_or(Error::InvalidChainId(chain_id))?;
 
     solana_program::msg!("Address: {}, ChainID: {}", address, chain_id);
 
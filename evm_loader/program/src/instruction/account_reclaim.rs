@@ -0,0 +1,64 @@
+use crate::account::{
+    BalanceAccount, ContractAccount, MainTreasury, Operator, OperatorRegistry, StorageCell,
+    TAG_ACCOUNT_CONTRACT, TAG_STORAGE_CELL,
+};
+use crate::error::{Error, Result};
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+
+pub fn process<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    _instruction: &[u8],
+) -> Result<()> {
+    solana_program::msg!("Instruction: Reclaim Accounts");
+
+    let _operator = Operator::from_account(program_id, &accounts[0], accounts)?;
+    let recipient = &accounts[1];
+    validate_recipient(program_id, recipient)?;
+
+    let (operator_registry, _) = OperatorRegistry::address(program_id);
+
+    for info in &accounts[2..] {
+        if *info.key == operator_registry {
+            continue;
+        }
+
+        if crate::account::is_blocked(program_id, info)? {
+            return Err(Error::AccountBlocked(*info.key));
+        }
+
+        let is_empty = match crate::account::tag(program_id, info)? {
+            TAG_STORAGE_CELL => StorageCell::from_account(program_id, info.clone())?.is_empty(),
+            TAG_ACCOUNT_CONTRACT => {
+                ContractAccount::from_account(program_id, info.clone())?.is_empty()
+            }
+            _ => {
+                return Err(Error::AccountInvalidTags(
+                    *info.key,
+                    &[TAG_STORAGE_CELL, TAG_ACCOUNT_CONTRACT],
+                ))
+            }
+        };
+
+        if !is_empty {
+            return Err(Error::AccountNotEmpty(*info.key));
+        }
+
+        solana_program::msg!("Reclaim {} lamports from {}", info.lamports(), info.key);
+        crate::account::close(info, recipient)?;
+    }
+
+    Ok(())
+}
+
+/// Rent goes either to the Main Treasury or to the Operator Balance account
+fn validate_recipient<'a>(program_id: &Pubkey, recipient: &'a AccountInfo<'a>) -> Result<()> {
+    let (main_treasury, _) = MainTreasury::address(program_id);
+    if *recipient.key == main_treasury {
+        MainTreasury::from_account(program_id, recipient)?;
+    } else {
+        BalanceAccount::from_account(program_id, recipient.clone())?;
+    }
+
+    Ok(())
+}
@@ -1,15 +1,19 @@
 use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
 
+use crate::account::ChainRegistry;
 use crate::error::Result;
 
 pub fn process<'a>(
-    _program_id: &'a Pubkey,
-    _accounts: &'a [AccountInfo<'a>],
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
     _instruction: &[u8],
 ) -> Result<()> {
     solana_program::msg!("Instruction: Config Get Chain Count");
 
-    let count = crate::config::CHAIN_ID_LIST.len();
+    let count = match ChainRegistry::from_optional_account(program_id, accounts.first())? {
+        Some(registry) => registry.chains().iter().filter(|c| c.enabled).count(),
+        None => crate::config::CHAIN_ID_LIST.len(),
+    };
 
     let return_data = count.to_le_bytes();
     solana_program::program::set_return_data(&return_data);
@@ -1,19 +1,36 @@
 use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
 
-use crate::error::Result;
+use crate::account::ChainRegistry;
+use crate::error::{Error, Result};
 
 pub fn process<'a>(
-    _program_id: &'a Pubkey,
-    _accounts: &'a [AccountInfo<'a>],
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
     instruction: &[u8],
 ) -> Result<()> {
     solana_program::msg!("Instruction: Config Get Chain Info");
 
     let bytes = instruction.try_into()?;
     let index = usize::from_le_bytes(bytes);
-    let info = &crate::config::CHAIN_ID_LIST[index];
 
-    let return_data = bincode::serialize(info)?;
+    let return_data = match ChainRegistry::from_optional_account(program_id, accounts.first())? {
+        Some(registry) => {
+            let chains = registry.chains();
+            let chain = chains
+                .iter()
+                .filter(|c| c.enabled)
+                .nth(index)
+                .ok_or_else(|| Error::Custom(format!("Chain index {index} out of range")))?;
+
+            let info = (chain.id, chain.name(), chain.token, chain.spec()?);
+            bincode::serialize(&info)?
+        }
+        None => {
+            let info = &crate::config::CHAIN_ID_LIST[index];
+            bincode::serialize(info)?
+        }
+    };
+
     solana_program::program::set_return_data(&return_data);
 
     Ok(())
@@ -36,9 +36,29 @@ impl<'a> Accounts<'a> {
     }
 }
 
-fn get_program_upgrade_authority<'a>(
-    program_id: &'a Pubkey,
-    program_data: &'a AccountInfo<'a>,
+/// Checks that `authority` is the program upgrade authority and signed the transaction
+pub fn validate_upgrade_authority(
+    program_id: &Pubkey,
+    program_data: &AccountInfo,
+    authority: &AccountInfo,
+) -> Result<()> {
+    let expected_upgrade_auth_key = get_program_upgrade_authority(program_id, program_data)?;
+    if *authority.key != expected_upgrade_auth_key {
+        return Err(Error::AccountInvalidKey(
+            *authority.key,
+            expected_upgrade_auth_key,
+        ));
+    }
+    if !authority.is_signer {
+        return Err(Error::AccountNotSigner(*authority.key));
+    }
+
+    Ok(())
+}
+
+fn get_program_upgrade_authority(
+    program_id: &Pubkey,
+    program_data: &AccountInfo,
 ) -> Result<Pubkey> {
     let (expected_program_data_key, _) =
         Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
@@ -102,17 +122,11 @@ pub fn process<'a>(
         ));
     }
 
-    let expected_upgrade_auth_key =
-        get_program_upgrade_authority(program_id, accounts.program_data)?;
-    if *accounts.program_upgrade_auth.key != expected_upgrade_auth_key {
-        return Err(Error::AccountInvalidKey(
-            *accounts.program_upgrade_auth.key,
-            expected_upgrade_auth_key,
-        ));
-    }
-    if !accounts.program_upgrade_auth.is_signer {
-        return Err(Error::AccountNotSigner(*accounts.program_upgrade_auth.key));
-    }
+    validate_upgrade_authority(
+        program_id,
+        accounts.program_data,
+        accounts.program_upgrade_auth,
+    )?;
 
     accounts.system_program.create_pda_account(
         &spl_token::id(),
@@ -19,6 +19,7 @@ pub enum EvmInstruction {
     ///  `[]` SPL Token program id.
     ///  `[writeable,signer]` Funding account (must be a system account).
     ///  `[]` System program.
+    ///  `[]` Chain Registry.
     /// Instruction data:
     ///  0..20  - destination address
     ///  20..28 - chain id in little endian
@@ -71,7 +72,7 @@ pub enum EvmInstruction {
     ///  `[WRITE]` Treasury
     ///  `[WRITE]` Operator Balance
     ///  `[]` System program
-    ///  `[WRITE?]` Other accounts
+    ///  `[WRITE?]` Other accounts, including the Chain Registry
     /// Instruction data:
     ///  0..4 - treasury index in little endian
     ///  4..  - transaction data
@@ -85,7 +86,7 @@ pub enum EvmInstruction {
     ///  `[WRITE]` Treasury
     ///  `[WRITE]` Operator Balance
     ///  `[]` System program
-    ///  `[WRITE?]` Other accounts
+    ///  `[WRITE?]` Other accounts, including the Chain Registry
     /// Instruction data:
     ///  0..4 - treasury index in little endian
     TransactionExecuteFromAccount,
@@ -98,7 +99,7 @@ pub enum EvmInstruction {
     ///  `[WRITE]` Treasury
     ///  `[WRITE]` Operator Balance
     ///  `[]` System program
-    ///  `[WRITE]`  Other accounts
+    ///  `[WRITE]`  Other accounts, including the Chain and Operator Registries
     /// Instruction data:
     ///  0..4 - treasury index in little endian
     ///  4..8 - step count in little endian
@@ -113,7 +114,7 @@ pub enum EvmInstruction {
     ///  `[WRITE]` Treasury
     ///  `[WRITE]` Operator Balance
     ///  `[]` System program
-    ///  `[WRITE]`  Other accounts
+    ///  `[WRITE]`  Other accounts, including the Chain and Operator Registries
     /// Instruction data:
     ///  0..4 - treasury index in little endian
     ///  4..8 - step count in little endian
@@ -127,18 +128,20 @@ pub enum EvmInstruction {
     ///  `[WRITE]` Treasury
     ///  `[WRITE]` Operator Balance
     ///  `[]` System program
-    ///  `[WRITE]`  Other accounts
+    ///  `[WRITE]`  Other accounts, including the Chain and Operator Registries
     /// Instruction data:
     ///  0..4 - treasury index in little endian
     ///  4..8 - step count in little endian
     TransactionStepFromAccountNoChainId,
 
     /// Cancel Transaction
+    /// Other operators can cancel it once it is abandoned for `OPERATOR_PRIORITY_SLOTS`.
     ///
     /// Accounts:
     ///  `[WRITE]` State
     ///  `[SIGNER]` Operator
     ///  `[WRITE]` Operator Balance
+    ///  `[WRITE]`  Other accounts, including the Operator Registry
     /// Instruction data:
     ///   0..32 - transaction hash
     Cancel,
@@ -157,9 +160,98 @@ pub enum EvmInstruction {
     ///  None
     CreateMainTreasury,
 
-    /// Block additional accounts
+    /// Block additional accounts for the Iterative Transaction in progress
+    /// Other operators can add accounts once it is abandoned for `OPERATOR_PRIORITY_SLOTS`.
+    ///
+    /// Accounts:
+    ///  `[WRITE]` State
+    ///  `[SIGNER]` Operator
+    ///  `[WRITE]`  Other accounts, including the Operator Registry
+    /// Instruction data:
+    ///   0..32 - transaction hash
     AccountBlockAdd,
 
+    /// Close empty Storage Cell and Contract accounts, return rent to the recipient.
+    /// Storage Cell is empty when all its values are zero,
+    /// Contract is empty when it has no code, zero storage and was never selfdestructed.
+    ///
+    /// Accounts:
+    ///  `[SIGNER]` Operator
+    ///  `[WRITE]` Recipient: Main Treasury or Operator Balance
+    ///  `[WRITE]` Storage Cell or Contract accounts
+    ///  `[]` Operator Registry
+    /// Instruction data:
+    ///   None
+    AccountReclaim,
+
+    /// Add a chain to the Chain Registry or enable the disabled one.
+    /// Registry is created from the compiled chain list on the first call.
+    ///
+    /// Accounts:
+    ///  `[WRITE]` Chain Registry
+    ///  `[]` Program data
+    ///  `[SIGNER]` Program upgrade authority
+    ///  `[WRITE,SIGNER]` Payer
+    ///  `[]` System program
+    /// Instruction data:
+    ///   0..8   - chain id in little endian
+    ///   8..40  - token mint
+    ///   40     - EVM spec
+    ///   41..   - chain name
+    ChainRegistryAdd,
+
+    /// Disable a chain in the Chain Registry.
+    /// Registry is created from the compiled chain list on the first call.
+    ///
+    /// Accounts:
+    ///  `[WRITE]` Chain Registry
+    ///  `[]` Program data
+    ///  `[SIGNER]` Program upgrade authority
+    ///  `[WRITE,SIGNER]` Payer
+    ///  `[]` System program
+    /// Instruction data:
+    ///   0..8   - chain id in little endian
+    ChainRegistryDisable,
+
+    /// Add an operator to the Operator Registry or resume the suspended one.
+    /// Registry is created from the compiled operator list on the first call.
+    ///
+    /// Accounts:
+    ///  `[WRITE]` Operator Registry
+    ///  `[]` Program data
+    ///  `[SIGNER]` Program upgrade authority
+    ///  `[WRITE,SIGNER]` Payer
+    ///  `[]` System program
+    /// Instruction data:
+    ///   0..32  - operator key
+    OperatorRegistryAdd,
+
+    /// Remove an operator from the Operator Registry.
+    /// Registry is created from the compiled operator list on the first call.
+    ///
+    /// Accounts:
+    ///  `[WRITE]` Operator Registry
+    ///  `[]` Program data
+    ///  `[SIGNER]` Program upgrade authority
+    ///  `[WRITE,SIGNER]` Payer
+    ///  `[]` System program
+    /// Instruction data:
+    ///   0..32  - operator key
+    OperatorRegistryRemove,
+
+    /// Suspend an operator in the Operator Registry until it is added again.
+    /// Registry is created from the compiled operator list on the first call.
+    ///
+    /// Accounts:
+    ///  `[WRITE]` Operator Registry
+    ///  `[]` Program data
+    ///  `[SIGNER]` Program upgrade authority
+    ///  `[WRITE,SIGNER]` Payer
+    ///  `[]` System program
+    /// Instruction data:
+    ///   0..32  - operator key
+    OperatorRegistrySuspend,
+
     /// Create a User Balance account
     ///
     /// Accounts:
@@ -167,6 +259,7 @@ pub enum EvmInstruction {
     ///  `[]` System program
     ///  `[WRITE]` NeonEVM user balance account
     ///  `[WRITE]` NeonEVM user contract account
+    ///  `[]` Chain Registry
     /// Instruction data:
     ///  0..20  - address
     ///  20..28 - chain id in little endian
@@ -189,12 +282,15 @@ impl EvmInstruction {
     /// Will return `ProgramError::InvalidInstructionData` if can't parse `tag`
     pub const fn parse(tag: &u8) -> Result<Self, ProgramError> {
         Ok(match tag {
-            0x1e => Self::CollectTreasure,    // 30
-            0x24 => Self::HolderCreate,       // 36
-            0x25 => Self::HolderDelete,       // 37
-            0x26 => Self::HolderWrite,        // 38
-            0x29 => Self::CreateMainTreasury, // 41
-            0x2B => Self::AccountBlockAdd,    // 43
+            0x1e => Self::CollectTreasure,      // 30
+            0x24 => Self::HolderCreate,         // 36
+            0x25 => Self::HolderDelete,         // 37
+            0x26 => Self::HolderWrite,          // 38
+            0x29 => Self::CreateMainTreasury,   // 41
+            0x2B => Self::AccountBlockAdd,      // 43
+            0x2C => Self::AccountReclaim,       // 44
+            0x2D => Self::ChainRegistryAdd,     // 45
+            0x2E => Self::ChainRegistryDisable, // 46
 
             0x30 => Self::AccountCreateBalance,              // 48
             0x31 => Self::Deposit,                           // 49
@@ -205,6 +301,10 @@ impl EvmInstruction {
             0x36 => Self::TransactionStepFromAccountNoChainId, // 54
             0x37 => Self::Cancel,                            // 55
 
+            0x40 => Self::OperatorRegistryAdd,     // 64
+            0x41 => Self::OperatorRegistryRemove,  // 65
+            0x42 => Self::OperatorRegistrySuspend, // 66
+
             0xA0 => Self::ConfigGetChainCount, // 160
             0xA1 => Self::ConfigGetChainInfo,
             0xA2 => Self::ConfigGetEnvironment,
@@ -223,6 +323,9 @@ pub mod account_create_balance;
 pub mod account_holder_create;
 pub mod account_holder_delete;
 pub mod account_holder_write;
+pub mod account_reclaim;
+pub mod chain_registry_add;
+pub mod chain_registry_disable;
 pub mod collect_treasury;
 pub mod config_get_chain_count;
 pub mod config_get_chain_info;
@@ -234,6 +337,9 @@ pub mod config_get_status;
 pub mod config_get_version;
 pub mod create_main_treasury;
 pub mod neon_tokens_deposit;
+pub mod operator_registry_add;
+pub mod operator_registry_remove;
+pub mod operator_registry_suspend;
 pub mod transaction_cancel;
 pub mod transaction_execute;
 pub mod transaction_execute_from_account;
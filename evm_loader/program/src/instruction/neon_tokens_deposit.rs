@@ -4,7 +4,10 @@ use solana_program::program::invoke_signed;
 use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
 use spl_associated_token_account::get_associated_token_address;
 
-use crate::account::{program, token, AccountsDB, BalanceAccount, Operator, ACCOUNT_SEED_VERSION};
+use crate::account::{
+    find_chain, program, token, AccountsDB, BalanceAccount, ChainRegistry, Operator,
+    ACCOUNT_SEED_VERSION,
+};
 use crate::config::DEFAULT_CHAIN_ID;
 use crate::error::{Error, Result};
 use crate::types::Address;
@@ -18,12 +21,16 @@ struct Accounts<'a> {
     token_program: program::Token<'a>,
     operator: Operator<'a>,
     system_program: program::System<'a>,
+    chain_registry: Option<ChainRegistry<'a>>,
 }
 
 const AUTHORITY_SEED: &[u8] = b"Deposit";
 
 impl<'a> Accounts<'a> {
-    pub fn from_slice(accounts: &'a [AccountInfo<'a>]) -> Result<Accounts<'a>> {
+    pub fn from_slice(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'a>],
+    ) -> Result<Accounts<'a>> {
         Ok(Accounts {
             mint: token::Mint::from_account(&accounts[0])?,
             source: token::State::from_account(&accounts[1])?,
@@ -33,6 +40,7 @@ impl<'a> Accounts<'a> {
             token_program: program::Token::from_account(&accounts[5])?,
             operator: unsafe { Operator::from_account_not_whitelisted(&accounts[6]) }?,
             system_program: program::System::from_account(&accounts[7])?,
+            chain_registry: ChainRegistry::from_instruction_account(program_id, accounts.get(8))?,
         })
     }
 }
@@ -44,7 +52,7 @@ pub fn process<'a>(
 ) -> Result<()> {
     solana_program::msg!("Instruction: Deposit");
 
-    let parsed_accounts = Accounts::from_slice(accounts)?;
+    let parsed_accounts = Accounts::from_slice(program_id, accounts)?;
 
     let address = array_ref![instruction, 0, 20];
     let address = Address::from(*address);
@@ -77,11 +85,10 @@ fn validate(
         return Err(Error::AccountInvalidKey(contract_account, expected_pubkey));
     }
 
-    let Ok(chain_id_index) = crate::config::CHAIN_ID_LIST.binary_search_by_key(&chain_id, |c| c.0) else {
+    let Some((expected_mint, _)) = find_chain(accounts.chain_registry.as_ref(), chain_id)? else {
         return Err(Error::InvalidChainId(chain_id));
     };
 
-    let expected_mint = crate::config::CHAIN_ID_LIST[chain_id_index].2;
     if mint != expected_mint {
         return Err(Error::AccountInvalidKey(mint, expected_mint));
     }
@@ -0,0 +1,31 @@
+use crate::account::{program::System, Operator, OperatorRegistry};
+use crate::error::Result;
+use arrayref::array_ref;
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+
+use super::create_main_treasury::validate_upgrade_authority;
+
+pub fn process<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    instruction: &[u8],
+) -> Result<()> {
+    solana_program::msg!("Instruction: Suspend Operator");
+
+    let registry_info = accounts[0].clone();
+    let program_data = &accounts[1];
+    let program_upgrade_auth = &accounts[2];
+    let payer = unsafe { Operator::from_account_not_whitelisted(&accounts[3]) }?;
+    let system = System::from_account(&accounts[4])?;
+
+    validate_upgrade_authority(program_id, program_data, program_upgrade_auth)?;
+
+    let operator = Pubkey::new_from_array(*array_ref![instruction, 0, 32]);
+
+    let mut registry = match OperatorRegistry::load(program_id, registry_info.clone())? {
+        Some(registry) => registry,
+        None => OperatorRegistry::create(program_id, registry_info, &system, &payer)?,
+    };
+
+    registry.suspend(&operator)
+}
@@ -15,7 +15,7 @@ pub fn process<'a>(
     let transaction_hash = array_ref![instruction, 0, 32];
 
     let storage_info = accounts[0].clone();
-    let operator = Operator::from_account(&accounts[1])?;
+    let operator = Operator::from_account(program_id, &accounts[1], accounts)?;
     let operator_balance = BalanceAccount::from_account(program_id, accounts[2].clone())?;
 
     solana_program::log::sol_log_data(&[b"HASH", transaction_hash]);
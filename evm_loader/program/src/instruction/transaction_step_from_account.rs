@@ -33,7 +33,7 @@ pub fn process_inner<'a>(
 
     let holder_or_storage = &accounts[0];
 
-    let operator = Operator::from_account(&accounts[1])?;
+    let operator = Operator::from_account(program_id, &accounts[1], accounts)?;
     let treasury = Treasury::from_account(program_id, treasury_index, &accounts[2])?;
     let operator_balance = BalanceAccount::from_account(program_id, accounts[3].clone())?;
     let system = program::System::from_account(&accounts[4])?;
@@ -48,6 +48,7 @@ pub enum TransactionEnvelope {
     Legacy,
     AccessList,
     DynamicFee,
+    Blob,
 }
 
 impl TransactionEnvelope {
@@ -61,6 +62,7 @@ impl TransactionEnvelope {
                 0x00 => (Some(TransactionEnvelope::Legacy), &bytes[1..]),
                 0x01 => (Some(TransactionEnvelope::AccessList), &bytes[1..]),
                 0x02 => (Some(TransactionEnvelope::DynamicFee), &bytes[1..]),
+                0x03 => (Some(TransactionEnvelope::Blob), &bytes[1..]),
                 byte => panic!("Unsupported EIP-2718 Transaction type | First byte: {byte}"),
             }
         }
@@ -194,27 +196,7 @@ impl rlp::Decodable for AccessListTx {
         let value: U256 = u256(&rlp.at(5)?)?;
         let call_data = rlp.val_at(6)?;
 
-        let rlp_access_list = rlp.at(7)?;
-        let mut access_list = vec![];
-
-        for entry in rlp_access_list.iter() {
-            // Check if entry is a list
-            if entry.is_list() {
-                // Parse address from first element
-                let address: Address = entry.at(0)?.as_val()?;
-
-                // Get storage keys from second element
-                let mut storage_keys: Vec<StorageKey> = vec![];
-
-                for key in entry.at(1)?.iter() {
-                    storage_keys.push(key.as_val()?);
-                }
-
-                access_list.push((address, storage_keys));
-            } else {
-                return Err(rlp::DecoderError::RlpExpectedToBeList);
-            }
-        }
+        let access_list = decode_access_list(&rlp.at(7)?)?;
 
         let y_parity: u8 = rlp.at(8)?.as_val()?;
         let r: U256 = u256(&rlp.at(9)?)?;
@@ -242,13 +224,188 @@ impl rlp::Decodable for AccessListTx {
     }
 }
 
-// TODO: Will be added as a part of EIP-1559
-// struct DynamicFeeTx {}
+#[derive(Debug)]
+pub struct DynamicFeeTx {
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: U256,
+    pub target: Option<Address>,
+    pub value: U256,
+    pub call_data: Vec<u8>,
+    pub r: U256,
+    pub s: U256,
+    pub chain_id: U256,
+    pub recovery_id: u8,
+    pub access_list: Vec<(Address, Vec<StorageKey>)>,
+}
+
+impl DynamicFeeTx {
+    /// Neon EVM has no block base fee (BASEFEE is zero),
+    /// so the effective gas price is the priority fee capped by the max fee.
+    #[must_use]
+    pub fn effective_gas_price(&self) -> U256 {
+        self.max_priority_fee_per_gas.min(self.max_fee_per_gas)
+    }
+}
+
+impl rlp::Decodable for DynamicFeeTx {
+    fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        let rlp_len = {
+            let info = rlp.payload_info()?;
+            info.header_len + info.value_len
+        };
+
+        if rlp.as_raw().len() != rlp_len {
+            return Err(rlp::DecoderError::RlpInconsistentLengthAndData);
+        }
+
+        let chain_id: U256 = u256(&rlp.at(0)?)?;
+        let nonce: u64 = rlp.val_at(1)?;
+        let max_priority_fee_per_gas: U256 = u256(&rlp.at(2)?)?;
+        let max_fee_per_gas: U256 = u256(&rlp.at(3)?)?;
+        let gas_limit: U256 = u256(&rlp.at(4)?)?;
+        let target: Option<Address> = {
+            let target = rlp.at(5)?;
+            if target.is_empty() {
+                if target.is_data() {
+                    None
+                } else {
+                    return Err(rlp::DecoderError::RlpExpectedToBeData);
+                }
+            } else {
+                Some(target.as_val()?)
+            }
+        };
+
+        let value: U256 = u256(&rlp.at(6)?)?;
+        let call_data = rlp.val_at(7)?;
+        let access_list = decode_access_list(&rlp.at(8)?)?;
+
+        let y_parity: u8 = rlp.at(9)?.as_val()?;
+        let r: U256 = u256(&rlp.at(10)?)?;
+        let s: U256 = u256(&rlp.at(11)?)?;
+
+        if rlp.at(12).is_ok() {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        if max_priority_fee_per_gas > max_fee_per_gas {
+            return Err(rlp::DecoderError::Custom(
+                "max_priority_fee_per_gas exceeds max_fee_per_gas",
+            ));
+        }
+
+        let tx = DynamicFeeTx {
+            nonce,
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+            gas_limit,
+            target,
+            value,
+            call_data,
+            r,
+            s,
+            chain_id,
+            recovery_id: y_parity,
+            access_list,
+        };
+
+        Ok(tx)
+    }
+}
+
+/// EIP-4844 blob transaction. Decoded only to be rejected, Neon EVM doesn't support blobs.
+#[derive(Debug)]
+pub struct BlobTx {
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: U256,
+    pub target: Address,
+    pub value: U256,
+    pub call_data: Vec<u8>,
+    pub max_fee_per_blob_gas: U256,
+    pub blob_versioned_hashes: Vec<[u8; 32]>,
+    pub r: U256,
+    pub s: U256,
+    pub chain_id: U256,
+    pub recovery_id: u8,
+    pub access_list: Vec<(Address, Vec<StorageKey>)>,
+}
+
+impl rlp::Decodable for BlobTx {
+    fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+        let rlp_len = {
+            let info = rlp.payload_info()?;
+            info.header_len + info.value_len
+        };
+
+        if rlp.as_raw().len() != rlp_len {
+            return Err(rlp::DecoderError::RlpInconsistentLengthAndData);
+        }
+
+        // Network form: [tx_payload_body, blobs, commitments, proofs]
+        if rlp.at(0)?.is_list() {
+            if rlp.item_count()? != 4 {
+                return Err(rlp::DecoderError::RlpIncorrectListLen);
+            }
+
+            return rlp.val_at(0);
+        }
+
+        let chain_id: U256 = u256(&rlp.at(0)?)?;
+        let nonce: u64 = rlp.val_at(1)?;
+        let max_priority_fee_per_gas: U256 = u256(&rlp.at(2)?)?;
+        let max_fee_per_gas: U256 = u256(&rlp.at(3)?)?;
+        let gas_limit: U256 = u256(&rlp.at(4)?)?;
+        // Blob transaction can't create a contract
+        let target: Address = rlp.val_at(5)?;
+        let value: U256 = u256(&rlp.at(6)?)?;
+        let call_data = rlp.val_at(7)?;
+        let access_list = decode_access_list(&rlp.at(8)?)?;
+        let max_fee_per_blob_gas: U256 = u256(&rlp.at(9)?)?;
+
+        let mut blob_versioned_hashes = vec![];
+        for hash in &rlp.at(10)? {
+            let StorageKey(hash) = hash.as_val()?;
+            blob_versioned_hashes.push(hash);
+        }
+
+        let y_parity: u8 = rlp.at(11)?.as_val()?;
+        let r: U256 = u256(&rlp.at(12)?)?;
+        let s: U256 = u256(&rlp.at(13)?)?;
+
+        if rlp.at(14).is_ok() {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
+
+        let tx = BlobTx {
+            nonce,
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+            gas_limit,
+            target,
+            value,
+            call_data,
+            max_fee_per_blob_gas,
+            blob_versioned_hashes,
+            r,
+            s,
+            chain_id,
+            recovery_id: y_parity,
+            access_list,
+        };
+
+        Ok(tx)
+    }
+}
 
 #[derive(Debug)]
 pub enum TransactionPayload {
     Legacy(LegacyTx),
     AccessList(AccessListTx),
+    DynamicFee(DynamicFeeTx),
 }
 
 #[derive(Debug)]
@@ -283,6 +440,20 @@ impl Transaction {
 
                 (hash, signed_hash)
             }
+            // Dynamic Fee transaction
+            Some(TransactionEnvelope::DynamicFee) => {
+                let hash =
+                    solana_program::keccak::hashv(&[&[0x02], transaction_rlp.as_raw()]).to_bytes();
+                let signed_hash = Self::eip2718_signed_hash(&[0x02], transaction_rlp, 9)?;
+
+                (hash, signed_hash)
+            }
+            // Blob transactions are rejected before this point
+            Some(TransactionEnvelope::Blob) => {
+                return Err(rlp::DecoderError::Custom(
+                    "Blob transaction is not supported",
+                ));
+            }
             // Legacy trasaction
             None => {
                 let hash = solana_program::keccak::hash(transaction_rlp.as_raw()).to_bytes();
@@ -290,7 +461,6 @@ impl Transaction {
 
                 (hash, signed_hash)
             }
-            _ => unimplemented!(),
         };
 
         let info = transaction_rlp.payload_info()?;
@@ -442,13 +612,39 @@ impl Transaction {
                     tx,
                 )?
             }
+            Some(TransactionEnvelope::Blob) => {
+                let blob_tx = rlp::decode::<BlobTx>(transaction).map_err(Error::from)?;
+
+                // Hash of the network form covers only the transaction payload body
+                let body = match rlp::Rlp::new(transaction).at(0) {
+                    Ok(body) if body.is_list() => body.as_raw(),
+                    _ => transaction,
+                };
+                let hash = solana_program::keccak::hashv(&[&[0x03], body]).to_bytes();
+
+                return Err(Error::BlobTransactionNotSupported(
+                    hash,
+                    blob_tx.blob_versioned_hashes.len(),
+                ));
+            }
             None => {
                 let legacy_tx = rlp::decode::<LegacyTx>(transaction).map_err(Error::from)?;
                 let chain_id = legacy_tx.chain_id;
                 let tx = TransactionPayload::Legacy(legacy_tx);
                 Transaction::from_payload(&None, chain_id, &rlp::Rlp::new(transaction), tx)?
             }
-            Some(TransactionEnvelope::DynamicFee) => unimplemented!(),
+            Some(TransactionEnvelope::DynamicFee) => {
+                let dynamic_fee_tx =
+                    rlp::decode::<DynamicFeeTx>(transaction).map_err(Error::from)?;
+                let chain_id = dynamic_fee_tx.chain_id;
+                let tx = TransactionPayload::DynamicFee(dynamic_fee_tx);
+                Transaction::from_payload(
+                    &Some(TransactionEnvelope::DynamicFee),
+                    Some(chain_id),
+                    &rlp::Rlp::new(transaction),
+                    tx,
+                )?
+            }
         };
 
         Ok(tx)
@@ -471,7 +667,8 @@ impl Transaction {
     pub fn nonce(&self) -> u64 {
         match self.transaction {
             TransactionPayload::Legacy(LegacyTx { nonce, .. })
-            | TransactionPayload::AccessList(AccessListTx { nonce, .. }) => nonce,
+            | TransactionPayload::AccessList(AccessListTx { nonce, .. })
+            | TransactionPayload::DynamicFee(DynamicFeeTx { nonce, .. }) => nonce,
         }
     }
 
@@ -480,6 +677,7 @@ impl Transaction {
         match self.transaction {
             TransactionPayload::Legacy(LegacyTx { gas_price, .. })
             | TransactionPayload::AccessList(AccessListTx { gas_price, .. }) => gas_price,
+            TransactionPayload::DynamicFee(ref tx) => tx.effective_gas_price(),
         }
     }
 
@@ -487,7 +685,8 @@ impl Transaction {
     pub fn gas_limit(&self) -> U256 {
         match self.transaction {
             TransactionPayload::Legacy(LegacyTx { gas_limit, .. })
-            | TransactionPayload::AccessList(AccessListTx { gas_limit, .. }) => gas_limit,
+            | TransactionPayload::AccessList(AccessListTx { gas_limit, .. })
+            | TransactionPayload::DynamicFee(DynamicFeeTx { gas_limit, .. }) => gas_limit,
         }
     }
 
@@ -495,7 +694,8 @@ impl Transaction {
     pub fn target(&self) -> Option<Address> {
         match self.transaction {
             TransactionPayload::Legacy(LegacyTx { target, .. })
-            | TransactionPayload::AccessList(AccessListTx { target, .. }) => target,
+            | TransactionPayload::AccessList(AccessListTx { target, .. })
+            | TransactionPayload::DynamicFee(DynamicFeeTx { target, .. }) => target,
         }
     }
 
@@ -503,7 +703,8 @@ impl Transaction {
     pub fn value(&self) -> U256 {
         match self.transaction {
             TransactionPayload::Legacy(LegacyTx { value, .. })
-            | TransactionPayload::AccessList(AccessListTx { value, .. }) => value,
+            | TransactionPayload::AccessList(AccessListTx { value, .. })
+            | TransactionPayload::DynamicFee(DynamicFeeTx { value, .. }) => value,
         }
     }
 
@@ -511,7 +712,8 @@ impl Transaction {
     pub fn call_data(&self) -> &[u8] {
         match &self.transaction {
             TransactionPayload::Legacy(LegacyTx { call_data, .. })
-            | TransactionPayload::AccessList(AccessListTx { call_data, .. }) => call_data,
+            | TransactionPayload::AccessList(AccessListTx { call_data, .. })
+            | TransactionPayload::DynamicFee(DynamicFeeTx { call_data, .. }) => call_data,
         }
     }
 
@@ -519,7 +721,8 @@ impl Transaction {
     pub fn into_call_data(self) -> crate::evm::Buffer {
         match self.transaction {
             TransactionPayload::Legacy(LegacyTx { call_data, .. })
-            | TransactionPayload::AccessList(AccessListTx { call_data, .. }) => {
+            | TransactionPayload::AccessList(AccessListTx { call_data, .. })
+            | TransactionPayload::DynamicFee(DynamicFeeTx { call_data, .. }) => {
                 crate::evm::Buffer::from_vec(call_data)
             }
         }
@@ -529,7 +732,8 @@ impl Transaction {
     pub fn r(&self) -> U256 {
         match self.transaction {
             TransactionPayload::Legacy(LegacyTx { r, .. })
-            | TransactionPayload::AccessList(AccessListTx { r, .. }) => r,
+            | TransactionPayload::AccessList(AccessListTx { r, .. })
+            | TransactionPayload::DynamicFee(DynamicFeeTx { r, .. }) => r,
         }
     }
 
@@ -537,7 +741,8 @@ impl Transaction {
     pub fn s(&self) -> U256 {
         match self.transaction {
             TransactionPayload::Legacy(LegacyTx { s, .. })
-            | TransactionPayload::AccessList(AccessListTx { s, .. }) => s,
+            | TransactionPayload::AccessList(AccessListTx { s, .. })
+            | TransactionPayload::DynamicFee(DynamicFeeTx { s, .. }) => s,
         }
     }
 
@@ -545,7 +750,8 @@ impl Transaction {
     pub fn chain_id(&self) -> Option<u64> {
         match self.transaction {
             TransactionPayload::Legacy(LegacyTx { chain_id, .. }) => chain_id,
-            TransactionPayload::AccessList(AccessListTx { chain_id, .. }) => Some(chain_id),
+            TransactionPayload::AccessList(AccessListTx { chain_id, .. })
+            | TransactionPayload::DynamicFee(DynamicFeeTx { chain_id, .. }) => Some(chain_id),
         }
         .map(std::convert::TryInto::try_into)
         .transpose()
@@ -556,7 +762,8 @@ impl Transaction {
     pub fn recovery_id(&self) -> u8 {
         match self.transaction {
             TransactionPayload::Legacy(LegacyTx { recovery_id, .. })
-            | TransactionPayload::AccessList(AccessListTx { recovery_id, .. }) => recovery_id,
+            | TransactionPayload::AccessList(AccessListTx { recovery_id, .. })
+            | TransactionPayload::DynamicFee(DynamicFeeTx { recovery_id, .. }) => recovery_id,
         }
     }
 
@@ -578,12 +785,40 @@ impl Transaction {
     #[must_use]
     pub fn access_list(&self) -> Option<&Vec<(Address, Vec<StorageKey>)>> {
         match &self.transaction {
-            TransactionPayload::AccessList(AccessListTx { access_list, .. }) => Some(access_list),
+            TransactionPayload::AccessList(AccessListTx { access_list, .. })
+            | TransactionPayload::DynamicFee(DynamicFeeTx { access_list, .. }) => Some(access_list),
             TransactionPayload::Legacy(_) => None,
         }
     }
 }
 
+fn decode_access_list(
+    rlp_access_list: &rlp::Rlp,
+) -> Result<Vec<(Address, Vec<StorageKey>)>, rlp::DecoderError> {
+    let mut access_list = vec![];
+
+    for entry in rlp_access_list.iter() {
+        // Check if entry is a list
+        if entry.is_list() {
+            // Parse address from first element
+            let address: Address = entry.at(0)?.as_val()?;
+
+            // Get storage keys from second element
+            let mut storage_keys: Vec<StorageKey> = vec![];
+
+            for key in entry.at(1)?.iter() {
+                storage_keys.push(key.as_val()?);
+            }
+
+            access_list.push((address, storage_keys));
+        } else {
+            return Err(rlp::DecoderError::RlpExpectedToBeList);
+        }
+    }
+
+    Ok(access_list)
+}
+
 #[inline]
 fn u256(rlp: &rlp::Rlp) -> Result<U256, rlp::DecoderError> {
     rlp.decoder().decode_value(|bytes| {
@@ -598,3 +833,124 @@ fn u256(rlp: &rlp::Rlp) -> Result<U256, rlp::DecoderError> {
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dynamic_fee_fields(stream: &mut rlp::RlpStream) {
+        stream.append(&111_u64); // chain_id
+        stream.append(&7_u64); // nonce
+        stream.append(&2_000_000_000_u64); // max_priority_fee_per_gas
+        stream.append(&3_000_000_000_u64); // max_fee_per_gas
+        stream.append(&21_000_u64); // gas_limit
+        stream.append(&[0x11_u8; 20].as_slice()); // target
+        stream.append(&1_000_u64); // value
+        stream.append(&vec![0xAA_u8, 0xBB]); // call_data
+        stream.begin_list(1);
+        stream.begin_list(2);
+        stream.append(&[0x22_u8; 20].as_slice());
+        stream.begin_list(1);
+        stream.append(&[0x33_u8; 32].as_slice());
+    }
+
+    #[test]
+    fn decode_dynamic_fee_transaction() {
+        let mut unsigned = rlp::RlpStream::new_list(9);
+        dynamic_fee_fields(&mut unsigned);
+        let unsigned = unsigned.out();
+
+        let mut signed = rlp::RlpStream::new_list(12);
+        dynamic_fee_fields(&mut signed);
+        signed.append(&1_u8); // y_parity
+        signed.append(&[0x44_u8; 32].as_slice()); // r
+        signed.append(&[0x55_u8; 32].as_slice()); // s
+        let signed = [&[0x02], signed.out().as_ref()].concat();
+
+        let trx = Transaction::from_rlp(&signed).unwrap();
+
+        assert_eq!(trx.chain_id(), Some(111));
+        assert_eq!(trx.nonce(), 7);
+        assert_eq!(trx.gas_price(), U256::new(2_000_000_000));
+        assert_eq!(trx.gas_limit(), U256::new(21_000));
+        assert_eq!(trx.target(), Some(Address::from([0x11; 20])));
+        assert_eq!(trx.value(), U256::new(1_000));
+        assert_eq!(trx.call_data(), &[0xAA, 0xBB]);
+        assert_eq!(trx.recovery_id(), 1);
+        assert_eq!(trx.access_list().unwrap().len(), 1);
+        assert_eq!(trx.rlp_len(), signed.len());
+
+        let hash = solana_program::keccak::hash(&signed).to_bytes();
+        assert_eq!(trx.hash(), hash);
+
+        let signed_hash = solana_program::keccak::hashv(&[&[0x02], &unsigned]).to_bytes();
+        assert_eq!(trx.signed_hash(), signed_hash);
+    }
+
+    fn blob_fields(stream: &mut rlp::RlpStream) {
+        stream.begin_list(14);
+        stream.append(&111_u64); // chain_id
+        stream.append(&0_u64); // nonce
+        stream.append(&1_u64); // max_priority_fee_per_gas
+        stream.append(&2_u64); // max_fee_per_gas
+        stream.append(&21_000_u64); // gas_limit
+        stream.append(&[0x11_u8; 20].as_slice()); // target
+        stream.append(&0_u64); // value
+        stream.append_empty_data(); // call_data
+        stream.begin_list(0); // access_list
+        stream.append(&3_u64); // max_fee_per_blob_gas
+        stream.begin_list(2);
+        stream.append(&[0x01_u8; 32].as_slice());
+        stream.append(&[0x01_u8; 32].as_slice());
+        stream.append(&0_u8); // y_parity
+        stream.append(&1_u8); // r
+        stream.append(&1_u8); // s
+    }
+
+    #[test]
+    fn blob_transaction_is_rejected() {
+        let mut stream = rlp::RlpStream::new();
+        blob_fields(&mut stream);
+        let body = stream.out();
+
+        let blob_tx = rlp::decode::<BlobTx>(&body).unwrap();
+        assert_eq!(blob_tx.max_fee_per_blob_gas, U256::new(3));
+        assert_eq!(blob_tx.blob_versioned_hashes, vec![[0x01; 32]; 2]);
+
+        let expected_hash = solana_program::keccak::hashv(&[&[0x03], &body]).to_bytes();
+
+        let mut network = rlp::RlpStream::new_list(4);
+        blob_fields(&mut network);
+        network.begin_list(0);
+        network.begin_list(0);
+        network.begin_list(0);
+        let network = network.out();
+
+        for payload in [&body, &network] {
+            let trx = [&[0x03], payload.as_ref()].concat();
+            match Transaction::from_rlp(&trx) {
+                Err(Error::BlobTransactionNotSupported(hash, 2)) => assert_eq!(hash, expected_hash),
+                other => panic!("unexpected result {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn priority_fee_above_max_fee_is_rejected() {
+        let mut stream = rlp::RlpStream::new_list(12);
+        stream.append(&111_u64);
+        stream.append(&0_u64);
+        stream.append(&2_u64); // max_priority_fee_per_gas
+        stream.append(&1_u64); // max_fee_per_gas
+        stream.append(&21_000_u64);
+        stream.append_empty_data();
+        stream.append(&0_u64);
+        stream.append_empty_data();
+        stream.begin_list(0);
+        stream.append(&0_u8);
+        stream.append(&1_u8);
+        stream.append(&1_u8);
+
+        assert!(rlp::decode::<DynamicFeeTx>(&stream.out()).is_err());
+    }
+}
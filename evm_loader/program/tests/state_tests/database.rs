@@ -0,0 +1,266 @@
+//! In-memory `Database` backed by the fixture pre-state.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use ethnum::U256;
+use evm_loader::{
+    error::{Error, Result},
+    evm::{database::Database, Buffer, Context, Spec},
+    solana_program::{account_info::AccountInfo, keccak, pubkey::Pubkey},
+    types::Address,
+};
+
+use crate::fixture::{AccountState, Env};
+use crate::trie;
+
+pub const CHAIN_ID: u64 = 1;
+
+#[derive(Clone, Default)]
+struct State {
+    accounts: BTreeMap<Address, AccountState>,
+    transient_storage: BTreeMap<(Address, U256), [u8; 32]>,
+    deployed: BTreeSet<Address>,
+    destroyed: BTreeSet<Address>,
+}
+
+pub struct StateDb {
+    state: State,
+    snapshots: Vec<State>,
+    spec: Spec,
+    block_number: U256,
+    block_timestamp: U256,
+}
+
+impl StateDb {
+    pub fn new(pre: &BTreeMap<Address, AccountState>, env: &Env, spec: Spec) -> Self {
+        Self {
+            state: State {
+                accounts: pre.clone(),
+                ..State::default()
+            },
+            snapshots: Vec::new(),
+            spec,
+            block_number: env.current_number,
+            block_timestamp: env.current_timestamp,
+        }
+    }
+
+    fn account(&self, address: Address) -> Option<&AccountState> {
+        self.state.accounts.get(&address)
+    }
+
+    fn account_mut(&mut self, address: Address) -> &mut AccountState {
+        self.state.accounts.entry(address).or_default()
+    }
+
+    pub fn add_balance(&mut self, address: Address, value: U256) {
+        let account = self.account_mut(address);
+        account.balance = account.balance.saturating_add(value);
+    }
+
+    pub fn sub_balance(&mut self, address: Address, value: U256) {
+        let account = self.account_mut(address);
+        account.balance = account.balance.saturating_sub(value);
+    }
+
+    /// Applies end of transaction rules: destroyed accounts and
+    /// empty accounts (EIP-161) are removed, zero storage slots are dropped.
+    pub fn finalize(&mut self) {
+        let destroyed = std::mem::take(&mut self.state.destroyed);
+        self.state.accounts.retain(|address, account| {
+            let is_empty =
+                account.nonce == 0 && account.balance == U256::ZERO && account.code.is_empty();
+            !destroyed.contains(address) && !is_empty
+        });
+
+        for account in self.state.accounts.values_mut() {
+            account.storage.retain(|_, value| *value != U256::ZERO);
+        }
+    }
+
+    pub fn state_root(&self) -> [u8; 32] {
+        let accounts = self.state.accounts.iter().map(|(address, account)| {
+            let storage_root = trie::secure_root(account.storage.iter().map(|(key, value)| {
+                (
+                    key.to_be_bytes().to_vec(),
+                    rlp::encode(&trim_leading_zeros(&value.to_be_bytes())).to_vec(),
+                )
+            }));
+            let code_hash = keccak::hash(&account.code).to_bytes();
+
+            let mut stream = rlp::RlpStream::new_list(4);
+            stream.append(&account.nonce);
+            stream.append(&trim_leading_zeros(&account.balance.to_be_bytes()));
+            stream.append(&storage_root.as_slice());
+            stream.append(&code_hash.as_slice());
+
+            (address.as_bytes().to_vec(), stream.out().to_vec())
+        });
+
+        trie::secure_root(accounts)
+    }
+}
+
+fn trim_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    &bytes[zeros..]
+}
+
+#[async_trait::async_trait(?Send)]
+impl Database for StateDb {
+    fn default_chain_id(&self) -> u64 {
+        CHAIN_ID
+    }
+
+    fn is_valid_chain_id(&self, chain_id: u64) -> bool {
+        chain_id == CHAIN_ID
+    }
+
+    fn chain_spec(&self, _chain_id: u64) -> Result<Spec> {
+        Ok(self.spec)
+    }
+
+    async fn contract_chain_id(&self, _address: Address) -> Result<u64> {
+        Ok(CHAIN_ID)
+    }
+
+    async fn nonce(&self, address: Address, _chain_id: u64) -> Result<u64> {
+        Ok(self.account(address).map_or(0, |a| a.nonce))
+    }
+
+    fn increment_nonce(&mut self, address: Address, _chain_id: u64) -> Result<()> {
+        let account = self.account_mut(address);
+        account.nonce = account
+            .nonce
+            .checked_add(1)
+            .ok_or(Error::NonceOverflow(address))?;
+
+        Ok(())
+    }
+
+    async fn balance(&self, address: Address, _chain_id: u64) -> Result<U256> {
+        Ok(self.account(address).map_or(U256::ZERO, |a| a.balance))
+    }
+
+    async fn transfer(
+        &mut self,
+        source: Address,
+        target: Address,
+        chain_id: u64,
+        value: U256,
+    ) -> Result<()> {
+        if self.balance(source, chain_id).await? < value {
+            return Err(Error::InsufficientBalance(source, chain_id, value));
+        }
+
+        self.sub_balance(source, value);
+        self.add_balance(target, value);
+
+        Ok(())
+    }
+
+    async fn code_size(&self, address: Address) -> Result<usize> {
+        Ok(self.account(address).map_or(0, |a| a.code.len()))
+    }
+
+    async fn code(&self, address: Address) -> Result<Buffer> {
+        let code = self.account(address).map_or(&[][..], |a| &a.code);
+        Ok(Buffer::from_slice(code))
+    }
+
+    fn set_code(&mut self, address: Address, _chain_id: u64, code: Vec<u8>) -> Result<()> {
+        self.account_mut(address).code = code;
+        self.state.deployed.insert(address);
+
+        Ok(())
+    }
+
+    fn selfdestruct(&mut self, address: Address) -> Result<()> {
+        self.state.destroyed.insert(address);
+
+        Ok(())
+    }
+
+    fn is_deployed_in_transaction(&self, address: Address) -> bool {
+        self.state.deployed.contains(&address)
+    }
+
+    async fn storage(&self, address: Address, index: U256) -> Result<[u8; 32]> {
+        let value = self
+            .account(address)
+            .and_then(|a| a.storage.get(&index).copied())
+            .unwrap_or_default();
+
+        Ok(value.to_be_bytes())
+    }
+
+    fn set_storage(&mut self, address: Address, index: U256, value: [u8; 32]) -> Result<()> {
+        let value = U256::from_be_bytes(value);
+        self.account_mut(address).storage.insert(index, value);
+
+        Ok(())
+    }
+
+    fn transient_storage(&self, address: Address, index: U256) -> Result<[u8; 32]> {
+        let value = self.state.transient_storage.get(&(address, index));
+        Ok(value.copied().unwrap_or_default())
+    }
+
+    fn set_transient_storage(
+        &mut self,
+        address: Address,
+        index: U256,
+        value: [u8; 32],
+    ) -> Result<()> {
+        self.state.transient_storage.insert((address, index), value);
+
+        Ok(())
+    }
+
+    async fn block_hash(&self, number: U256) -> Result<[u8; 32]> {
+        // Same as the reference implementation (geth `vmTestBlockHash`)
+        let lower = self.block_number.saturating_sub(U256::new(256));
+        if number >= self.block_number || number < lower {
+            return Ok([0; 32]);
+        }
+
+        Ok(keccak::hash(number.to_string().as_bytes()).to_bytes())
+    }
+
+    fn block_number(&self) -> Result<U256> {
+        Ok(self.block_number)
+    }
+
+    fn block_timestamp(&self) -> Result<U256> {
+        Ok(self.block_timestamp)
+    }
+
+    async fn map_solana_account<F, R>(&self, address: &Pubkey, _action: F) -> R
+    where
+        F: FnOnce(&AccountInfo) -> R,
+    {
+        panic!("state tests don't have Solana accounts: {address}")
+    }
+
+    fn snapshot(&mut self) {
+        self.snapshots.push(self.state.clone());
+    }
+
+    fn revert_snapshot(&mut self) {
+        self.state = self.snapshots.pop().expect("snapshot stack is empty");
+    }
+
+    fn commit_snapshot(&mut self) {
+        self.snapshots.pop().expect("snapshot stack is empty");
+    }
+
+    async fn precompile_extension(
+        &mut self,
+        _context: &Context,
+        _address: &Address,
+        _data: &[u8],
+        _is_static: bool,
+    ) -> Option<Result<Vec<u8>>> {
+        None
+    }
+}
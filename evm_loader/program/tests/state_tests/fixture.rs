@@ -0,0 +1,108 @@
+//! Ethereum GeneralStateTests filled JSON format.
+//! See https://ethereum-tests.readthedocs.io/en/latest/test_types/state_tests.html
+
+use std::collections::BTreeMap;
+
+use ethnum::U256;
+use evm_loader::types::Address;
+use serde::{Deserialize, Deserializer};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateTest {
+    pub env: Env,
+    pub pre: BTreeMap<Address, AccountState>,
+    pub post: BTreeMap<String, Vec<PostState>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Env {
+    pub current_coinbase: Address,
+    #[serde(deserialize_with = "u256")]
+    pub current_number: U256,
+    #[serde(deserialize_with = "u256")]
+    pub current_timestamp: U256,
+    #[serde(default, deserialize_with = "option_u256")]
+    pub current_base_fee: Option<U256>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AccountState {
+    #[serde(deserialize_with = "u64")]
+    pub nonce: u64,
+    #[serde(deserialize_with = "u256")]
+    pub balance: U256,
+    #[serde(deserialize_with = "bytes")]
+    pub code: Vec<u8>,
+    #[serde(deserialize_with = "storage")]
+    pub storage: BTreeMap<U256, U256>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostState {
+    #[serde(deserialize_with = "h256")]
+    pub hash: [u8; 32],
+    #[serde(deserialize_with = "h256")]
+    pub logs: [u8; 32],
+    #[serde(deserialize_with = "bytes")]
+    pub txbytes: Vec<u8>,
+    pub indexes: Indexes,
+    pub expect_exception: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Indexes {
+    pub data: usize,
+    pub gas: usize,
+    pub value: usize,
+}
+
+fn strip_hex_prefix(s: &str) -> &str {
+    s.strip_prefix("0x").unwrap_or(s)
+}
+
+fn parse_u256(s: &str) -> Result<U256, String> {
+    let digits = strip_hex_prefix(s);
+    if digits.is_empty() {
+        return Ok(U256::ZERO);
+    }
+
+    U256::from_str_radix(digits, 16).map_err(|e| format!("invalid number {s}: {e}"))
+}
+
+fn u256<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    parse_u256(&s).map_err(serde::de::Error::custom)
+}
+
+fn option_u256<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<U256>, D::Error> {
+    u256(deserializer).map(Some)
+}
+
+fn u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let value = u256(deserializer)?;
+    u64::try_from(value).map_err(serde::de::Error::custom)
+}
+
+fn bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    hex::decode(strip_hex_prefix(&s)).map_err(serde::de::Error::custom)
+}
+
+fn h256<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 32], D::Error> {
+    let s = String::deserialize(deserializer)?;
+    let mut hash = [0_u8; 32];
+    hex::decode_to_slice(strip_hex_prefix(&s), &mut hash).map_err(serde::de::Error::custom)?;
+
+    Ok(hash)
+}
+
+fn storage<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<U256, U256>, D::Error> {
+    let raw = BTreeMap::<String, String>::deserialize(deserializer)?;
+    raw.iter()
+        .map(|(key, value)| Ok((parse_u256(key)?, parse_u256(value)?)))
+        .collect::<Result<_, String>>()
+        .map_err(serde::de::Error::custom)
+}
@@ -0,0 +1,83 @@
+//! Reconstructs Ethereum logs from the events the interpreter emits with `sol_log_data`.
+
+use std::sync::Mutex;
+
+use evm_loader::solana_program::{keccak, program_stubs};
+
+static EVENTS: Mutex<Vec<Vec<Vec<u8>>>> = Mutex::new(Vec::new());
+
+struct CaptureStubs;
+
+impl program_stubs::SyscallStubs for CaptureStubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields = fields.iter().map(|f| f.to_vec()).collect();
+        EVENTS.lock().unwrap().push(fields);
+    }
+}
+
+pub fn install() {
+    program_stubs::set_syscall_stubs(Box::new(CaptureStubs));
+}
+
+pub fn clear() {
+    EVENTS.lock().unwrap().clear();
+}
+
+struct Log {
+    address: Vec<u8>,
+    topics: Vec<Vec<u8>>,
+    data: Vec<u8>,
+}
+
+/// Logs of reverted frames are discarded, the rest are merged into the parent frame.
+fn collect() -> Vec<Log> {
+    let events = std::mem::take(&mut *EVENTS.lock().unwrap());
+    let mut frames: Vec<Vec<Log>> = vec![Vec::new()];
+
+    for event in events {
+        match event[0].as_slice() {
+            b"ENTER" => frames.push(Vec::new()),
+            b"EXIT" => {
+                let logs = frames.pop().expect("unbalanced EXIT event");
+                if frames.is_empty() {
+                    frames.push(Vec::new());
+                }
+                if event[1] != b"REVERT" {
+                    frames.last_mut().unwrap().extend(logs);
+                }
+            }
+            name if name.starts_with(b"LOG") => {
+                // LOGn, address, [n], topics..., data
+                let count = usize::from(event[2][0]);
+                frames.last_mut().unwrap().push(Log {
+                    address: event[1].clone(),
+                    topics: event[3..3 + count].to_vec(),
+                    data: event.get(3 + count).cloned().unwrap_or_default(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    frames.into_iter().flatten().collect()
+}
+
+/// keccak(rlp([[address, [topics...], data]...])), as `logs` in the fixture.
+pub fn logs_hash() -> [u8; 32] {
+    let logs = collect();
+
+    let mut stream = rlp::RlpStream::new_list(logs.len());
+    for log in &logs {
+        stream.begin_list(3);
+        stream.append(&log.address.as_slice());
+        stream.begin_list(log.topics.len());
+        for topic in &log.topics {
+            stream.append(&topic.as_slice());
+        }
+        stream.append(&log.data.as_slice());
+    }
+
+    keccak::hash(&stream.out()).to_bytes()
+}
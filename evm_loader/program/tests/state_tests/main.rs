@@ -0,0 +1,303 @@
+//! Runs Ethereum GeneralStateTests fixtures through the Neon EVM interpreter.
+//!
+//! Fixtures are not part of the repository. Point `STATE_TESTS_PATH` to a local checkout
+//! of `ethereum/tests/GeneralStateTests` (or any directory with filled state tests):
+//!
+//! ```sh
+//! STATE_TESTS_PATH=~/ethereum-tests/GeneralStateTests \
+//!     cargo test --features async-trait,serde_json --test state_tests
+//! ```
+//!
+//! `STATE_TESTS_FILTER` limits the run to tests which id contains the given substring.
+//! Tests listed in `skip.txt` cover intentional divergence of Neon from Ethereum.
+
+mod database;
+mod fixture;
+mod logs;
+mod trie;
+
+use std::path::{Path, PathBuf};
+
+use ethnum::U256;
+use evm_loader::{
+    evm::{gas, ExitStatus, Machine, Spec},
+    types::{Transaction, TransactionPayload},
+};
+
+use database::StateDb;
+use fixture::{Env, PostState, StateTest};
+
+const STEP_LIMIT: u64 = 10_000_000;
+
+enum Outcome {
+    Pass,
+    Fail(String),
+    Skip(String),
+}
+
+fn fork_spec(fork: &str) -> Option<Spec> {
+    match fork {
+        "Istanbul" => Some(Spec::Istanbul),
+        "Berlin" => Some(Spec::Berlin),
+        "London" | "Merge" | "Paris" => Some(Spec::London),
+        "Shanghai" => Some(Spec::Shanghai),
+        "Cancun" => Some(Spec::Cancun),
+        _ => None,
+    }
+}
+
+struct SkipList(Vec<String>);
+
+impl SkipList {
+    fn load() -> Self {
+        let entries = include_str!("skip.txt")
+            .lines()
+            .map(|line| line.split('#').next().unwrap().trim())
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect();
+
+        Self(entries)
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.0.iter().any(|entry| match entry.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == entry,
+        })
+    }
+}
+
+fn collect_fixtures(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if path.is_file() {
+        if path.extension().map_or(false, |ext| ext == "json") {
+            files.push(path.to_path_buf());
+        }
+        return Ok(());
+    }
+
+    let mut entries = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for entry in entries {
+        collect_fixtures(&entry, files)?;
+    }
+
+    Ok(())
+}
+
+/// Transaction is invalid and must not be included in a block.
+/// Ethereum clients do these checks before the EVM is involved.
+fn validate(trx: &Transaction, sender_balance: U256, env: &Env, spec: Spec) -> Result<(), String> {
+    if U256::from(gas::intrinsic_gas(trx)) > trx.gas_limit() {
+        return Err("intrinsic gas too low".into());
+    }
+
+    let max_gas_price = match &trx.transaction {
+        TransactionPayload::DynamicFee(tx) => tx.max_fee_per_gas,
+        _ => trx.gas_price(),
+    };
+
+    if let (Some(base_fee), true) = (env.current_base_fee, spec.is_enabled(Spec::London)) {
+        if max_gas_price < base_fee {
+            return Err("max fee per gas less than block base fee".into());
+        }
+    }
+
+    let upfront_cost = trx
+        .gas_limit()
+        .checked_mul(max_gas_price)
+        .and_then(|cost| cost.checked_add(trx.value()));
+    if upfront_cost.map_or(true, |cost| sender_balance < cost) {
+        return Err("insufficient funds for gas * price + value".into());
+    }
+
+    Ok(())
+}
+
+/// Gas price paid by the sender and the part of it received by the coinbase.
+fn gas_prices(trx: &Transaction, env: &Env, spec: Spec) -> (U256, U256) {
+    let base_fee = env
+        .current_base_fee
+        .filter(|_| spec.is_enabled(Spec::London))
+        .unwrap_or_default();
+
+    let price = match &trx.transaction {
+        TransactionPayload::DynamicFee(tx) => tx
+            .max_fee_per_gas
+            .min(base_fee.saturating_add(tx.max_priority_fee_per_gas)),
+        _ => trx.gas_price(),
+    };
+
+    (price, price.saturating_sub(base_fee))
+}
+
+/// Executes the transaction. `Ok(false)` means the transaction was rejected as invalid.
+async fn execute(db: &mut StateDb, env: &Env, spec: Spec, txbytes: &[u8]) -> Result<bool, Outcome> {
+    let Ok(trx) = Transaction::from_rlp(txbytes) else {
+        return Ok(false);
+    };
+    let Ok(origin) = trx.recover_caller_address() else {
+        return Ok(false);
+    };
+
+    let balance = evm_loader::evm::database::Database::balance(db, origin, database::CHAIN_ID)
+        .await
+        .map_err(|e| Outcome::Fail(e.to_string()))?;
+    if validate(&trx, balance, env, spec).is_err() {
+        return Ok(false);
+    }
+
+    let (price, coinbase_price) = gas_prices(&trx, env, spec);
+    let gas_limit = trx.gas_limit();
+    db.sub_balance(origin, gas_limit * price);
+
+    let Ok(mut evm) = Machine::new(trx, origin, db, None).await else {
+        db.add_balance(origin, gas_limit * price);
+        return Ok(false);
+    };
+
+    let (status, _) = evm
+        .execute(STEP_LIMIT, db)
+        .await
+        .map_err(|e| Outcome::Fail(format!("execution error: {e}")))?;
+
+    if status == ExitStatus::StepLimit {
+        return Err(Outcome::Skip("step limit exceeded".into()));
+    }
+
+    let gas_used = U256::from(evm.gas_used());
+    if gas_used > gas_limit {
+        // Neon doesn't stop execution when Ethereum gas runs out
+        return Err(Outcome::Skip("out of Ethereum gas".into()));
+    }
+
+    db.add_balance(origin, (gas_limit - gas_used) * price);
+    db.add_balance(env.current_coinbase, gas_used * coinbase_price);
+
+    Ok(true)
+}
+
+async fn run(test: &StateTest, spec: Spec, post: &PostState) -> Outcome {
+    let mut db = StateDb::new(&test.pre, &test.env, spec);
+    logs::clear();
+
+    let is_valid = match execute(&mut db, &test.env, spec, &post.txbytes).await {
+        Ok(is_valid) => is_valid,
+        Err(outcome) => return outcome,
+    };
+
+    match (&post.expect_exception, is_valid) {
+        (Some(exception), true) => return Outcome::Fail(format!("expected {exception}")),
+        (None, false) => return Outcome::Fail("transaction rejected".into()),
+        (Some(_), false) => logs::clear(),
+        (None, true) => {}
+    }
+
+    let logs_hash = logs::logs_hash();
+    if logs_hash != post.logs {
+        return Outcome::Fail(format!(
+            "logs hash 0x{} != 0x{}",
+            hex::encode(logs_hash),
+            hex::encode(post.logs)
+        ));
+    }
+
+    db.finalize();
+    let state_root = db.state_root();
+    if state_root != post.hash {
+        return Outcome::Fail(format!(
+            "state root 0x{} != 0x{}",
+            hex::encode(state_root),
+            hex::encode(post.hash)
+        ));
+    }
+
+    Outcome::Pass
+}
+
+async fn run_file(
+    path: &Path,
+    filter: Option<&str>,
+    skip_list: &SkipList,
+    summary: &mut [usize; 3],
+) -> Result<(), String> {
+    let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let tests: std::collections::BTreeMap<String, StateTest> =
+        serde_json::from_str(&json).map_err(|e| e.to_string())?;
+
+    for (name, test) in &tests {
+        for (fork, posts) in &test.post {
+            for post in posts {
+                let indexes = &post.indexes;
+                let id = format!(
+                    "{name}::{fork}[{},{},{}]",
+                    indexes.data, indexes.gas, indexes.value
+                );
+                if filter.map_or(false, |filter| !id.contains(filter)) {
+                    continue;
+                }
+
+                let outcome = match fork_spec(fork) {
+                    None => Outcome::Skip(format!("unsupported fork {fork}")),
+                    Some(_) if skip_list.contains(name) => Outcome::Skip("skip list".into()),
+                    Some(spec) => run(test, spec, post).await,
+                };
+
+                match outcome {
+                    Outcome::Pass => {
+                        summary[0] += 1;
+                        println!("PASS {id}");
+                    }
+                    Outcome::Fail(reason) => {
+                        summary[1] += 1;
+                        println!("FAIL {id}: {reason}");
+                    }
+                    Outcome::Skip(reason) => {
+                        summary[2] += 1;
+                        println!("SKIP {id}: {reason}");
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let Some(root) = std::env::var_os("STATE_TESTS_PATH").map(PathBuf::from) else {
+        println!("state tests: STATE_TESTS_PATH is not set, skipping");
+        return;
+    };
+    if !root.exists() {
+        println!("state tests: {} doesn't exist, skipping", root.display());
+        return;
+    }
+
+    let filter = std::env::var("STATE_TESTS_FILTER").ok();
+    let skip_list = SkipList::load();
+
+    let mut files = Vec::new();
+    collect_fixtures(&root, &mut files).expect("read fixtures directory");
+
+    logs::install();
+
+    let mut summary = [0_usize; 3];
+    for file in &files {
+        if let Err(e) = run_file(file, filter.as_deref(), &skip_list, &mut summary).await {
+            summary[1] += 1;
+            println!("FAIL {}: {e}", file.display());
+        }
+    }
+
+    let [passed, failed, skipped] = summary;
+    println!("state tests: {passed} passed, {failed} failed, {skipped} skipped");
+
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
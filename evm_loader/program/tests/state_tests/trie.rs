@@ -0,0 +1,101 @@
+//! Minimal Merkle Patricia Trie root calculation.
+//! Only the root hash is needed to compare against the fixture, so the trie is never stored.
+
+use rlp::RlpStream;
+use solana_program::keccak;
+
+/// Root of the secure trie: every key is hashed with keccak before insertion.
+pub fn secure_root<I>(entries: I) -> [u8; 32]
+where
+    I: IntoIterator<Item = (Vec<u8>, Vec<u8>)>,
+{
+    let mut items: Vec<(Vec<u8>, Vec<u8>)> = entries
+        .into_iter()
+        .map(|(key, value)| (to_nibbles(&keccak::hash(&key).to_bytes()), value))
+        .collect();
+    items.sort_by(|a, b| a.0.cmp(&b.0));
+
+    keccak::hash(&encode_node(&items, 0)).to_bytes()
+}
+
+fn to_nibbles(key: &[u8]) -> Vec<u8> {
+    key.iter().flat_map(|b| [b >> 4, b & 0x0F]).collect()
+}
+
+fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 0x20 } else { 0x00 };
+    let mut result = Vec::with_capacity(nibbles.len() / 2 + 1);
+
+    let rest = if nibbles.len() % 2 == 1 {
+        result.push(flag | 0x10 | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        result.push(flag);
+        nibbles
+    };
+
+    result.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    result
+}
+
+fn append_node_ref(stream: &mut RlpStream, node: &[u8]) {
+    if node.len() < 32 {
+        stream.append_raw(node, 1);
+    } else {
+        stream.append(&keccak::hash(node).to_bytes().as_slice());
+    }
+}
+
+/// RLP encoding of the node holding `items`, whose keys share the first `depth` nibbles.
+fn encode_node(items: &[(Vec<u8>, Vec<u8>)], depth: usize) -> Vec<u8> {
+    match items {
+        [] => rlp::NULL_RLP.to_vec(),
+        [(key, value)] => {
+            let mut stream = RlpStream::new_list(2);
+            stream.append(&hex_prefix(&key[depth..], true));
+            stream.append(value);
+            stream.out().to_vec()
+        }
+        _ => {
+            let first = &items[0].0;
+            let last = &items[items.len() - 1].0;
+            let prefix = first[depth..]
+                .iter()
+                .zip(&last[depth..])
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            if prefix > 0 {
+                let mut stream = RlpStream::new_list(2);
+                stream.append(&hex_prefix(&first[depth..depth + prefix], false));
+                append_node_ref(&mut stream, &encode_node(items, depth + prefix));
+                return stream.out().to_vec();
+            }
+
+            // A key which ends at this node keeps its value in the branch itself.
+            // Sorting puts it first.
+            let (value, mut rest) = match items.split_first() {
+                Some(((key, value), rest)) if key.len() == depth => (Some(value), rest),
+                _ => (None, items),
+            };
+
+            let mut stream = RlpStream::new_list(17);
+            for nibble in 0..16 {
+                let count = rest.iter().take_while(|(k, _)| k[depth] == nibble).count();
+                let (group, tail) = rest.split_at(count);
+                rest = tail;
+
+                if group.is_empty() {
+                    stream.append_empty_data();
+                } else {
+                    append_node_ref(&mut stream, &encode_node(group, depth + 1));
+                }
+            }
+            match value {
+                Some(value) => stream.append(value),
+                None => stream.append_empty_data(),
+            };
+            stream.out().to_vec()
+        }
+    }
+}
@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: MIT
+
+pragma solidity >= 0.7.0;
+pragma abicoder v2;
+
+interface CallSolana {
+    // Return the Solana account which signs instructions submitted by `execute` on behalf of the caller.
+    function getAuthority() external view returns(bytes32);
+
+    // Return the Solana account which signs instructions submitted by `executeWithSeed` on behalf of the caller.
+    // This is PDA[ACCOUNT_SEED_VERSION, b"ContractData", msg.sender, salt], the same account as `SPLToken.findAccount`.
+    function getAuthorityWithSeed(bytes32 salt) external view returns(bytes32);
+
+    // Submit Solana instruction signed by `getAuthority()`.
+    // `accounts` is a sequence of 34-byte account metas: pubkey, is_signer (0 or 1), is_writable (0 or 1).
+    // The authority is the only allowed signer. Accounts owned by Neon EVM and the operator can't be writable.
+    function execute(bytes32 program, bytes memory accounts, bytes memory data) external;
+
+    // Submit Solana instruction signed by `getAuthorityWithSeed(salt)`.
+    // The operator transfers `lamports` to the authority before the instruction, they are charged as a transaction fee.
+    function executeWithSeed(bytes32 salt, uint64 lamports, bytes32 program, bytes memory accounts, bytes memory data) external;
+}
@@ -0,0 +1,8 @@
+// SPDX-License-Identifier: MIT
+
+pragma solidity >= 0.7.0;
+pragma abicoder v2;
+
+interface INeonWithdraw {
+    function withdraw(bytes32 destination) external payable returns(bool);
+}
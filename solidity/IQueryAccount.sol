@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: MIT
+
+pragma solidity >= 0.7.0;
+pragma abicoder v2;
+
+interface IQueryAccount {
+    struct AccountInfo {
+        bytes32 pubkey;
+        uint64 lamports;
+        bytes32 owner;
+        bool executable;
+        uint64 rent_epoch;
+    }
+
+    function owner(bytes32 solana_address) external view returns(bytes32);
+    function lamports(bytes32 solana_address) external view returns(uint256);
+    function executable(bytes32 solana_address) external view returns(bool);
+    function rent_epoch(bytes32 solana_address) external view returns(uint256);
+    function info(bytes32 solana_address) external view returns(AccountInfo memory);
+    function length(bytes32 solana_address) external view returns(uint256);
+
+    // Returns the chunk of the account data as is, without ABI encoding
+    function data(bytes32 solana_address, uint64 offset, uint64 len) external view;
+
+    // Deprecated methods, the account is passed as uint256
+    // cache() does nothing
+    function cache(uint256 solana_address, uint64 offset, uint64 len) external view;
+    function owner(uint256 solana_address) external view returns(bytes32);
+    function lamports(uint256 solana_address) external view returns(uint256);
+    function executable(uint256 solana_address) external view returns(bool);
+    function rent_epoch(uint256 solana_address) external view returns(uint256);
+    function length(uint256 solana_address) external view returns(uint256);
+    function data(uint256 solana_address, uint64 offset, uint64 len) external view;
+}
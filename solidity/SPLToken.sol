@@ -4,7 +4,6 @@ pragma solidity >= 0.7.0;
 pragma abicoder v2;
 
 interface SPLToken {
-
     enum AccountState {
         Uninitialized,
         Initialized,
@@ -29,6 +28,26 @@ interface SPLToken {
         bytes32 mintAuthority;
     }
 
+    struct MintExtensions {
+        bytes32 program;
+        uint16 transferFeeBasisPoints;
+        uint64 maximumTransferFee;
+        uint64 withheldFeeAmount;
+        int16 interestRate;
+        bytes32 metadataAddress;
+        bytes32 permanentDelegate;
+        bytes32 transferHookProgram;
+        bool nonTransferable;
+    }
+
+    struct AccountExtensions {
+        bytes32 program;
+        uint64 withheldFeeAmount;
+        bool requireMemo;
+        bool cpiGuard;
+        bool immutableOwner;
+    }
+
     function findAccount(bytes32 salt) external pure returns(bytes32);
 
     function isSystemAccount(bytes32 account) external view returns(bool);
@@ -41,9 +60,22 @@ interface SPLToken {
     // Return default not initialized spl_token mint data if corresponded Solana account doesn't exist.
     function getMint(bytes32 account) external view returns(Mint memory);
 
+    // Return Token-2022 extensions of the mint. Fields of missing extensions are zero.
+    // `program` is the token program which owns the mint.
+    function getMintExtensions(bytes32 mint) external view returns(MintExtensions memory);
+
+    // Return Token-2022 extensions of the token account. Fields of missing extensions are zero.
+    // `program` is the token program which owns the account.
+    function getAccountExtensions(bytes32 account) external view returns(AccountExtensions memory);
+
     function initializeMint(bytes32 salt, uint8 decimals) external returns(bytes32);
     function initializeMint(bytes32 salt, uint8 decimals, bytes32 mint_authority, bytes32 freeze_authority) external returns(bytes32);
 
+    // Create a mint owned by the Token-2022 program.
+    // Token accounts for the mint are created with `initializeAccount`, all other methods work for both programs.
+    function initializeMint2022(bytes32 salt, uint8 decimals) external returns(bytes32);
+    function initializeMint2022(bytes32 salt, uint8 decimals, bytes32 mint_authority, bytes32 freeze_authority) external returns(bytes32);
+
     function initializeAccount(bytes32 salt, bytes32 mint) external returns(bytes32);
     function initializeAccount(bytes32 salt, bytes32 mint, bytes32 owner) external returns(bytes32);
 
@@ -63,4 +95,16 @@ interface SPLToken {
 
     function freeze(bytes32 mint, bytes32 account) external;
     function thaw(bytes32 mint, bytes32 account) external;
+
+    // Return associated token account of the Solana wallet `owner` for the `mint`.
+    // The account is derived for the token program which owns the mint.
+    function findAssociatedTokenAccount(bytes32 owner, bytes32 mint) external view returns(bytes32);
+
+    // Create associated token account of the Solana wallet `owner` for the `mint`, if it doesn't exist.
+    // Rent is paid by the operator and included in the transaction fee.
+    function createAssociatedTokenAccount(bytes32 owner, bytes32 mint) external returns(bytes32);
+
+    // Transfer funds to the associated token account of the Solana wallet `owner`.
+    // The associated token account is created for the mint of the `source` account if it doesn't exist.
+    function transferToAssociatedTokenAccount(bytes32 source, bytes32 owner, uint64 amount) external returns(bytes32);
 }
@@ -0,0 +1,23 @@
+// SPDX-License-Identifier: MIT
+
+pragma solidity >= 0.7.0;
+pragma abicoder v2;
+
+interface SystemProgram {
+    // Return PDA[ACCOUNT_SEED_VERSION, b"ContractData", msg.sender, salt], the same account as `SPLToken.findAccount`.
+    // All other methods operate on this account and sign with its seeds.
+    function findAccount(bytes32 salt) external pure returns(bytes32);
+
+    // Create rent exempt account of `space` bytes owned by `owner`.
+    // Rent is paid by the operator and included in the transaction fee.
+    function createAccount(bytes32 salt, uint64 space, bytes32 owner) external returns(bytes32);
+
+    // Transfer lamports from the account to `target`. The account must be owned by the system program and carry no data.
+    function transfer(bytes32 salt, bytes32 target, uint64 lamports) external;
+
+    // Allocate `space` bytes for the account owned by the system program
+    function allocate(bytes32 salt, uint64 space) external;
+
+    // Assign the account owned by the system program to `owner`
+    function assign(bytes32 salt, bytes32 owner) external;
+}